@@ -193,6 +193,489 @@ bench_collatz_sync!(
     collatz_dfs::CollatzDfs::new(black_box(START), SYNC_LIMIT, ALLOW_CIRCLES)
 );
 
+#[cfg(feature = "async")]
+mod large_node {
+    use par_dfs::r#async::{KeyedNode, Node, NodeStream};
+    use std::sync::Arc;
+
+    /// The size of the payload of a [`LargeNode`] in bytes.
+    const PAYLOAD_SIZE: usize = 16 * 1024;
+
+    /// A node of a binary tree that is expensive to clone.
+    #[derive(Clone, Debug)]
+    pub struct LargeNode {
+        id: u64,
+        payload: String,
+    }
+
+    impl LargeNode {
+        pub fn new(id: u64) -> Self {
+            Self {
+                id,
+                payload: "x".repeat(PAYLOAD_SIZE),
+            }
+        }
+
+        pub fn payload_len(&self) -> usize {
+            self.payload.len()
+        }
+    }
+
+    impl KeyedNode for LargeNode {
+        type Key = u64;
+
+        fn key(&self) -> Self::Key {
+            self.id
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Node for LargeNode {
+        type Error = std::convert::Infallible;
+
+        async fn children(
+            self: Arc<Self>,
+            _depth: usize,
+        ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+            let children = [2 * self.id + 1, 2 * self.id + 2]
+                .into_iter()
+                .map(|id| Ok(Self::new(id)));
+            Ok(Box::pin(futures::stream::iter(children)))
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+mod fat_node {
+    use par_dfs::r#async::{KeyedNode, Node, NodeStream};
+    use std::sync::Arc;
+
+    /// A node of a binary tree of 128 bytes, which is its own key.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct FatNode {
+        id: u64,
+        payload: [u8; 120],
+    }
+
+    impl FatNode {
+        pub fn new(id: u64) -> Self {
+            Self {
+                id,
+                payload: [0; 120],
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Node for FatNode {
+        type Error = std::convert::Infallible;
+
+        async fn children(
+            self: Arc<Self>,
+            _depth: usize,
+        ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+            let children = [2 * self.id + 1, 2 * self.id + 2].map(Self::new);
+            Ok(par_dfs::r#async::from_iter(children))
+        }
+    }
+
+    /// A [`FatNode`] that is identified by its 8 byte id.
+    #[derive(Clone, Debug)]
+    pub struct KeyedFatNode(FatNode);
+
+    impl KeyedFatNode {
+        pub fn new(id: u64) -> Self {
+            Self(FatNode::new(id))
+        }
+    }
+
+    impl KeyedNode for KeyedFatNode {
+        type Key = u64;
+
+        fn key(&self) -> Self::Key {
+            self.0.id
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Node for KeyedFatNode {
+        type Error = std::convert::Infallible;
+
+        async fn children(
+            self: Arc<Self>,
+            _depth: usize,
+        ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+            let children = [2 * self.0.id + 1, 2 * self.0.id + 2].map(Self::new);
+            Ok(par_dfs::r#async::from_iter(children))
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+const LARGE_LIMIT: Option<usize> = Some(10);
+
+#[cfg(feature = "async")]
+macro_rules! bench_large_async {
+    ($name:ident: $group:literal, $stream:ident) => {
+        /// Benchmarks for nodes that are expensive to clone.
+        ///
+        /// Owned nodes are cloned when they are still shared with their pending children,
+        /// whereas shared and borrowed nodes are never cloned.
+        fn $name(c: &mut criterion::Criterion) {
+            use large_node::LargeNode;
+            use par_dfs::r#async::{$stream, Traverse};
+
+            let mut group = c.benchmark_group($group);
+            configure_group(&mut group);
+
+            let runtime = tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .expect("build tokio runtime");
+
+            group.bench_function("owned", |b| {
+                b.to_async(&runtime).iter(|| async {
+                    use futures::StreamExt;
+                    $stream::<LargeNode>::new(LargeNode::new(black_box(0)), LARGE_LIMIT, false)
+                        .map(|node| node.map(|node| node.payload_len()))
+                        .count()
+                        .await;
+                })
+            });
+
+            group.bench_function("shared", |b| {
+                b.to_async(&runtime).iter(|| async {
+                    use futures::StreamExt;
+                    $stream::<LargeNode>::new(LargeNode::new(black_box(0)), LARGE_LIMIT, false)
+                        .shared()
+                        .map(|node| node.map(|node| node.payload_len()))
+                        .count()
+                        .await;
+                })
+            });
+
+            group.bench_function("for_each/owned", |b| {
+                b.to_async(&runtime).iter(|| async {
+                    use futures::StreamExt;
+                    $stream::<LargeNode>::new(LargeNode::new(black_box(0)), LARGE_LIMIT, false)
+                        .for_each(|node| {
+                            black_box(node.unwrap().payload_len());
+                            futures::future::ready(())
+                        })
+                        .await;
+                })
+            });
+
+            group.bench_function("for_each/borrowed", |b| {
+                b.to_async(&runtime).iter(|| async {
+                    $stream::<LargeNode>::new(LargeNode::new(black_box(0)), LARGE_LIMIT, false)
+                        .for_each_ref(|node, _depth| {
+                            black_box(node.payload_len());
+                        })
+                        .await
+                        .unwrap();
+                })
+            });
+        }
+    };
+}
+
+#[cfg(feature = "async")]
+bench_large_async!(bench_large_async_bfs: "large/async/bfs", Bfs);
+
+#[cfg(feature = "async")]
+bench_large_async!(bench_large_async_dfs: "large/async/dfs", Dfs);
+
+#[cfg(feature = "async")]
+criterion_group!(large_async, bench_large_async_bfs, bench_large_async_dfs);
+
+/// The latency of each [`Node::children`] call of the latency benchmarks.
+#[cfg(feature = "async")]
+const LATENCY: std::time::Duration = std::time::Duration::from_millis(1);
+
+/// Benchmarks a depth-first traversal of nodes whose children are slow to produce,
+/// comparing the serial traversal with expanding nodes ahead of time.
+#[cfg(feature = "async")]
+fn bench_latency_async_dfs(c: &mut criterion::Criterion) {
+    use par_dfs::r#async::{from_fn, Dfs, FromFn, NodeStream};
+
+    let mut group = c.benchmark_group("latency/async/dfs");
+    configure_group(&mut group);
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("build tokio runtime");
+
+    for lookahead in [0, 2, 8] {
+        group.bench_function(format!("lookahead/{lookahead}"), |b| {
+            b.to_async(&runtime).iter(|| async {
+                let root = from_fn(black_box(0usize), |_, _| async move {
+                    tokio::time::sleep(LATENCY).await;
+                    let stream: NodeStream<_, std::convert::Infallible> =
+                        Box::pin(futures::stream::iter((0..4).map(Ok)));
+                    Ok(stream)
+                });
+                Dfs::<FromFn<_, _>>::new(root, Some(4), true)
+                    .lookahead(lookahead)
+                    .count()
+                    .await
+                    .unwrap();
+            })
+        });
+    }
+}
+
+#[cfg(feature = "async")]
+criterion_group!(latency_async, bench_latency_async_dfs);
+
+/// Counts the heap allocations of the benchmark process.
+#[cfg(feature = "async")]
+mod allocations {
+    use criterion::measurement::{Measurement, ValueFormatter};
+    use criterion::Throughput;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+    static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+    /// The system allocator, counting the number of allocations and allocated bytes.
+    pub struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+        }
+    }
+
+    /// Measures the number of allocations instead of the wall-clock time.
+    pub struct Allocations;
+
+    impl Measurement for Allocations {
+        type Intermediate = u64;
+        type Value = u64;
+
+        fn start(&self) -> Self::Intermediate {
+            ALLOCATIONS.load(Ordering::Relaxed)
+        }
+
+        fn end(&self, start: Self::Intermediate) -> Self::Value {
+            ALLOCATIONS.load(Ordering::Relaxed) - start
+        }
+
+        fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+            v1 + v2
+        }
+
+        fn zero(&self) -> Self::Value {
+            0
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        fn to_f64(&self, value: &Self::Value) -> f64 {
+            *value as f64
+        }
+
+        fn formatter(&self) -> &dyn ValueFormatter {
+            &AllocationsFormatter
+        }
+    }
+
+    /// Measures the number of allocated bytes instead of the wall-clock time.
+    ///
+    /// Reallocations are not counted, hence this is a lower bound.
+    pub struct AllocatedBytes;
+
+    impl Measurement for AllocatedBytes {
+        type Intermediate = u64;
+        type Value = u64;
+
+        fn start(&self) -> Self::Intermediate {
+            ALLOCATED_BYTES.load(Ordering::Relaxed)
+        }
+
+        fn end(&self, start: Self::Intermediate) -> Self::Value {
+            ALLOCATED_BYTES.load(Ordering::Relaxed) - start
+        }
+
+        fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+            v1 + v2
+        }
+
+        fn zero(&self) -> Self::Value {
+            0
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        fn to_f64(&self, value: &Self::Value) -> f64 {
+            *value as f64
+        }
+
+        fn formatter(&self) -> &dyn ValueFormatter {
+            &AllocatedBytesFormatter
+        }
+    }
+
+    struct AllocatedBytesFormatter;
+
+    impl ValueFormatter for AllocatedBytesFormatter {
+        fn scale_values(&self, _typical_value: f64, values: &mut [f64]) -> &'static str {
+            for value in values {
+                *value /= 1024.0 * 1024.0;
+            }
+            "MiB"
+        }
+
+        fn scale_throughputs(
+            &self,
+            _typical_value: f64,
+            _throughput: &Throughput,
+            values: &mut [f64],
+        ) -> &'static str {
+            self.scale_values(0.0, values)
+        }
+
+        fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+            "bytes"
+        }
+    }
+
+    struct AllocationsFormatter;
+
+    impl ValueFormatter for AllocationsFormatter {
+        fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+            "allocs"
+        }
+
+        fn scale_throughputs(
+            &self,
+            _typical_value: f64,
+            _throughput: &Throughput,
+            _values: &mut [f64],
+        ) -> &'static str {
+            "allocs"
+        }
+
+        fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+            "allocs"
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[global_allocator]
+static GLOBAL: allocations::CountingAllocator = allocations::CountingAllocator;
+
+#[cfg(feature = "async")]
+macro_rules! bench_allocations_async {
+    ($name:ident: $group:literal, $stream:ident) => {
+        /// Benchmarks the number of allocations of a traversal of a wide tree.
+        fn $name(c: &mut criterion::Criterion<allocations::Allocations>) {
+            use par_dfs::r#async::$stream;
+
+            let mut group = c.benchmark_group($group);
+            group.sample_size(10);
+
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .expect("build tokio runtime");
+
+            group.bench_function("wide", |b| {
+                b.to_async(&runtime).iter(|| async {
+                    // the children of each node are the numbers up to its depth times ten
+                    let root =
+                        par_dfs::r#async::from_fn(black_box(0usize), |_, depth| async move {
+                            let children = (0..depth * 10).map(Ok);
+                            let stream: par_dfs::r#async::NodeStream<_, std::convert::Infallible> =
+                                Box::pin(futures::stream::iter(children));
+                            Ok(stream)
+                        });
+                    $stream::<par_dfs::r#async::FromFn<_, _>>::new(root, Some(3), true)
+                        .count()
+                        .await
+                        .unwrap();
+                })
+            });
+        }
+    };
+}
+
+#[cfg(feature = "async")]
+bench_allocations_async!(bench_allocations_async_bfs: "allocations/async/bfs", Bfs);
+
+#[cfg(feature = "async")]
+bench_allocations_async!(bench_allocations_async_dfs: "allocations/async/dfs", Dfs);
+
+#[cfg(feature = "async")]
+criterion_group!(
+    name = allocations_async;
+    config = criterion::Criterion::default().with_measurement(allocations::Allocations);
+    targets = bench_allocations_async_bfs, bench_allocations_async_dfs
+);
+
+/// The maximum depth of the visited key benchmarks, which yields about a million nodes.
+#[cfg(feature = "async")]
+const VISITED_LIMIT: Option<usize> = Some(19);
+
+#[cfg(feature = "async")]
+macro_rules! bench_visited_keys_async {
+    ($name:ident: $group:literal, $measurement:ty) => {
+        /// Benchmarks tracking 128 byte nodes as visited by themselves or by an 8 byte key.
+        fn $name(c: &mut criterion::Criterion<$measurement>) {
+            use fat_node::{FatNode, KeyedFatNode};
+            use par_dfs::r#async::Bfs;
+
+            let mut group = c.benchmark_group($group);
+            group.sample_size(10);
+
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .expect("build tokio runtime");
+
+            group.bench_function("node", |b| {
+                b.to_async(&runtime).iter(|| async {
+                    Bfs::<FatNode>::new(FatNode::new(black_box(0)), VISITED_LIMIT, false)
+                        .count()
+                        .await
+                        .unwrap();
+                })
+            });
+
+            group.bench_function("key", |b| {
+                b.to_async(&runtime).iter(|| async {
+                    Bfs::<KeyedFatNode>::new(KeyedFatNode::new(black_box(0)), VISITED_LIMIT, false)
+                        .count()
+                        .await
+                        .unwrap();
+                })
+            });
+        }
+    };
+}
+
+#[cfg(feature = "async")]
+bench_visited_keys_async!(bench_visited_keys_time: "visited/async/time", criterion::measurement::WallTime);
+
+#[cfg(feature = "async")]
+bench_visited_keys_async!(bench_visited_keys_bytes: "visited/async/bytes", allocations::AllocatedBytes);
+
+#[cfg(feature = "async")]
+criterion_group!(visited_async, bench_visited_keys_time);
+
+#[cfg(feature = "async")]
+criterion_group!(
+    name = visited_bytes_async;
+    config = criterion::Criterion::default().with_measurement(allocations::AllocatedBytes);
+    targets = bench_visited_keys_bytes
+);
+
 #[cfg(feature = "async")]
 criterion_group!(
     collatz_async,
@@ -215,6 +698,16 @@ fn main() {
     collatz_sync();
     #[cfg(feature = "async")]
     collatz_async();
+    #[cfg(feature = "async")]
+    large_async();
+    #[cfg(feature = "async")]
+    latency_async();
+    #[cfg(feature = "async")]
+    allocations_async();
+    #[cfg(feature = "async")]
+    visited_async();
+    #[cfg(feature = "async")]
+    visited_bytes_async();
 
     criterion::Criterion::default()
         .configure_from_args()
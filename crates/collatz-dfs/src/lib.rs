@@ -118,7 +118,10 @@ impl CollatzDfs {
     pub fn new<D: Into<Option<usize>>>(start: u32, max_depth: D, allow_circles: bool) -> Self {
         Self {
             max_depth: max_depth.into(),
-            queue: VecDeque::from_iter([(0, Ok(start))]),
+            queue: CollatzNode(start)
+                .collatz_children()
+                .map(|child| (1, child.map(u32::from)))
+                .collect(),
             visited: HashSet::from_iter([start]),
             allow_circles,
         }
@@ -0,0 +1,132 @@
+//! Derive macro for the async [`Node`] trait of [`par_dfs`].
+//!
+//! [`Node`]: https://docs.rs/par-dfs/latest/par_dfs/async/trait.Node.html
+//! [`par_dfs`]: https://docs.rs/par-dfs
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+/// Derives the async `Node` trait, yielding the fields marked with `#[children]`.
+///
+/// See `par_dfs::async::Node` for details.
+#[proc_macro_derive(Node, attributes(children))]
+pub fn derive_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Returns `true` if the field is marked with `#[children]`.
+fn is_children(field: &syn::Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("children"))
+}
+
+/// Collects the children of the `fields`, which are bound to the `bindings`.
+fn collect_fields<'a>(
+    fields: &'a Fields,
+    bindings: impl Iterator<Item = TokenStream> + 'a,
+) -> impl Iterator<Item = TokenStream> + 'a {
+    fields
+        .iter()
+        .zip(bindings)
+        .filter(|(field, _)| is_children(field))
+        .map(|(_, binding)| {
+            quote! {
+                ::par_dfs::r#async::__private::Children::collect_children(#binding, &mut children);
+            }
+        })
+}
+
+/// Returns the pattern binding all `fields` of a variant, and the bindings.
+fn bind_fields(fields: &Fields) -> (TokenStream, Vec<TokenStream>) {
+    match fields {
+        Fields::Named(named) => {
+            let idents: Vec<_> = named
+                .named
+                .iter()
+                .map(|field| field.ident.clone().expect("named field"))
+                .collect();
+            (
+                quote! { { #(#idents),* } },
+                idents.iter().map(|ident| quote! { #ident }).collect(),
+            )
+        }
+        Fields::Unnamed(unnamed) => {
+            let idents: Vec<_> = (0..unnamed.unnamed.len())
+                .map(|index| format_ident!("__field{}", index))
+                .collect();
+            (
+                quote! { ( #(#idents),* ) },
+                idents.iter().map(|ident| quote! { #ident }).collect(),
+            )
+        }
+        Fields::Unit => (TokenStream::new(), vec![]),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let collect = match &input.data {
+        Data::Struct(data) => {
+            let bindings = data.fields.iter().enumerate().map(|(index, field)| {
+                if let Some(ident) = &field.ident {
+                    quote! { &self.#ident }
+                } else {
+                    let index = syn::Index::from(index);
+                    quote! { &self.#index }
+                }
+            });
+            let collect = collect_fields(&data.fields, bindings);
+            quote! { #(#collect)* }
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let (pattern, bindings) = bind_fields(&variant.fields);
+                let collect = collect_fields(&variant.fields, bindings.into_iter());
+                quote! {
+                    #[allow(unused_variables)]
+                    Self::#ident #pattern => { #(#collect)* }
+                }
+            });
+            quote! {
+                match &*self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "Node can only be derived for structs and enums",
+            ));
+        }
+    };
+
+    Ok(quote! {
+        #[::par_dfs::r#async::__private::async_trait]
+        impl #impl_generics ::par_dfs::r#async::Node for #name #ty_generics #where_clause {
+            type Error = ::std::convert::Infallible;
+
+            async fn children(
+                self: ::std::sync::Arc<Self>,
+                _depth: usize,
+            ) -> ::std::result::Result<
+                ::par_dfs::r#async::NodeStream<Self, Self::Error>,
+                Self::Error,
+            > {
+                #[allow(unused_mut)]
+                let mut children = ::std::vec::Vec::new();
+                #collect
+                ::std::result::Result::Ok(::par_dfs::r#async::from_iter(children))
+            }
+        }
+    })
+}
@@ -0,0 +1,92 @@
+use futures::StreamExt;
+use par_dfs::r#async::{Bfs, Node};
+
+#[derive(Node, Clone, Debug, PartialEq, Eq, Hash)]
+struct Tree {
+    value: usize,
+    #[children]
+    left: Option<Box<Tree>>,
+    #[children]
+    right: Option<Box<Tree>>,
+}
+
+impl Tree {
+    fn leaf(value: usize) -> Self {
+        Self {
+            value,
+            left: None,
+            right: None,
+        }
+    }
+
+    fn branch(value: usize, left: Tree, right: Option<Tree>) -> Self {
+        Self {
+            value,
+            left: Some(Box::new(left)),
+            right: right.map(Box::new),
+        }
+    }
+}
+
+#[derive(Node, Clone, Debug, PartialEq, Eq, Hash)]
+enum Expr {
+    Literal(usize),
+    Negate(#[children] Box<Expr>),
+    Sum {
+        #[children]
+        terms: Vec<Expr>,
+    },
+}
+
+#[derive(Node, Clone, Debug, PartialEq, Eq, Hash)]
+struct Dir(&'static str, #[children] Vec<Dir>);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_derive_struct() -> anyhow::Result<()> {
+    let tree = Tree::branch(1, Tree::branch(2, Tree::leaf(4), None), Some(Tree::leaf(3)));
+    let values: Vec<_> = Bfs::<Tree>::new(tree, None, true)
+        .map(|node| node.map(|node| node.value))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()?;
+    similar_asserts::assert_eq!(values, vec![2, 3, 4]);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_derive_enum() -> anyhow::Result<()> {
+    let expr = Expr::Sum {
+        terms: vec![Expr::Literal(1), Expr::Negate(Box::new(Expr::Literal(2)))],
+    };
+    let nodes: Vec<_> = Bfs::<Expr>::new(expr, None, true)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()?;
+    similar_asserts::assert_eq!(
+        nodes,
+        vec![
+            Expr::Literal(1),
+            Expr::Negate(Box::new(Expr::Literal(2))),
+            Expr::Literal(2),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_derive_tuple_struct() -> anyhow::Result<()> {
+    let root = Dir(
+        "/",
+        vec![Dir("a", vec![Dir("b", vec![])]), Dir("c", vec![])],
+    );
+    let names: Vec<_> = Bfs::<Dir>::new(root, None, true)
+        .map(|node| node.map(|node| node.0))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()?;
+    similar_asserts::assert_eq!(names, vec!["a", "c", "b"]);
+    Ok(())
+}
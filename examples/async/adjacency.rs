@@ -0,0 +1,27 @@
+#[cfg(not(feature = "async"))]
+fn main() {
+    panic!("Feature \"async\" must be enabled for this example");
+}
+
+#[cfg(feature = "async")]
+#[tokio::main(flavor = "multi_thread")]
+async fn main() -> anyhow::Result<()> {
+    use futures::StreamExt;
+    use par_dfs::r#async::{AdjacencyMap, AdjacencyNode, Bfs, Traverse};
+    use std::sync::Arc;
+
+    let graph: AdjacencyMap<&str> = AdjacencyMap::from([
+        ("berlin", vec!["hamburg", "leipzig"]),
+        ("hamburg", vec!["bremen", "berlin"]),
+        ("leipzig", vec!["dresden", "munich"]),
+        ("munich", vec!["stuttgart"]),
+    ]);
+    let root = AdjacencyNode::new(Arc::new(graph), "berlin");
+    let mut bfs = Bfs::<AdjacencyNode<&str>>::new(root, None, false).with_depth();
+
+    while let Some(node) = bfs.next().await {
+        let (depth, node) = node?;
+        println!("{}{}", "  ".repeat(depth), node.key());
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+#[cfg(not(all(feature = "async", feature = "trace")))]
+fn main() {
+    panic!("Features \"async\" and \"trace\" must be enabled for this example");
+}
+
+#[cfg(all(feature = "async", feature = "trace"))]
+#[tokio::main(flavor = "multi_thread")]
+async fn main() -> anyhow::Result<()> {
+    use par_dfs::r#async::{from_fn, Bfs, FromFn, NodeStream};
+    use std::time::Duration;
+    use tracing_subscriber::fmt::format::FmtSpan;
+
+    // print the busy and idle time of each `children` span when it closes,
+    // together with the depth of the children and the index of the call
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_span_events(FmtSpan::CLOSE)
+        .init();
+
+    // the children of deeper nodes take longer to compute
+    let root = from_fn(1u32, |value, depth| async move {
+        let delay = u64::try_from(depth).unwrap_or(u64::MAX);
+        tokio::time::sleep(Duration::from_millis(10 * delay)).await;
+        let children = futures::stream::iter([Ok(2 * value), Ok(2 * value + 1)]);
+        Ok::<NodeStream<u32, std::convert::Infallible>, _>(Box::pin(children))
+    });
+
+    let bfs: Bfs<FromFn<u32, _>> = Bfs::new(root, 3, false);
+    println!("visited {} nodes", bfs.count().await?);
+    Ok(())
+}
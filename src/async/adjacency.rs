@@ -0,0 +1,225 @@
+use super::{KeyedNode, Node, NodeStream, Traverse};
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// An adjacency list mapping each key to the keys of its children.
+pub type AdjacencyMap<T> = HashMap<T, Vec<T>>;
+
+/// A [`Node`] of a graph given as an [`AdjacencyMap`].
+///
+/// The children of a node are the entries of its key in the shared map,
+/// in the order they are listed.
+/// Keys without an entry have no children.
+///
+/// Nodes are compared and hashed by their key only.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{AdjacencyNode, Bfs};
+/// use std::collections::HashMap;
+/// use std::sync::Arc;
+///
+/// let graph = Arc::new(HashMap::from([
+///     ("a", vec!["b", "c"]),
+///     ("b", vec!["d"]),
+///     ("c", vec!["d", "a"]),
+/// ]));
+///
+/// let result = tokio_test::block_on(async {
+///     let root = AdjacencyNode::new(graph, "a");
+///     let bfs = Bfs::<AdjacencyNode<&str>>::new(root, None, false);
+///     let output = bfs
+///         .collect::<Vec<_>>()
+///         .await
+///         .into_iter()
+///         .collect::<Result<Vec<_>, _>>()
+///         .unwrap();
+///     output.into_iter().map(|node| *node.key()).collect::<Vec<_>>()
+/// });
+/// assert_eq!(result, ["b", "c", "d"]);
+/// ```
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`AdjacencyMap`]: type@crate::async::AdjacencyMap
+pub struct AdjacencyNode<T> {
+    graph: Arc<AdjacencyMap<T>>,
+    key: T,
+}
+
+impl<T> AdjacencyNode<T> {
+    #[inline]
+    #[must_use]
+    /// Creates the node of `key` in the `graph`.
+    pub fn new(graph: Arc<AdjacencyMap<T>>, key: T) -> Self {
+        Self { graph, key }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the key of the node.
+    pub fn key(&self) -> &T {
+        &self.key
+    }
+
+    #[inline]
+    #[must_use]
+    /// Consumes the node, returning its key.
+    pub fn into_key(self) -> T {
+        self.key
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the graph of the node.
+    pub fn graph(&self) -> &Arc<AdjacencyMap<T>> {
+        &self.graph
+    }
+}
+
+impl<T> Clone for AdjacencyNode<T>
+where
+    T: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            graph: Arc::clone(&self.graph),
+            key: self.key.clone(),
+        }
+    }
+}
+
+impl<T> PartialEq for AdjacencyNode<T>
+where
+    T: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<T> Eq for AdjacencyNode<T> where T: Eq {}
+
+impl<T> Hash for AdjacencyNode<T>
+where
+    T: Hash,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl<T> std::fmt::Debug for AdjacencyNode<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("AdjacencyNode").field(&self.key).finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> Node for AdjacencyNode<T>
+where
+    T: Hash + Eq + Clone + std::fmt::Debug + Send + Sync + 'static,
+{
+    type Error = std::convert::Infallible;
+
+    async fn children(
+        self: Arc<Self>,
+        _depth: usize,
+    ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+        let children: Vec<_> = self
+            .graph
+            .get(&self.key)
+            .into_iter()
+            .flatten()
+            .map(|key| Ok(Self::new(Arc::clone(&self.graph), key.clone())))
+            .collect();
+        Ok(Box::pin(futures::stream::iter(children)))
+    }
+}
+
+/// Consumes the `traversal`, collecting the yielded children of each node.
+///
+/// Nodes without yielded children are omitted.
+pub(super) async fn collect_adjacency<T>(
+    traversal: T,
+) -> Result<HashMap<<T::Node as KeyedNode>::Key, Vec<T::Node>>, <T::Node as Node>::Error>
+where
+    T: Traverse,
+    T::Node: Clone,
+{
+    let mut adjacency: HashMap<_, Vec<_>> = HashMap::new();
+    let mut traversal = std::pin::pin!(traversal);
+    while let Some(path) =
+        futures::future::poll_fn(|cx| traversal.as_mut().poll_next_path(cx)).await
+    {
+        let path = path?;
+        if let Some(parent) = path.parent() {
+            adjacency
+                .entry(parent.node().key())
+                .or_default()
+                .push(T::Node::clone(path.node()));
+        }
+    }
+    Ok(adjacency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdjacencyMap, AdjacencyNode};
+    use crate::r#async::{Bfs, Dfs, Traverse};
+    use anyhow::Result;
+    use futures::StreamExt;
+    use std::sync::Arc;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_collect_adjacency() -> Result<()> {
+        let graph: AdjacencyMap<u32> =
+            AdjacencyMap::from([(0, vec![1, 2]), (1, vec![3]), (2, vec![3]), (3, vec![4])]);
+        let root = AdjacencyNode::new(Arc::new(graph), 0);
+        let bfs = Bfs::<AdjacencyNode<u32>>::new(root, 2, true);
+        let adjacency: AdjacencyMap<u32> = bfs
+            .collect_adjacency()
+            .await?
+            .into_iter()
+            .map(|(node, children)| {
+                let children = children.into_iter().map(AdjacencyNode::into_key).collect();
+                (node.into_key(), children)
+            })
+            .collect();
+        // the children of 3 are beyond the maximum depth
+        similar_asserts::assert_eq!(
+            adjacency,
+            AdjacencyMap::from([(0, vec![1, 2]), (1, vec![3]), (2, vec![3])])
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_adjacency_node_dfs() -> Result<()> {
+        let graph: AdjacencyMap<u32> =
+            AdjacencyMap::from([(0, vec![1, 2]), (1, vec![3]), (2, vec![3, 0]), (3, vec![])]);
+        let root = AdjacencyNode::new(Arc::new(graph), 0);
+        let dfs = Dfs::<AdjacencyNode<u32>>::new(root, None, false);
+        let nodes = dfs
+            .with_depth()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let nodes: Vec<_> = nodes
+            .into_iter()
+            .map(|(depth, node)| (depth, *node.key()))
+            .collect();
+        // the cycle back to the root is not followed
+        similar_asserts::assert_eq!(nodes, [(1, 1), (2, 3), (1, 2)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use super::{Node, Path, Predicate, Stats, VisitedSet};
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// The rules deciding which of the nodes reached by a traversal are yielded and expanded.
+///
+/// Shared by [`Bfs`] and [`Dfs`], such that both apply the same rules,
+/// regardless of the order in which they reach the nodes.
+///
+/// [`Bfs`]: struct@crate::async::Bfs
+/// [`Dfs`]: struct@crate::async::Dfs
+pub(super) struct Admission<N> {
+    /// Whether visited nodes are not tracked.
+    pub allow_circles: bool,
+    /// Whether visited nodes are yielded again but not expanded again.
+    pub expand_once: bool,
+    pub min_depth: usize,
+    pub max_nodes: Option<usize>,
+    pub prune: Option<Predicate<N>>,
+    pub filter: Option<Predicate<N>>,
+}
+
+impl<N> Default for Admission<N> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            allow_circles: false,
+            expand_once: false,
+            min_depth: 0,
+            max_nodes: None,
+            prune: None,
+            filter: None,
+        }
+    }
+}
+
+/// Whether the children of an admitted node are requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Expand {
+    Yes,
+    No,
+    /// The children are not requested because of the maximum depth.
+    Truncated,
+}
+
+/// How a node reached by a traversal is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Admitted {
+    /// The node is neither yielded nor expanded,
+    /// because it was visited before or is rejected by the filter.
+    Skip,
+    /// The node is not yielded, because it is above the minimum depth.
+    Hide { expand: Expand },
+    /// The node is yielded.
+    Emit {
+        expand: Expand,
+        /// Whether the node is the last node to be yielded.
+        last: bool,
+    },
+}
+
+impl Admitted {
+    /// Returns whether the children of the node are requested.
+    #[inline]
+    pub fn expand(self) -> Expand {
+        match self {
+            Self::Skip => Expand::No,
+            Self::Hide { expand } | Self::Emit { expand, .. } => expand,
+        }
+    }
+}
+
+impl<N> Admission<N>
+where
+    N: Node,
+{
+    /// Returns `true` if visited nodes are tracked.
+    #[inline]
+    pub fn tracks_visited(&self) -> bool {
+        !self.allow_circles || self.expand_once
+    }
+
+    /// Decides how the last node of `path` is handled, marking it as visited.
+    ///
+    /// Terminal nodes are never marked as visited nor expanded.
+    #[inline]
+    pub fn admit<V>(
+        &self,
+        path: &Path<N>,
+        visited: &mut V,
+        nodes_emitted: usize,
+        max_depth: Option<usize>,
+    ) -> Admitted
+    where
+        V: VisitedSet<N>,
+    {
+        let depth = path.depth();
+        let terminal = path.node().is_terminal();
+        let tracked = self.tracks_visited() && !path.node().allow_revisit() && !terminal;
+        let first_visit = !tracked || visited.insert(path.node());
+        // filtered nodes are neither yielded nor expanded
+        let keep = (first_visit || self.expand_once)
+            && self
+                .filter
+                .as_ref()
+                .is_none_or(|filter| filter(path.node(), depth));
+        if !keep {
+            return Admitted::Skip;
+        }
+        // nodes above the minimum depth are expanded but not yielded
+        let emit = depth >= self.min_depth;
+        let last = emit
+            && self
+                .max_nodes
+                .is_some_and(|max_nodes| nodes_emitted + 1 >= max_nodes);
+        let pruned = self
+            .prune
+            .as_ref()
+            .is_some_and(|prune| prune(path.node(), depth));
+        let expand = if !first_visit || last || pruned || terminal {
+            Expand::No
+        } else if max_depth.is_none_or(|max_depth| depth < max_depth) {
+            Expand::Yes
+        } else {
+            Expand::Truncated
+        };
+        if emit {
+            Admitted::Emit { expand, last }
+        } else {
+            Admitted::Hide { expand }
+        }
+    }
+
+    /// Pops the next root to be yielded and records it as emitted.
+    ///
+    /// Once the maximum number of nodes is reached,
+    /// the remaining roots and the deferred expansions are dropped.
+    #[inline]
+    pub fn pop_root(
+        &self,
+        roots: &mut VecDeque<Arc<Path<N>>>,
+        deferred: &mut VecDeque<Arc<Path<N>>>,
+        stats: &mut Stats,
+    ) -> Option<Path<N>> {
+        let root = roots.pop_front()?;
+        stats.record_emitted(root.depth());
+        if self
+            .max_nodes
+            .is_some_and(|max_nodes| stats.nodes_emitted >= max_nodes)
+        {
+            // this is the last node, drop all pending roots
+            roots.clear();
+            deferred.clear();
+        }
+        Some(Path::clone(&root))
+    }
+}
+
+/// Pops the next deferred node to be expanded, if it is `ready` to be expanded.
+///
+/// The maximum depth may have been lowered since the expansion was deferred,
+/// hence nodes at or beyond `max_depth` are dropped and the traversal is marked as `truncated`.
+#[inline]
+pub(super) fn pop_deferred<N, F>(
+    deferred: &mut VecDeque<Arc<Path<N>>>,
+    max_depth: Option<usize>,
+    truncated: &mut bool,
+    ready: F,
+) -> Option<Arc<Path<N>>>
+where
+    F: FnOnce(&Path<N>) -> bool,
+{
+    while let Some(front) = deferred.front() {
+        if max_depth.is_none_or(|max_depth| front.depth() < max_depth) {
+            return if ready(front) {
+                deferred.pop_front()
+            } else {
+                None
+            };
+        }
+        deferred.pop_front();
+        *truncated = true;
+    }
+    None
+}
@@ -0,0 +1,457 @@
+use super::{
+    into_node_stream, pop_deferred, ChildStreamFut, Frame, FrontierQueue, KeyedNode, Node, Path,
+    State, Traversal, TraversalError, VisitedSet,
+};
+
+use futures::FutureExt;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Compares two nodes of the frontier, where the greater node is yielded first.
+type Comparator<N> = Arc<dyn Fn(&N, &N) -> Ordering + Send + Sync>;
+
+/// A node in the frontier of a [`BestFirst`] traversal.
+///
+/// [`BestFirst`]: struct@crate::async::BestFirst
+struct Candidate<N> {
+    path: Path<N>,
+    /// The insertion order, used to break ties between equal nodes.
+    seq: usize,
+    cmp: Comparator<N>,
+}
+
+impl<N> PartialEq for Candidate<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N> Eq for Candidate<N> {}
+
+impl<N> PartialOrd for Candidate<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Candidate<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.cmp)(self.path.node(), other.path.node())
+            // equal nodes are yielded in the order they were discovered
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Asynchronous best-first stream for types implementing the [`Node`] trait.
+///
+/// Yields the greatest node of the frontier first, as given by its [`Ord`] implementation.
+/// The children of each yielded node are added to the frontier before the next node is yielded,
+/// hence the nodes are yielded in priority order rather than in the order they were discovered.
+/// Equal nodes are yielded in the order they were discovered.
+/// Use [`with_comparator`] to order the frontier by a custom comparator instead.
+///
+/// Since the frontier must be complete before the next node can be chosen,
+/// only a single [`Node::children`] call is in flight at a time.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{Node, BestFirst, NodeStream};
+///
+/// #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
+/// struct Score(u32);
+///
+/// #[async_trait::async_trait]
+/// impl Node for Score {
+///     type Error = std::convert::Infallible;
+///
+///     async fn children(
+///         self: std::sync::Arc<Self>,
+///         _depth: usize
+///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+///         let nodes = [self.0 * 2, self.0 * 3]
+///             .into_iter()
+///             .map(Self)
+///             .map(Result::Ok);
+///         let stream = futures::stream::iter(nodes);
+///         Ok(Box::pin(stream.boxed()))
+///     }
+/// }
+///
+/// let result = tokio_test::block_on(async {
+///     let best_first = BestFirst::<Score>::new(Score(1), 2, false);
+///     let output = best_first
+///         .collect::<Vec<_>>()
+///         .await
+///         .into_iter()
+///         .collect::<Result<Vec<_>, _>>()
+///         .unwrap();
+///     output.into_iter().map(|s| s.0).collect::<Vec<_>>()
+/// });
+/// assert_eq!(result, [3, 9, 6, 2, 4]);
+/// ```
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`Node::children`]: fn@crate::async::Node::children
+/// [`Ord`]: trait@std::cmp::Ord
+/// [`with_comparator`]: fn@crate::async::BestFirst::with_comparator
+#[allow(clippy::module_name_repetitions)]
+pub struct BestFirst<N, V = HashSet<<N as KeyedNode>::Key>>
+where
+    N: Node,
+{
+    pub(super) traversal: Traversal<N, V, Priority<N, N::Error>>,
+}
+
+impl<N> BestFirst<N>
+where
+    N: Node + Ord + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    #[inline]
+    /// Creates a new [`BestFirst`] stream.
+    ///
+    /// The best-first search will be performed from the `root` node up to depth `max_depth`.
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    ///
+    /// [`BestFirst`]: struct@crate::async::BestFirst
+    pub fn new<R, D>(root: R, max_depth: D, allow_circles: bool) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        Self::with_visited(root, max_depth, allow_circles, HashSet::new())
+    }
+}
+
+impl<N> BestFirst<N>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    #[inline]
+    /// Creates a new [`BestFirst`] stream that orders the frontier by `cmp`.
+    ///
+    /// The greatest node of the frontier according to `cmp` is yielded first.
+    /// Nodes that compare equal are yielded in the order they were discovered,
+    /// so the output is reproducible for deterministic child streams.
+    ///
+    /// The best-first search will be performed from the `root` node up to depth `max_depth`.
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    ///
+    /// [`BestFirst`]: struct@crate::async::BestFirst
+    pub fn with_comparator<R, D, C>(root: R, max_depth: D, allow_circles: bool, cmp: C) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+        C: Fn(&N, &N) -> Ordering + Send + Sync + 'static,
+    {
+        Self::from_parts(
+            root.into(),
+            max_depth.into(),
+            allow_circles,
+            HashSet::new(),
+            Arc::new(cmp),
+        )
+    }
+}
+
+impl<N, V> BestFirst<N, V>
+where
+    N: Node + Ord + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+    V: VisitedSet<N>,
+{
+    #[inline]
+    /// Creates a new [`BestFirst`] stream with the given set of `visited` nodes.
+    ///
+    /// The best-first search will be performed from the `root` node up to depth `max_depth`.
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    /// Otherwise, the visited nodes are tracked in the given [`VisitedSet`].
+    ///
+    /// [`VisitedSet`]: trait@crate::async::VisitedSet
+    /// [`BestFirst`]: struct@crate::async::BestFirst
+    pub fn with_visited<R, D>(root: R, max_depth: D, allow_circles: bool, visited: V) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        Self::from_parts(
+            root.into(),
+            max_depth.into(),
+            allow_circles,
+            visited,
+            Arc::new(N::cmp),
+        )
+    }
+}
+
+impl<N, V> BestFirst<N, V>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+    V: VisitedSet<N>,
+{
+    fn from_parts(
+        root: N,
+        max_depth: Option<usize>,
+        allow_circles: bool,
+        visited: V,
+        cmp: Comparator<N>,
+    ) -> Self {
+        let traversal = Traversal::from_roots(
+            Priority::new(cmp),
+            [Arc::new(root)],
+            max_depth,
+            allow_circles,
+            visited,
+            false,
+            0,
+        );
+        Self { traversal }
+    }
+}
+
+/// The frontier of a [`BestFirst`] traversal, which reaches the greatest node first.
+///
+/// [`BestFirst`]: struct@crate::async::BestFirst
+pub(super) struct Priority<N, E> {
+    candidates: BinaryHeap<Candidate<N>>,
+    cmp: Comparator<N>,
+    num_discovered: usize,
+    /// The child stream of the last expanded node.
+    child_stream_fut: Option<ChildStreamFut<N, E>>,
+    current_stream: Option<Frame<N, E>>,
+}
+
+impl<N, E> Priority<N, E> {
+    #[inline]
+    fn new(cmp: Comparator<N>) -> Self {
+        Self {
+            candidates: BinaryHeap::new(),
+            cmp,
+            num_discovered: 0,
+            child_stream_fut: None,
+            current_stream: None,
+        }
+    }
+}
+
+impl<N> FrontierQueue<N> for Priority<N, N::Error>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    fn push<V>(&mut self, parent: &Path<N>, state: &mut State<N, V>) {
+        debug_assert!(self.child_stream_fut.is_none());
+        state.stats.record_children_call(1);
+        let fut = state.expansion.child_stream_fut(Arc::new(parent.clone()));
+        self.child_stream_fut = Some(fut);
+    }
+
+    fn poll_next<V>(
+        &mut self,
+        cx: &mut Context<'_>,
+        state: &mut State<N, V>,
+    ) -> Poll<Option<Result<Path<N>, TraversalError<Arc<N>, N::Error>>>>
+    where
+        V: VisitedSet<N>,
+    {
+        loop {
+            // add all children of the last expanded node to the frontier
+            if let Some(fut) = self.child_stream_fut.as_mut() {
+                let (parent, stream) = futures::ready!(fut.poll_unpin(cx));
+                self.child_stream_fut = None;
+                let stream = into_node_stream(stream);
+                self.current_stream = Some((parent, stream));
+            }
+
+            if let Some((parent, stream)) = self.current_stream.as_mut() {
+                match futures::ready!(stream.as_mut().poll_next(cx)) {
+                    Some(Err(err)) => {
+                        return Poll::Ready(Some(Err(TraversalError::child_of(parent, err))));
+                    }
+                    Some(Ok(node)) => {
+                        let path = Path::child(parent, Arc::new(node));
+                        let tracked =
+                            state.admission.tracks_visited() && !path.node().allow_revisit();
+                        if !(tracked && state.visited.contains(path.node())) {
+                            let seq = self.num_discovered;
+                            self.num_discovered += 1;
+                            let cmp = Arc::clone(&self.cmp);
+                            self.candidates.push(Candidate { path, seq, cmp });
+                        }
+                    }
+                    None => {
+                        self.current_stream = None;
+                    }
+                }
+                continue;
+            }
+
+            // the frontier is complete, continue with the best node
+            if let Some(Candidate { path, .. }) = self.candidates.pop() {
+                return Poll::Ready(Some(Ok(path)));
+            }
+
+            // the frontier is exhausted, continue with the next root
+            let Some(root) = pop_deferred(
+                &mut state.deferred,
+                state.max_depth,
+                &mut state.truncated,
+                |_| true,
+            ) else {
+                return Poll::Ready(None);
+            };
+            state.stats.record_children_call(1);
+            self.child_stream_fut = Some(state.expansion.child_stream_fut(root));
+        }
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.candidates.clear();
+        self.child_stream_fut = None;
+        self.current_stream = None;
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        usize::from(self.child_stream_fut.is_some())
+            + usize::from(self.current_stream.is_some())
+            + self.candidates.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    fn pending_children(&self) -> usize {
+        usize::from(self.child_stream_fut.is_some())
+    }
+
+    #[inline]
+    fn peek_depth(&self) -> Option<usize> {
+        if let Some(fut) = self.child_stream_fut.as_ref() {
+            return Some(fut.depth());
+        }
+        if let Some((parent, _)) = self.current_stream.as_ref() {
+            return Some(parent.depth() + 1);
+        }
+        self.candidates
+            .peek()
+            .map(|candidate| candidate.path.depth())
+    }
+
+    #[inline]
+    fn restart(&self) -> Self {
+        Self::new(Arc::clone(&self.cmp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BestFirst;
+    use crate::r#async::{Node, NodeStream};
+    use anyhow::Result;
+    use futures::StreamExt;
+    use std::sync::Arc;
+
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
+    struct Scored(u32);
+
+    #[async_trait::async_trait]
+    impl Node for Scored {
+        type Error = crate::utils::test::Error;
+
+        async fn children(
+            self: Arc<Self>,
+            _depth: usize,
+        ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+            let scores = match self.0 {
+                0 => vec![1, 5, 3],
+                5 => vec![2, 4],
+                3 => vec![6],
+                _ => vec![],
+            };
+            let nodes = scores.into_iter().map(|score| Ok(Self(score)));
+            Ok(Box::pin(futures::stream::iter(nodes)))
+        }
+    }
+
+    async fn scores<S>(stream: S) -> Result<Vec<u32>>
+    where
+        S: futures::Stream<Item = Result<Scored, crate::utils::test::Error>>,
+    {
+        let nodes = stream
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(nodes.into_iter().map(|node| node.0).collect())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_best_first_follows_priority() -> Result<()> {
+        let best_first = BestFirst::<Scored>::new(Scored(0), None, false);
+        similar_asserts::assert_eq!(scores(best_first).await?, [5, 4, 3, 6, 2, 1]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_best_first_max_depth() -> Result<()> {
+        let best_first = BestFirst::<Scored>::new(Scored(0), 1, false);
+        similar_asserts::assert_eq!(scores(best_first).await?, [5, 3, 1]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_best_first_max_nodes() -> Result<()> {
+        let best_first = BestFirst::<Scored>::new(Scored(0), None, false).max_nodes(3);
+        similar_asserts::assert_eq!(scores(best_first).await?, [5, 4, 3]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_best_first_with_depth() -> Result<()> {
+        use crate::r#async::Traverse;
+        let best_first = BestFirst::<crate::utils::test::Node>::new(0, 3, true);
+        let depths: Vec<_> = best_first
+            .with_depth()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(|item| item.map(|(depth, _)| depth))
+            .collect::<Result<_, _>>()?;
+        // deeper test nodes are greater, hence the traversal descends first
+        similar_asserts::assert_eq!(depths, [1, 2, 3, 3, 2, 3, 3, 1, 2, 3, 3, 2, 3, 3]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_best_first_with_comparator() -> Result<()> {
+        // prefer odd scores, then lower scores
+        let best_first = BestFirst::<Scored>::with_comparator(Scored(0), None, false, |a, b| {
+            (a.0 % 2).cmp(&(b.0 % 2)).then_with(|| b.0.cmp(&a.0))
+        });
+        similar_asserts::assert_eq!(scores(best_first).await?, [1, 3, 5, 2, 4, 6]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_best_first_with_comparator_breaks_ties_by_discovery() -> Result<()> {
+        // all nodes compare equal, hence they are yielded in the order they were discovered
+        let best_first = BestFirst::<Scored>::with_comparator(Scored(0), None, false, |_, _| {
+            std::cmp::Ordering::Equal
+        });
+        similar_asserts::assert_eq!(scores(best_first).await?, [1, 5, 3, 2, 4, 6]);
+        Ok(())
+    }
+}
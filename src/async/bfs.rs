@@ -1,18 +1,25 @@
-use super::{Node, NodeStream, StreamQueue};
+use super::{
+    into_node_stream, pop_deferred, BfsBuilder, ByLevel, DepthControl, Frame, FrontierQueue,
+    KeyedNode, LevelEvents, LruVisitedSet, Node, NodeStream, Path, State, StreamQueue, Tiebreak,
+    Traversal, TraversalError, VisitedSet,
+};
 
-use futures::stream::{FuturesOrdered, Stream, StreamExt};
-use futures::FutureExt;
-use pin_project::pin_project;
-use std::collections::HashSet;
-use std::pin::Pin;
+use std::collections::{HashSet, VecDeque};
+use std::hash::BuildHasher;
+use std::ops::RangeBounds;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
 #[allow(clippy::module_name_repetitions)]
 #[derive(Default)]
-#[pin_project]
 /// Asynchronous breadth-first stream for types implementing the [`Node`] trait.
 ///
+/// Although the [`Node::children`] calls run concurrently, the nodes are yielded
+/// in a deterministic order: the child streams are consumed in the order their
+/// nodes were yielded, regardless of how fast the calls complete.
+/// Hence, repeated traversals of the same graph yield identical sequences,
+/// unless [`unordered`] or [`eager`] is enabled.
+///
 /// ### Example
 /// ```
 /// use futures::StreamExt;
@@ -63,148 +70,794 @@ use std::task::{Context, Poll};
 /// ```
 ///
 /// [`Node`]: trait@crate::async::Node
-pub struct Bfs<N>
+/// [`Node::children`]: fn@crate::async::Node::children
+/// [`unordered`]: fn@crate::async::Bfs::unordered
+/// [`eager`]: fn@crate::async::Bfs::eager
+pub struct Bfs<N, V = HashSet<<N as KeyedNode>::Key>>
 where
     N: Node,
 {
-    #[pin]
-    current_stream: Option<(usize, NodeStream<N, N::Error>)>,
-    child_streams_futs: StreamQueue<N, N::Error>,
-    max_depth: Option<usize>,
-    allow_circles: bool,
-    visited: HashSet<N>,
+    pub(super) traversal: Traversal<N, V, Fifo<N, N::Error>>,
 }
 
 impl<N> Bfs<N>
 where
-    N: Node + Send + Unpin + Clone + 'static,
+    N: Node + Send + Unpin + 'static,
     N::Error: Send + 'static,
 {
     #[inline]
     /// Creates a new [`Bfs`] stream.
     ///
     /// The BFS will be performed from the `root` node up to depth `max_depth`.
+    /// The `root` itself is not yielded, unless enabled via [`BfsBuilder::include_root`].
     ///
     /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
     ///
     /// [`Bfs`]: struct@crate::async::Bfs
+    /// [`BfsBuilder::include_root`]: fn@crate::async::BfsBuilder::include_root
     pub fn new<R, D>(root: R, max_depth: D, allow_circles: bool) -> Self
     where
         R: Into<N>,
         D: Into<Option<usize>>,
     {
-        let root = root.into();
-        let max_depth = max_depth.into();
-        let mut child_streams_futs: StreamQueue<N, N::Error> = FuturesOrdered::new();
-        let depth = 1;
-        let child_stream_fut = Arc::new(root.clone())
-            .children(depth)
-            .map(move |stream| (depth, stream));
-        child_streams_futs.push_back(Box::pin(child_stream_fut));
+        Self::builder(root)
+            .max_depth(max_depth)
+            .allow_circles(allow_circles)
+            .build()
+    }
 
-        Self {
-            current_stream: None,
-            child_streams_futs,
-            max_depth,
-            visited: HashSet::from_iter([root]),
+    #[inline]
+    /// Creates a new [`Bfs`] stream from the `nodes` at `start_depth`, e.g. the
+    /// children of a root that were already computed, or of a root that is not a [`Node`].
+    ///
+    /// The `nodes` are yielded and expanded as if they were the children of a root
+    /// at `start_depth - 1`, but there is no such root: it is neither yielded nor tracked
+    /// as visited, and errors of the `nodes` stream are returned without a node.
+    /// Without `allow_circles`, the `nodes` are tracked as visited when they are yielded,
+    /// hence duplicates among them are skipped.
+    ///
+    /// The `nodes` stream can only be consumed once, hence it is not part of a
+    /// [`Bfs::snapshot`] and not restarted by [`Bfs::restart`].
+    ///
+    /// [`Bfs`]: struct@crate::async::Bfs
+    /// [`Node`]: trait@crate::async::Node
+    /// [`Bfs::snapshot`]: fn@crate::async::Bfs::snapshot
+    /// [`Bfs::restart`]: fn@crate::async::Bfs::restart
+    pub fn from_frontier<D>(
+        nodes: NodeStream<N, N::Error>,
+        start_depth: usize,
+        max_depth: D,
+        allow_circles: bool,
+    ) -> Self
+    where
+        D: Into<Option<usize>>,
+    {
+        let mut bfs = Self::from_parts(
+            VecDeque::new(),
+            VecDeque::new(),
+            max_depth.into(),
+            allow_circles,
+            HashSet::default(),
+        );
+        bfs.traversal.frontier.start_nodes = Some((start_depth, nodes));
+        bfs
+    }
+
+    #[inline]
+    /// Creates a new [`Bfs`] stream that yields the nodes with a depth in `range`.
+    ///
+    /// Nodes above the range are traversed but not yielded, as with [`Bfs::min_depth`],
+    /// and nodes below the range are not reached. Without an upper bound, the depth is unlimited.
+    /// The `root` itself is only yielded when the range contains depth 0,
+    /// unlike with [`Bfs::new`].
+    ///
+    /// ### Example
+    /// ```
+    /// use futures::StreamExt;
+    /// use par_dfs::r#async::{AdjacencyNode, Bfs};
+    /// use std::collections::HashMap;
+    /// use std::sync::Arc;
+    ///
+    /// let graph = Arc::new(HashMap::from([("a", vec!["b"]), ("b", vec!["c"]), ("c", vec!["d"])]));
+    /// let nodes = tokio_test::block_on(async {
+    ///     Bfs::<AdjacencyNode<&str>>::in_depth_range(AdjacencyNode::new(graph, "a"), 2..=3, false)
+    ///         .map(|node| *node.unwrap().key())
+    ///         .collect::<Vec<_>>()
+    ///         .await
+    /// });
+    /// assert_eq!(nodes, ["c", "d"]);
+    /// ```
+    ///
+    /// [`Bfs`]: struct@crate::async::Bfs
+    /// [`Bfs::min_depth`]: fn@crate::async::Bfs::min_depth
+    /// [`Bfs::new`]: fn@crate::async::Bfs::new
+    pub fn in_depth_range<R, B>(root: R, range: B, allow_circles: bool) -> Self
+    where
+        R: Into<N>,
+        B: RangeBounds<usize>,
+    {
+        let (min_depth, max_depth) = super::depth_bounds(&range);
+        Self::builder(root)
+            .max_depth(max_depth)
+            .allow_circles(allow_circles)
+            .include_root(min_depth == 0)
+            .build()
+            .min_depth(min_depth)
+    }
+
+    #[inline]
+    /// Creates a new [`Bfs`] stream whose maximum depth is read from `control`.
+    ///
+    /// See [`Bfs::depth_control`].
+    ///
+    /// [`Bfs`]: struct@crate::async::Bfs
+    /// [`Bfs::depth_control`]: fn@crate::async::Bfs::depth_control
+    pub fn new_with_control<R>(root: R, control: DepthControl, allow_circles: bool) -> Self
+    where
+        R: Into<N>,
+    {
+        Self::new(root, control.get(), allow_circles).depth_control(control)
+    }
+
+    #[inline]
+    /// Creates a [`BfsBuilder`] for a [`Bfs`] stream from the `root` node.
+    ///
+    /// By default, the traversal has no depth limit and circles are not allowed.
+    ///
+    /// ### Example
+    /// ```
+    /// use futures::StreamExt;
+    /// use par_dfs::r#async::{Bfs, Node, NodeStream};
+    ///
+    /// #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    /// struct Number(u32);
+    ///
+    /// #[async_trait::async_trait]
+    /// impl Node for Number {
+    ///     type Error = std::convert::Infallible;
+    ///
+    ///     async fn children(
+    ///         self: std::sync::Arc<Self>,
+    ///         depth: usize
+    ///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+    ///         let nodes = [Ok(Self(depth as u32)), Ok(Self(depth as u32))];
+    ///         Ok(Box::pin(futures::stream::iter(nodes)))
+    ///     }
+    /// }
+    ///
+    /// let result = tokio_test::block_on(async {
+    ///     let bfs = Bfs::<Number>::builder(Number(0))
+    ///         .max_depth(2)
+    ///         .allow_circles(true)
+    ///         .build()
+    ///         .max_nodes(4);
+    ///     let output = bfs
+    ///         .collect::<Vec<_>>()
+    ///         .await
+    ///         .into_iter()
+    ///         .collect::<Result<Vec<_>, _>>()
+    ///         .unwrap();
+    ///     output.into_iter().map(|n| n.0).collect::<Vec<_>>()
+    /// });
+    /// assert_eq!(result, [1, 1, 2, 2]);
+    /// ```
+    ///
+    /// [`BfsBuilder`]: struct@crate::async::BfsBuilder
+    /// [`Bfs`]: struct@crate::async::Bfs
+    pub fn builder<R>(root: R) -> BfsBuilder<N>
+    where
+        R: Into<N>,
+    {
+        BfsBuilder::new(root.into())
+    }
+
+    #[inline]
+    /// Creates a new [`Bfs`] stream from multiple `roots`.
+    ///
+    /// The roots are yielded first at depth 0, followed by their descendants
+    /// level by level up to depth `max_depth`. Nodes reachable from multiple roots
+    /// are only yielded once, unless `allow_circles`, in which case visited nodes will not be tracked.
+    /// Without any roots, the stream is empty.
+    ///
+    /// [`Bfs`]: struct@crate::async::Bfs
+    pub fn new_multi<I, R, D>(roots: I, max_depth: D, allow_circles: bool) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        let roots = roots.into_iter().map(|root| Arc::new(root.into()));
+        Self::from_roots(
+            roots,
+            max_depth.into(),
             allow_circles,
+            HashSet::new(),
+            true,
+            0,
+        )
+    }
+}
+
+impl<N, S> Bfs<N, HashSet<N::Key, S>>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+    S: BuildHasher,
+{
+    #[inline]
+    /// Creates a new [`Bfs`] stream with the given `hasher` for the visited nodes.
+    ///
+    /// The BFS will be performed from the `root` node up to depth `max_depth`.
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    /// Otherwise, the keys of the visited nodes are tracked in a [`HashSet`] using the given `hasher`.
+    ///
+    /// [`HashSet`]: struct@std::collections::HashSet
+    /// [`Bfs`]: struct@crate::async::Bfs
+    pub fn with_hasher<R, D>(root: R, max_depth: D, allow_circles: bool, hasher: S) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        Self::with_visited(root, max_depth, allow_circles, HashSet::with_hasher(hasher))
+    }
+}
+
+impl<N> Bfs<N, LruVisitedSet<N::Key>>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Key: Clone,
+    N::Error: Send + 'static,
+{
+    #[inline]
+    /// Creates a new [`Bfs`] stream that tracks at most `capacity` visited nodes.
+    ///
+    /// The BFS will be performed from the `root` node up to depth `max_depth`.
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    /// Otherwise, the least recently seen nodes are evicted once `capacity` is reached,
+    /// which bounds the memory at the cost of best-effort cycle detection:
+    /// a node that was evicted is yielded and expanded again when it is reached another time.
+    /// See [`LruVisitedSet`].
+    ///
+    /// [`LruVisitedSet`]: struct@crate::async::LruVisitedSet
+    /// [`Bfs`]: struct@crate::async::Bfs
+    pub fn with_visited_capacity<R, D>(
+        root: R,
+        max_depth: D,
+        allow_circles: bool,
+        capacity: usize,
+    ) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        Self::with_visited(root, max_depth, allow_circles, LruVisitedSet::new(capacity))
+    }
+}
+
+impl<N, V> Bfs<N, V>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+    V: VisitedSet<N>,
+{
+    #[inline]
+    /// Creates a new [`Bfs`] stream with the given set of `visited` nodes.
+    ///
+    /// The BFS will be performed from the `root` node up to depth `max_depth`.
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    /// Otherwise, the visited nodes are tracked in the given [`VisitedSet`].
+    ///
+    /// [`VisitedSet`]: trait@crate::async::VisitedSet
+    /// [`Bfs`]: struct@crate::async::Bfs
+    pub fn with_visited<R, D>(root: R, max_depth: D, allow_circles: bool, visited: V) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        let root = Arc::new(root.into());
+        Self::from_roots([root], max_depth.into(), allow_circles, visited, false, 0)
+    }
+
+    /// Creates a new [`Bfs`] stream from a shared `root` node.
+    ///
+    /// [`Bfs`]: struct@crate::async::Bfs
+    pub(super) fn from_shared(
+        root: Arc<N>,
+        max_depth: Option<usize>,
+        allow_circles: bool,
+        visited: V,
+    ) -> Self {
+        Self::from_roots([root], max_depth, allow_circles, visited, false, 0)
+    }
+
+    /// Consumes the traversal, returning its set of visited nodes.
+    pub(super) fn into_visited(self) -> V {
+        self.traversal.state.visited
+    }
+
+    /// Creates a new [`Bfs`] stream from shared `roots` at `initial_depth`,
+    /// which are only yielded when `emit_roots`.
+    ///
+    /// [`Bfs`]: struct@crate::async::Bfs
+    pub(super) fn from_roots<I>(
+        roots: I,
+        max_depth: Option<usize>,
+        allow_circles: bool,
+        visited: V,
+        emit_roots: bool,
+        initial_depth: usize,
+    ) -> Self
+    where
+        I: IntoIterator<Item = Arc<N>>,
+    {
+        Self {
+            traversal: Traversal::from_roots(
+                Fifo::default(),
+                roots,
+                max_depth,
+                allow_circles,
+                visited,
+                emit_roots,
+                initial_depth,
+            ),
+        }
+    }
+
+    /// Creates a new [`Bfs`] stream from the `roots` that are yet to be yielded
+    /// and the `deferred` nodes that are yet to be expanded.
+    ///
+    /// [`Bfs`]: struct@crate::async::Bfs
+    fn from_parts(
+        roots: VecDeque<Arc<Path<N>>>,
+        deferred: VecDeque<Arc<Path<N>>>,
+        max_depth: Option<usize>,
+        allow_circles: bool,
+        visited: V,
+    ) -> Self {
+        Self {
+            traversal: Traversal::from_parts(
+                Fifo::default(),
+                roots,
+                deferred,
+                max_depth,
+                allow_circles,
+                visited,
+            ),
         }
     }
+
+    #[inline]
+    #[must_use]
+    /// Returns the depth of the next node to be yielded, without polling the stream.
+    ///
+    /// Since child streams are lazy, this is the depth of the next node under the
+    /// assumption that the current child stream yields another node.
+    /// If it does not, the next node is yielded from a later child stream,
+    /// hence the returned depth is a lower bound.
+    /// Returns `None` once the frontier is empty.
+    pub fn peek_depth(&self) -> Option<usize> {
+        self.traversal.peek_depth()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Limits the number of child stream futures that are in flight at once.
+    ///
+    /// When the limit is reached, nodes are still yielded but their expansion
+    /// is deferred until enough of the pending child streams have been consumed.
+    /// This bounds the memory used by pending [`Node::children`] futures for wide graphs.
+    /// A limit of zero is treated as one.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.traversal.frontier.max_concurrency = Some(max_concurrency.max(1));
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Yields the child streams of each level in the order their futures complete.
+    ///
+    /// By default, child streams are consumed in the order their nodes were yielded,
+    /// so a single slow [`Node::children`] call stalls the remaining nodes of its level.
+    /// In unordered mode, nodes are still yielded level by level, i.e. with increasing depth,
+    /// but the order of the nodes within a level depends on how fast
+    /// their parents' child streams become available, and may differ between runs.
+    ///
+    /// Must be called before the stream is polled.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    pub fn unordered(mut self, unordered: bool) -> Self {
+        debug_assert!(self.traversal.frontier.child_streams_futs.is_empty());
+        self.traversal.frontier.child_streams_futs = if unordered {
+            StreamQueue::unordered()
+        } else {
+            StreamQueue::ordered()
+        };
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Expands the nodes of a level only once the previous level is exhausted.
+    ///
+    /// By default, the children of a node are requested as soon as it is yielded,
+    /// even while [`Node::children`] calls of the previous level are still pending.
+    /// In strict mode, the expansions are deferred until all [`Node::children`] calls
+    /// of the previous level completed and their child streams were consumed,
+    /// which limits the pending calls to a single level at a time,
+    /// e.g. to rate-limit requests per depth.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    pub fn strict_levels(mut self, strict_levels: bool) -> Self {
+        self.traversal.frontier.strict_levels = strict_levels;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Yields the child streams in the order their futures complete, regardless of their depth.
+    ///
+    /// Unlike [`unordered`], eager mode does not wait for the shallower levels,
+    /// so the children of a fast node may be yielded before the children of a slow node
+    /// at a lower depth. Hence, this intentionally breaks the guarantee that nodes are
+    /// yielded with increasing depth, and [`by_level`] must not be used in eager mode.
+    /// Use [`with_depth`] to recover the depth of each node.
+    ///
+    /// Must be called before the stream is polled.
+    /// Disabling eager mode restores the default ordered mode.
+    ///
+    /// [`unordered`]: fn@crate::async::Bfs::unordered
+    /// [`by_level`]: fn@crate::async::Bfs::by_level
+    /// [`with_depth`]: fn@crate::async::Traverse::with_depth
+    pub fn eager(mut self, eager: bool) -> Self {
+        debug_assert!(self.traversal.frontier.child_streams_futs.is_empty());
+        self.traversal.frontier.child_streams_futs = if eager {
+            StreamQueue::eager()
+        } else {
+            StreamQueue::ordered()
+        };
+        self
+    }
+
+    #[inline]
+    /// Yields all nodes of each depth at once, together with the depth.
+    ///
+    /// The resulting [`Stream`] yields `Result<(usize, Vec<N>), N::Error>`.
+    /// Since nodes are yielded level by level, a level is complete once the first node
+    /// of the next level is yielded, or the traversal ends.
+    /// Errors are yielded as soon as they occur.
+    ///
+    /// [`Stream`]: trait@futures::stream::Stream
+    pub fn by_level(self) -> ByLevel<Self> {
+        ByLevel::new(self)
+    }
+
+    #[inline]
+    /// Yields the nodes, followed by a [`LevelEvent::LevelComplete`] after the last
+    /// node of each depth.
+    ///
+    /// The resulting [`Stream`] yields `Result<LevelEvent<N>, N::Error>`.
+    /// Like [`by_level`], a level is complete once the first node of the next level
+    /// is yielded, or the traversal ends, in which case the last level is completed
+    /// before the stream ends. Errors are yielded as soon as they occur
+    /// and are not counted.
+    ///
+    /// [`LevelEvent::LevelComplete`]: enum@crate::async::LevelEvent
+    /// [`Stream`]: trait@futures::stream::Stream
+    /// [`by_level`]: fn@crate::async::Bfs::by_level
+    pub fn level_events(self) -> LevelEvents<Self> {
+        LevelEvents::new(self)
+    }
+
+    #[inline]
+    /// Yields the nodes of each depth in the order given by `tiebreak`.
+    ///
+    /// In [`unordered`] mode, the order of the nodes within a level depends on how fast
+    /// the [`Node::children`] calls complete. Sorting each level with `tiebreak`,
+    /// e.g. by key, yields the same sequence on every run.
+    /// Since a level can only be sorted once it is complete, all nodes of a level are
+    /// buffered before the first of them is yielded, which delays the nodes and
+    /// holds an entire level in memory. Without a tiebreak, nothing is buffered.
+    /// Errors are yielded as soon as they occur.
+    ///
+    /// Like [`by_level`], this relies on nodes being yielded with increasing depth,
+    /// and must not be used in [`eager`] mode.
+    ///
+    /// [`unordered`]: fn@crate::async::Bfs::unordered
+    /// [`by_level`]: fn@crate::async::Bfs::by_level
+    /// [`eager`]: fn@crate::async::Bfs::eager
+    /// [`Node::children`]: fn@crate::async::Node::children
+    pub fn tiebreak<F>(self, tiebreak: F) -> Tiebreak<Self, F>
+    where
+        F: FnMut(&N, &N) -> std::cmp::Ordering,
+    {
+        Tiebreak::new(self, tiebreak)
+    }
 }
 
-impl<N> Stream for Bfs<N>
+#[cfg(feature = "serde")]
+impl<N, V> Bfs<N, V>
 where
-    N: Node + Send + Clone + Unpin + 'static,
+    N: Node + Send + Unpin + 'static,
     N::Error: Send + 'static,
+    V: VisitedSet<N>,
 {
-    type Item = Result<N, N::Error>;
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    #[must_use]
+    /// Captures the state of the traversal in a serializable [`Snapshot`].
+    ///
+    /// Pending [`Node::children`] calls can not be captured, hence the snapshot
+    /// contains the nodes whose children are yet to be yielded instead.
+    /// This includes the node whose child stream is partially consumed,
+    /// which is expanded again on resume. Without `allow_circles`,
+    /// its children that were already yielded are skipped as visited.
+    ///
+    /// The configuration, such as `min_depth` or `max_nodes`, is not part of the snapshot.
+    ///
+    /// [`Snapshot`]: struct@crate::async::Snapshot
+    /// [`Node::children`]: fn@crate::async::Node::children
+    pub fn snapshot(&self) -> super::Snapshot<N, V>
+    where
+        N: Clone,
+        V: Clone,
+    {
+        let Traversal { frontier, state } = &self.traversal;
+        let current = frontier.current_stream.iter().map(|(parent, _)| parent);
+        let frontier = current
+            .chain(frontier.child_streams_futs.parents())
+            .chain(state.deferred.iter())
+            .map(|path| (path.depth(), N::clone(path.node())))
+            .collect();
+        let roots = state
+            .roots
+            .iter()
+            .map(|root| N::clone(root.node()))
+            .collect();
+        super::Snapshot {
+            roots,
+            frontier,
+            visited: state.visited.clone(),
+            max_depth: state.max_depth,
+            allow_circles: state.admission.allow_circles,
+        }
+    }
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let mut this = self.project();
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    #[must_use]
+    /// Resumes a traversal from a [`Snapshot`].
+    ///
+    /// Since the ancestors of the nodes are not part of the snapshot,
+    /// the [`Path`]s of the resumed traversal start at the nodes of the snapshot.
+    ///
+    /// [`Snapshot`]: struct@crate::async::Snapshot
+    /// [`Path`]: struct@crate::async::Path
+    pub fn resume(snapshot: super::Snapshot<N, V>) -> Self {
+        let roots = snapshot
+            .roots
+            .into_iter()
+            .map(|root| Arc::new(Path::root(Arc::new(root))))
+            .collect();
+        let deferred = snapshot
+            .frontier
+            .into_iter()
+            .map(|(depth, node)| Arc::new(Path::detached(Arc::new(node), depth)))
+            .collect();
+        Self::from_parts(
+            roots,
+            deferred,
+            snapshot.max_depth,
+            snapshot.allow_circles,
+            snapshot.visited,
+        )
+    }
+}
 
-        // println!("------- poll");
-        // println!("has current stream: {:?}", this.current_stream.is_some());
+/// The frontier of a [`Bfs`], which expands the nodes in the order they are reached.
+///
+/// [`Bfs`]: struct@crate::async::Bfs
+pub(super) struct Fifo<N, E> {
+    /// The child stream that is currently consumed.
+    current_stream: Option<Frame<N, E>>,
+    /// Nodes at the given depth that the traversal starts from, without a parent.
+    start_nodes: Option<(usize, NodeStream<N, E>)>,
+    child_streams_futs: StreamQueue<N, E>,
+    max_concurrency: Option<usize>,
+    /// Whether the expansion of a level waits until the previous level is exhausted.
+    strict_levels: bool,
+}
 
-        loop {
-            let mut current_stream = this.current_stream.as_mut().as_pin_mut();
-            let next_item = match current_stream.as_deref_mut() {
-                Some((depth, stream)) => {
-                    let next_item = stream.as_mut().poll_next(cx);
-                    Some(next_item.map(|node| (depth, node)))
-                }
-                None => None,
-            };
+impl<N, E> Default for Fifo<N, E> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            current_stream: None,
+            start_nodes: None,
+            child_streams_futs: StreamQueue::ordered(),
+            max_concurrency: None,
+            strict_levels: false,
+        }
+    }
+}
 
-            // println!("next item: {:?}", next_item);
-            match next_item {
-                // stream item is ready but failure success
-                Some(Poll::Ready((_, Some(Err(err))))) => {
-                    return Poll::Ready(Some(Err(err)));
-                }
-                // stream item is ready and success
-                Some(Poll::Ready((depth, Some(Ok(node))))) => {
-                    if *this.allow_circles || !this.visited.contains(&node) {
-                        if !*this.allow_circles {
-                            this.visited.insert(node.clone());
-                        }
+impl<N, E> Fifo<N, E> {
+    /// Returns `true` if the number of pending child streams reached `max_concurrency`.
+    #[inline]
+    fn is_saturated(&self) -> bool {
+        self.max_concurrency
+            .is_some_and(|max_concurrency| self.child_streams_futs.len() >= max_concurrency)
+    }
+}
 
-                        if let Some(max_depth) = this.max_depth {
-                            if depth >= max_depth {
-                                return Poll::Ready(Some(Ok(node)));
-                            }
-                        }
+impl<N> Fifo<N, N::Error>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    /// Resumes deferred expansions up to the concurrency limit.
+    fn expand_deferred<V>(&mut self, state: &mut State<N, V>) {
+        while !self.is_saturated() {
+            let child_streams_futs = &self.child_streams_futs;
+            let strict_levels = self.strict_levels;
+            let Some(parent) = pop_deferred(
+                &mut state.deferred,
+                state.max_depth,
+                &mut state.truncated,
+                |front| {
+                    // wait until the expansions of the previous level completed
+                    !strict_levels
+                        || child_streams_futs
+                            .parents()
+                            .next()
+                            .is_none_or(|pending| pending.depth() == front.depth())
+                },
+            ) else {
+                break;
+            };
+            self.child_streams_futs
+                .push_back(state.expansion.child_stream_fut(parent));
+            state
+                .stats
+                .record_children_call(self.child_streams_futs.len());
+        }
+    }
+}
 
-                        // add child stream future to be polled
-                        let arc_node = Arc::new(node.clone());
-                        let next_depth = *depth + 1;
-                        let child_stream_fut = arc_node
-                            .children(next_depth)
-                            .map(move |stream| (next_depth, stream));
-                        this.child_streams_futs
-                            .push_back(Box::pin(child_stream_fut));
+impl<N> FrontierQueue<N> for Fifo<N, N::Error>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    fn push<V>(&mut self, parent: &Path<N>, state: &mut State<N, V>) {
+        let parent = Arc::new(parent.clone());
+        if self.strict_levels || self.is_saturated() || !state.deferred.is_empty() {
+            // defer the expansion to keep the order,
+            // or until the current level is exhausted
+            state.deferred.push_back(parent);
+        } else {
+            // add child stream future to be polled
+            self.child_streams_futs
+                .push_back(state.expansion.child_stream_fut(parent));
+            state
+                .stats
+                .record_children_call(self.child_streams_futs.len());
+        }
+    }
+
+    fn poll_next<V>(
+        &mut self,
+        cx: &mut Context<'_>,
+        state: &mut State<N, V>,
+    ) -> Poll<Option<Result<Path<N>, TraversalError<Arc<N>, N::Error>>>>
+    where
+        V: VisitedSet<N>,
+    {
+        #[cfg(feature = "trace")]
+        let _span = tracing::trace_span!("bfs", has_current_stream = self.current_stream.is_some())
+            .entered();
 
-                        return Poll::Ready(Some(Ok(node)));
+        loop {
+            if let Some((parent, stream)) = self.current_stream.as_mut() {
+                match stream.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(item)) => {
+                        let item = item
+                            .map(|node| Path::child(parent, Arc::new(node)))
+                            .map_err(|err| TraversalError::child_of(parent, err));
+                        return Poll::Ready(Some(item));
                     }
+                    // current stream completed, drop it and proceed to poll the next stream
+                    Poll::Ready(None) => self.current_stream = None,
+                    Poll::Pending => return Poll::Pending,
                 }
-                // stream item is pending
-                Some(Poll::Pending) => {
-                    return Poll::Pending;
-                }
-                // no current stream or completed
-                Some(Poll::Ready((_, None))) | None => {
-                    // proceed to poll the next stream
+            } else if let Some((depth, stream)) = self.start_nodes.as_mut() {
+                let depth = *depth;
+                match stream.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(item)) => {
+                        let item = item
+                            .map(|node| Path::detached(Arc::new(node), depth))
+                            .map_err(|source| TraversalError {
+                                node: None,
+                                depth,
+                                source,
+                            });
+                        return Poll::Ready(Some(item));
+                    }
+                    // proceed with the child streams of the start nodes
+                    Poll::Ready(None) => self.start_nodes = None,
+                    Poll::Pending => return Poll::Pending,
                 }
             }
 
+            self.expand_deferred(state);
+
             // poll the next stream
-            // println!("child stream futs: {:?}", this.child_streams_futs.len());
-            match this.child_streams_futs.poll_next_unpin(cx) {
-                Poll::Ready(Some((depth, stream))) => {
-                    // println!(
-                    //     "child stream fut depth {} completed: {:?}",
-                    //     depth,
-                    //     stream.is_ok()
-                    // );
-                    let stream = match stream {
-                        Ok(stream) => stream.boxed(),
-                        Err(err) => futures::stream::iter([Err(err)]).boxed(),
-                    };
-                    this.current_stream.set(Some((depth, Box::pin(stream))));
+            trace!(child_streams_futs = self.child_streams_futs.len());
+            match self.child_streams_futs.poll_next_unpin(cx) {
+                Poll::Ready(Some((parent, stream))) => {
+                    trace!(
+                        depth = parent.depth() + 1,
+                        ok = stream.is_ok(),
+                        "child stream completed"
+                    );
+                    let stream = into_node_stream(stream);
+                    self.current_stream = Some((parent, stream));
                 }
                 // when there are no more child stream futures,
                 // we are done
                 Poll::Ready(None) => {
-                    // println!("no more child streams");
+                    trace!("no more child streams");
                     return Poll::Ready(None);
                 }
                 // still waiting for the next stream
                 Poll::Pending => {
-                    // println!("child stream is still pending");
+                    trace!("child stream is still pending");
                     return Poll::Pending;
                 }
             }
         }
     }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.current_stream = None;
+        self.start_nodes = None;
+        self.child_streams_futs.clear();
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        usize::from(self.current_stream.is_some()) + self.child_streams_futs.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0 && self.start_nodes.is_none()
+    }
+
+    #[inline]
+    fn pending_children(&self) -> usize {
+        self.child_streams_futs.len()
+    }
+
+    #[inline]
+    fn peek_depth(&self) -> Option<usize> {
+        if let (None, Some((depth, _))) = (&self.current_stream, &self.start_nodes) {
+            return Some(*depth);
+        }
+        self.current_stream
+            .as_ref()
+            .map(|(parent, _)| parent)
+            .or_else(|| self.child_streams_futs.parents().next())
+            .map(|parent| parent.depth() + 1)
+    }
+
+    #[inline]
+    fn restart(&self) -> Self {
+        Self::default()
+    }
 }
 
 #[cfg(test)]
@@ -303,4 +956,1871 @@ mod tests {
         test_depths_ordered,
         test_depths_unordered,
     );
+
+    test_depths!(
+        bfs_with_hasher:
+        (
+            Bfs::<crate::utils::test::Node, _>::with_hasher(
+                0,
+                3,
+                false,
+                std::hash::BuildHasherDefault::<std::collections::hash_map::DefaultHasher>::default(),
+            ),
+            [1, 2, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_with_visited_capacity:
+        (
+            Bfs::<crate::utils::test::Node, _>::with_visited_capacity(0, 3, false, 1),
+            [1, 2, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_with_visited_all:
+        (
+            Bfs::<crate::utils::test::Node, _>::with_visited(
+                0,
+                3,
+                false,
+                crate::utils::test::r#async::AllVisited,
+            ),
+            Vec::<usize>::new()
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_with_visited_none:
+        (
+            Bfs::<crate::utils::test::Node, _>::with_visited(
+                0,
+                3,
+                false,
+                crate::utils::test::r#async::NoneVisited,
+            ),
+            [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_prune:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, true).prune(|node, _depth| node.0 == 2),
+            [1, 1, 2, 2, 2, 2]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_prune_unordered_queue:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, true)
+                .unordered(true)
+                .prune(|node, _depth| node.0 == 2),
+            [1, 1, 2, 2, 2, 2]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_prune_no_circles:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, false).prune(|_node, depth| depth == 1),
+            [1]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_filter_nodes:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, true).filter_nodes(|node, _depth| node.0 != 2),
+            [1, 1]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_min_depth:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, true).min_depth(2),
+            [2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_min_depth_no_circles:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, false).min_depth(2),
+            [2, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_min_depth_exceeds_max_depth:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, true).min_depth(4),
+            Vec::<usize>::new()
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_max_concurrency:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, true).max_concurrency(1),
+            [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_max_concurrency_bounds_in_flight_children() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio::time::{sleep, Duration};
+
+        static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+        static MAX_IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct CountingNode(usize);
+
+        #[async_trait::async_trait]
+        impl Node for CountingNode {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let in_flight = IN_FLIGHT.fetch_add(1, Ordering::SeqCst) + 1;
+                MAX_IN_FLIGHT.fetch_max(in_flight, Ordering::SeqCst);
+                sleep(Duration::from_millis(10)).await;
+                IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+
+                let id = self.0;
+                let nodes = (1..=4).map(move |i| Ok(Self(id * 4 + i)));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let bfs = Bfs::<CountingNode>::new(CountingNode(0), 3, false).max_concurrency(2);
+        let nodes = bfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        similar_asserts::assert_eq!(nodes.len(), 4 + 16 + 64);
+        assert!(MAX_IN_FLIGHT.load(Ordering::SeqCst) <= 2);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_find_drops_pending_children() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio::time::{sleep, Duration};
+
+        static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+        /// Tracks a running children call until it completes or is dropped.
+        struct InFlight;
+
+        impl InFlight {
+            fn new() -> Self {
+                IN_FLIGHT.fetch_add(1, Ordering::SeqCst);
+                Self
+            }
+        }
+
+        impl Drop for InFlight {
+            fn drop(&mut self) {
+                IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct CountingNode(usize);
+
+        #[async_trait::async_trait]
+        impl Node for CountingNode {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let _in_flight = InFlight::new();
+                let id = self.0;
+                // later siblings are slower, hence still pending when a match is found
+                sleep(Duration::from_millis(50 * id as u64)).await;
+                let nodes = (1..=4).map(move |i| Ok(Self(id * 4 + i)));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let bfs = Bfs::<CountingNode>::new(CountingNode(0), None, false);
+        let found = bfs.find(|node| node.0 == 6).await?;
+        similar_asserts::assert_eq!(found, Some(CountingNode(6)));
+        // the pending children calls of the remaining nodes were dropped
+        similar_asserts::assert_eq!(IN_FLIGHT.load(Ordering::SeqCst), 0);
+        Ok(())
+    }
+
+    test_depths!(
+        bfs_unordered_queue:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, true).unordered(true),
+            [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_unordered_queue_does_not_stall_on_slow_node() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+        use tokio::time::{sleep, Duration};
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec!["slow", "fast"],
+                    "slow" => {
+                        sleep(Duration::from_millis(200)).await;
+                        vec!["slow child"]
+                    }
+                    "fast" => vec!["fast child"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|name| Ok(Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        for (unordered, expected) in [
+            (false, ["slow", "fast", "slow child", "fast child"]),
+            (true, ["slow", "fast", "fast child", "slow child"]),
+        ] {
+            let root = NamedNode("root".into());
+            let bfs = Bfs::<NamedNode>::new(root, None, false).unordered(unordered);
+            let nodes = bfs
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            let names: Vec<_> = nodes.iter().map(|node| node.0.as_str()).collect();
+            similar_asserts::assert_eq!(names, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bfs_unordered_expands_deeper_nodes_concurrently() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+        use tokio::time::{sleep, Duration, Instant};
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec!["slow", "fast"],
+                    "fast" => vec!["fast child"],
+                    "slow" | "fast child" => {
+                        sleep(Duration::from_millis(300)).await;
+                        vec![]
+                    }
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|name| Ok(Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let mut elapsed = vec![];
+        for unordered in [false, true] {
+            let root = NamedNode("root".into());
+            let bfs = Bfs::<NamedNode>::new(root, None, false).unordered(unordered);
+            let start = Instant::now();
+            let nodes = bfs
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            elapsed.push(start.elapsed());
+            similar_asserts::assert_eq!(nodes.len(), 3);
+        }
+        // the children of "fast child" are requested while "slow" is still pending,
+        // whereas the ordered queue waits for "slow" before yielding "fast child".
+        // the clock is paused, hence the elapsed time only advances with the sleeps
+        let [ordered, unordered] = elapsed[..] else {
+            unreachable!();
+        };
+        assert!(ordered >= Duration::from_millis(600), "{ordered:?}");
+        assert!(unordered < Duration::from_millis(600), "{unordered:?}");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_strict_levels_waits_for_previous_level() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::{Arc, Mutex};
+        use tokio::time::{sleep, Duration};
+
+        /// The depth of each started and completed children call, in order.
+        static EVENTS: Mutex<Vec<(&str, usize)>> = Mutex::new(vec![]);
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                EVENTS.lock().unwrap().push(("start", depth));
+                let children = match self.0.as_str() {
+                    "root" => vec!["fast", "slow"],
+                    "slow" => {
+                        sleep(Duration::from_millis(200)).await;
+                        vec!["slow child"]
+                    }
+                    "fast" => vec!["fast child"],
+                    _ => vec![],
+                };
+                EVENTS.lock().unwrap().push(("end", depth));
+                let nodes = children.into_iter().map(|name| Ok(Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        for strict_levels in [false, true] {
+            EVENTS.lock().unwrap().clear();
+            let root = NamedNode("root".into());
+            let bfs = Bfs::<NamedNode>::new(root, None, false).strict_levels(strict_levels);
+            let nodes = bfs
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            similar_asserts::assert_eq!(nodes.len(), 4);
+
+            let events = EVENTS.lock().unwrap().clone();
+            // the children of the depth 1 nodes are at depth 2
+            let last_depth_1_end = events
+                .iter()
+                .rposition(|event| *event == ("end", 2))
+                .unwrap();
+            let first_depth_2_start = events
+                .iter()
+                .position(|event| *event == ("start", 3))
+                .unwrap();
+            similar_asserts::assert_eq!(first_depth_2_start > last_depth_1_end, strict_levels);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_eager_yields_deeper_nodes_first() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, Traverse};
+        use futures::StreamExt;
+        use std::sync::Arc;
+        use tokio::time::{sleep, Duration};
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec!["slow", "fast"],
+                    "slow" => {
+                        sleep(Duration::from_millis(200)).await;
+                        vec!["slow child"]
+                    }
+                    "fast" => vec!["fast child"],
+                    "fast child" => vec!["fast grandchild"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|name| Ok(Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let root = NamedNode("root".into());
+        let bfs = Bfs::<NamedNode>::new(root, None, true).eager(true);
+        let nodes = bfs
+            .with_depth()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let nodes: Vec<_> = nodes
+            .iter()
+            .map(|(depth, node)| (*depth, node.0.as_str()))
+            .collect();
+        // the depth is no longer monotonic
+        similar_asserts::assert_eq!(
+            nodes,
+            [
+                (1, "slow"),
+                (1, "fast"),
+                (2, "fast child"),
+                (3, "fast grandchild"),
+                (2, "slow child"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_tiebreak_sorts_unordered_levels() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+        use tokio::time::{sleep, Duration};
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec!["slow", "fast"],
+                    "slow" => {
+                        sleep(Duration::from_millis(200)).await;
+                        vec!["slow child"]
+                    }
+                    "fast" => vec!["fast child"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|name| Ok(Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let root = NamedNode("root".into());
+        let nodes = Bfs::<NamedNode>::new(root, None, false)
+            .unordered(true)
+            .tiebreak(|a, b| a.0.cmp(&b.0))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let names: Vec<_> = nodes.iter().map(|node| node.0.as_str()).collect();
+        similar_asserts::assert_eq!(names, ["fast", "slow", "fast child", "slow child"]);
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_drain_into() -> Result<()> {
+        use futures::StreamExt;
+        let expected = Bfs::<crate::utils::test::Node>::new(0, 3, true)
+            .collect::<Vec<_>>()
+            .await;
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let bfs = Bfs::<crate::utils::test::Node>::new(0, 3, true);
+        let drain = tokio::spawn(bfs.drain_into(tx));
+        let mut output = Vec::new();
+        while let Some(item) = rx.recv().await {
+            output.push(item);
+        }
+        drain.await??;
+        similar_asserts::assert_eq!(output, expected);
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_per_call_timeout() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, TimeoutError};
+        use futures::StreamExt;
+        use std::sync::Arc;
+        use tokio::time::{sleep, Duration};
+
+        #[derive(thiserror::Error, Debug, PartialEq, Eq)]
+        enum Error {
+            #[error(transparent)]
+            Timeout(#[from] TimeoutError),
+        }
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec!["slow", "fast"],
+                    "slow" => {
+                        sleep(Duration::from_secs(10)).await;
+                        vec!["slow child"]
+                    }
+                    "fast" => vec!["fast child"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|name| Ok(Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let timeout = Duration::from_millis(100);
+        let root = NamedNode("root".into());
+        let bfs = Bfs::<NamedNode>::new(root, None, false).per_call_timeout(timeout);
+        let items = bfs
+            .map(|item| item.map(|node| node.0))
+            .collect::<Vec<_>>()
+            .await;
+        similar_asserts::assert_eq!(
+            items,
+            [
+                Ok("slow".to_string()),
+                Ok("fast".to_string()),
+                Err(Error::Timeout(TimeoutError { depth: 2, timeout })),
+                Ok("fast child".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_inspect_errors() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, Traverse};
+        use crate::utils::test::Error;
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(&'static str);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0 {
+                    "root" => vec![Ok("failing"), Err(Error), Ok("ok")],
+                    "failing" => return Err(Error),
+                    "ok" => vec![Ok("ok child")],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|child| child.map(Self));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let mut errors = 0;
+        let items = Bfs::<NamedNode>::new(NamedNode("root"), None, false)
+            .inspect_errors(|_| errors += 1)
+            .collect::<Vec<_>>()
+            .await;
+        similar_asserts::assert_eq!(errors, 2);
+        // the errors are yielded unchanged
+        similar_asserts::assert_eq!(
+            items,
+            [
+                Ok(NamedNode("failing")),
+                Err(Error),
+                Ok(NamedNode("ok")),
+                Err(Error),
+                Ok(NamedNode("ok child")),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_with_seq() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, Traverse};
+        use crate::utils::test::Error;
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(&'static str);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0 {
+                    "root" => vec![Ok("a"), Err(Error), Ok("b")],
+                    "a" => vec![Ok("c")],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|child| child.map(Self));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let items = Bfs::<NamedNode>::new(NamedNode("root"), None, false)
+            .with_seq()
+            .collect::<Vec<_>>()
+            .await;
+        // the sequence starts at 0 and errors do not skip a number
+        similar_asserts::assert_eq!(
+            items,
+            [
+                Ok((0, NamedNode("a"))),
+                Err(Error),
+                Ok((1, NamedNode("b"))),
+                Ok((2, NamedNode("c"))),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_skip_errors() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use crate::utils::test::Error;
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec![Ok("failing"), Err(Error), Ok("ok")],
+                    "failing" => return Err(Error),
+                    "ok" => vec![Ok("ok child")],
+                    _ => vec![],
+                };
+                let nodes = children
+                    .into_iter()
+                    .map(|child| child.map(|name| Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let root = NamedNode("root".into());
+        let mut bfs = Bfs::<NamedNode>::new(root, None, false).skip_errors(true);
+        let nodes = bfs
+            .by_ref()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let names: Vec<_> = nodes.iter().map(|node| node.0.as_str()).collect();
+        similar_asserts::assert_eq!(names, ["failing", "ok", "ok child"]);
+        similar_asserts::assert_eq!(bfs.skipped_errors(), [Error, Error]);
+        similar_asserts::assert_eq!(bfs.take_skipped_errors(), [Error, Error]);
+        assert!(bfs.skipped_errors().is_empty());
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_retry() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, RetryPolicy};
+        use crate::utils::test::Error;
+        use futures::StreamExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio::time::Duration;
+
+        static FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct FlakyNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for FlakyNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec!["flaky"],
+                    "flaky" => {
+                        // fails twice before succeeding
+                        if FAILURES.fetch_add(1, Ordering::SeqCst) < 2 {
+                            return Err(Error);
+                        }
+                        vec!["flaky child"]
+                    }
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|name| Ok(Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let backoff = Duration::from_millis(10);
+        for (max_retries, expected) in [
+            (
+                2,
+                vec![Ok("flaky".to_string()), Ok("flaky child".to_string())],
+            ),
+            (1, vec![Ok("flaky".to_string()), Err(Error)]),
+        ] {
+            FAILURES.store(0, Ordering::SeqCst);
+            let root = FlakyNode("root".into());
+            let bfs = Bfs::<FlakyNode>::new(root, None, false)
+                .retry(RetryPolicy::new(max_retries, backoff));
+            let items = bfs
+                .map(|item| item.map(|node| node.0))
+                .collect::<Vec<_>>()
+                .await;
+            similar_asserts::assert_eq!(items, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_keyed_node() -> Result<()> {
+        use crate::r#async::{KeyedNode, Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        /// A node with a payload that is neither `Hash` nor `Eq`.
+        #[derive(Clone, Debug)]
+        struct Page {
+            url: &'static str,
+            score: f64,
+        }
+
+        impl KeyedNode for Page {
+            type Key = &'static str;
+
+            fn key(&self) -> Self::Key {
+                self.url
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl Node for Page {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let urls = match self.url {
+                    "/" => vec!["/a", "/b"],
+                    "/a" => vec!["/", "/b", "/c"],
+                    _ => vec![],
+                };
+                let score = self.score + 1.0;
+                let nodes = urls.into_iter().map(move |url| Ok(Self { url, score }));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let root = Page {
+            url: "/",
+            score: 0.0,
+        };
+        let nodes = Bfs::<Page>::new(root, None, false)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let urls: Vec<_> = nodes.iter().map(|node| node.url).collect();
+        similar_asserts::assert_eq!(urls, ["/a", "/b", "/c"]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_shared_non_clone_node() -> Result<()> {
+        use crate::r#async::{KeyedNode, Node, NodeStream, Traverse};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        /// A node that is not `Clone`.
+        #[derive(Debug)]
+        struct BufferNode {
+            id: usize,
+            buffer: Vec<u8>,
+        }
+
+        impl KeyedNode for BufferNode {
+            type Key = usize;
+
+            fn key(&self) -> Self::Key {
+                self.id
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl Node for BufferNode {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let ids = [2 * self.id + 1, 2 * self.id + 2];
+                let nodes = ids.map(|id| {
+                    Ok(Self {
+                        id,
+                        buffer: vec![0; 1024],
+                    })
+                });
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let root = BufferNode {
+            id: 0,
+            buffer: vec![],
+        };
+        let nodes = Bfs::<BufferNode>::new(root, 2, false)
+            .shared()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(nodes.iter().all(|node| node.buffer.len() == 1024));
+        let ids: Vec<_> = nodes.iter().map(|node| node.id).collect();
+        similar_asserts::assert_eq!(ids, [1, 2, 3, 4, 5, 6]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_catch_panics() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, PanicError};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(thiserror::Error, Debug, PartialEq, Eq)]
+        enum Error {
+            #[error(transparent)]
+            Panic(#[from] PanicError),
+        }
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec!["a", "b"],
+                    "a" => panic!("bad node"),
+                    "b" => vec!["b child"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|name| Ok(Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let root = NamedNode("root".into());
+        let bfs = Bfs::<NamedNode>::new(root, None, false).catch_panics();
+        let items = bfs
+            .map(|item| item.map(|node| node.0))
+            .collect::<Vec<_>>()
+            .await;
+        similar_asserts::assert_eq!(
+            items,
+            [
+                Ok("a".to_string()),
+                Ok("b".to_string()),
+                Err(Error::Panic(PanicError {
+                    depth: 2,
+                    message: "bad node".to_string(),
+                })),
+                Ok("b child".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_stop_flag_after_items() -> Result<()> {
+        use futures::StreamExt;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+        use tokio::time::{timeout, Duration};
+
+        let stop = Arc::new(AtomicBool::new(false));
+        // without a depth limit, the traversal never completes on its own
+        let mut bfs =
+            Bfs::<crate::utils::test::Node>::new(0, None, true).stop_flag(Arc::clone(&stop));
+        let handle = tokio::spawn(async move {
+            let mut count = 0;
+            while let Some(node) = bfs.next().await {
+                node?;
+                count += 1;
+                if count == 5 {
+                    stop.store(true, Ordering::Release);
+                }
+            }
+            Ok::<_, anyhow::Error>((bfs.stats().children_calls, count))
+        });
+        let (children_calls, count) = timeout(Duration::from_secs(10), handle).await???;
+        similar_asserts::assert_eq!(count, 5);
+        // the remaining frontier is dropped instead of drained,
+        // hence only the root and the yielded nodes were expanded
+        assert!(children_calls <= count + 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_deadline() -> Result<()> {
+        use tokio::time::{timeout, Duration, Instant};
+
+        // without a depth limit, the traversal never completes on its own
+        let deadline = Instant::now() + Duration::from_millis(400);
+        let bfs = Bfs::<crate::utils::test::Node>::new(0, None, true).deadline(deadline);
+        let count = timeout(Duration::from_secs(10), bfs.count()).await??;
+        assert!(count > 0);
+        assert!(Instant::now() >= deadline);
+        Ok(())
+    }
+
+    #[cfg(feature = "cancellation")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_cancellation_wakes_pending_stream() -> Result<()> {
+        use tokio::time::{sleep, timeout, Duration};
+        use tokio_util::sync::CancellationToken;
+
+        let token = CancellationToken::new();
+        // without a depth limit, the traversal never completes on its own
+        let bfs =
+            Bfs::<crate::utils::test::Node>::new(0, None, true).cancellation_token(token.clone());
+        let handle = tokio::spawn(bfs.count());
+        sleep(Duration::from_millis(400)).await;
+        token.cancel();
+        let count = timeout(Duration::from_secs(10), handle).await???;
+        assert!(count > 0);
+        Ok(())
+    }
+
+    test_depths!(
+        bfs_max_nodes:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, true).max_nodes(5),
+            [1, 1, 2, 2, 2]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_max_nodes_without_max_depth:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, None, true).max_nodes(5),
+            [1, 1, 2, 2, 2]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_max_depth_before_max_nodes:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, true).max_nodes(100),
+            [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        bfs_zero_max_nodes:
+        (
+            Bfs::<crate::utils::test::Node>::new(0, 3, true).max_nodes(0),
+            Vec::<usize>::new()
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_paths() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+        let bfs = Bfs::<crate::utils::test::Node>::new(0, 3, false);
+        let paths = bfs
+            .paths()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let paths: Vec<Vec<_>> = paths
+            .into_iter()
+            .map(|path| path.into_iter().map(|node| node.0).collect())
+            .collect();
+        // test nodes are created with the depth passed to children
+        similar_asserts::assert_eq!(paths, [vec![0, 1], vec![0, 1, 2], vec![0, 1, 2, 3]]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_take_while_depth() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+        // without a depth limit, the traversal never completes on its own
+        let bfs = Bfs::<crate::utils::test::Node>::new(0, None, true);
+        let depths: Vec<_> = bfs
+            .take_while_depth(2)
+            .with_depth()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(|item| item.map(|(depth, _)| depth))
+            .collect::<Result<_, _>>()?;
+        similar_asserts::assert_eq!(depths, [1, 1, 2, 2, 2, 2]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_map_emitted() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+        let bfs = Bfs::<crate::utils::test::Node>::new(0, 3, false);
+        let labels = bfs
+            .map_emitted(|node| format!("node {}", node.0))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        // the traversal still expands the original nodes
+        similar_asserts::assert_eq!(labels, ["node 1", "node 2", "node 3"]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_with_depth() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+        for (allow_circles, expected_depths) in [
+            (true, [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 3].to_vec()),
+            (false, [1, 2, 3].to_vec()),
+        ] {
+            let bfs = Bfs::<crate::utils::test::Node>::new(0, 3, allow_circles);
+            let nodes = bfs
+                .with_depth()
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            // test nodes are created with the depth passed to children
+            assert!(nodes.iter().all(|(depth, node)| *depth == node.0));
+            let depths: Vec<_> = nodes.into_iter().map(|(depth, _)| depth).collect();
+            similar_asserts::assert_eq!(depths, expected_depths);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_new_multi() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+        let bfs = Bfs::<crate::utils::test::Node>::new_multi([10, 20, 10], 3, false);
+        let nodes = bfs
+            .with_depth()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let nodes: Vec<_> = nodes
+            .into_iter()
+            .map(|(depth, node)| (depth, node.0))
+            .collect();
+        // the descendants are shared by both roots
+        similar_asserts::assert_eq!(nodes, [(0, 10), (0, 20), (1, 1), (2, 2), (3, 3)]);
+
+        let bfs = Bfs::<crate::utils::test::Node>::new_multi(Vec::<usize>::new(), 3, false);
+        similar_asserts::assert_eq!(bfs.collect::<Vec<_>>().await.len(), 0);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_level_events() -> Result<()> {
+        use crate::r#async::LevelEvent;
+        use crate::utils::test::Node;
+        use futures::StreamExt;
+        let events = Bfs::<Node>::new(0, 2, true)
+            .level_events()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        similar_asserts::assert_eq!(
+            events,
+            [
+                LevelEvent::Node(Node(1)),
+                LevelEvent::Node(Node(1)),
+                LevelEvent::LevelComplete { depth: 1, count: 2 },
+                LevelEvent::Node(Node(2)),
+                LevelEvent::Node(Node(2)),
+                LevelEvent::Node(Node(2)),
+                LevelEvent::Node(Node(2)),
+                LevelEvent::LevelComplete { depth: 2, count: 4 },
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_by_level() -> Result<()> {
+        use futures::StreamExt;
+        let bfs = Bfs::<crate::utils::test::Node>::new_multi([10, 20], 3, true);
+        let levels = bfs
+            .by_level()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let levels: Vec<_> = levels
+            .into_iter()
+            .map(|(depth, nodes)| (depth, nodes.into_iter().map(|node| node.0).collect()))
+            .collect();
+        similar_asserts::assert_eq!(
+            levels,
+            [
+                (0, vec![10, 20]),
+                (1, vec![1; 4]),
+                (2, vec![2; 8]),
+                (3, vec![3; 16]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_diamond_at_max_depth_is_yielded_once() -> Result<()> {
+        use crate::r#async::{AdjacencyMap, AdjacencyNode};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        // both parents of 3 reach it at the maximum depth
+        let graph: AdjacencyMap<u32> =
+            AdjacencyMap::from([(0, vec![1, 2]), (1, vec![3]), (2, vec![3])]);
+        let root = AdjacencyNode::new(Arc::new(graph), 0);
+        let bfs = Bfs::<AdjacencyNode<u32>>::new(root, 2, false);
+        let nodes = bfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let keys: Vec<_> = nodes.into_iter().map(AdjacencyNode::into_key).collect();
+        similar_asserts::assert_eq!(keys, [1, 2, 3]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_lower_depth_control() -> Result<()> {
+        use crate::r#async::{DepthControl, Traverse};
+        use futures::StreamExt;
+        let control = DepthControl::new(None);
+        let mut bfs = Bfs::<crate::utils::test::Node>::new_with_control(0, control.clone(), true)
+            .with_depth();
+        let mut depths = vec![];
+        while let Some(node) = bfs.next().await {
+            depths.push(node?.0);
+            if depths.len() == 3 {
+                control.set(2);
+            }
+        }
+        // the children of the node at depth 2 that was expanded before still drain
+        similar_asserts::assert_eq!(depths, [1, 1, 2, 2, 2, 2, 3, 3]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_from_frontier() -> Result<()> {
+        use crate::utils::test::Node;
+        use futures::StreamExt;
+        for allow_circles in [true, false] {
+            let expected = Bfs::<Node>::new(0, 3, allow_circles)
+                .collect::<Vec<_>>()
+                .await;
+            // the children of the root
+            let nodes = Box::pin(futures::stream::iter([Ok(Node(1)), Ok(Node(1))]));
+            let output = Bfs::<Node>::from_frontier(nodes, 1, 3, allow_circles)
+                .collect::<Vec<_>>()
+                .await;
+            similar_asserts::assert_eq!(output, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_for_each_ref() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+        let expected = Bfs::<crate::utils::test::Node>::new(0, 3, true)
+            .with_depth()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut output = Vec::new();
+        Bfs::<crate::utils::test::Node>::new(0, 3, true)
+            .for_each_ref(|node, depth| output.push((depth, node.clone())))
+            .await?;
+        similar_asserts::assert_eq!(output, expected);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_count() -> Result<()> {
+        let bfs = Bfs::<crate::utils::test::Node>::new(0, 3, true);
+        similar_asserts::assert_eq!(bfs.count().await?, 2 + 4 + 8);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_allow_revisit() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(&'static str);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = std::convert::Infallible;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                // diamond, where "c" is reached via both "a" and "b"
+                let children = match self.0 {
+                    "root" => vec!["a", "b"],
+                    "a" | "b" => vec!["c"],
+                    "c" => vec!["d"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(Self).map(Result::Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+
+            fn allow_revisit(&self) -> bool {
+                self.0 == "c"
+            }
+        }
+
+        let bfs = Bfs::<NamedNode>::new(NamedNode("root"), None, false);
+        let nodes = bfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let names: Vec<_> = nodes.iter().map(|node| node.0).collect();
+        // "c" is yielded and expanded twice, but "d" only once
+        similar_asserts::assert_eq!(names, ["a", "b", "c", "c", "d"]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_is_terminal() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(&'static str);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = std::convert::Infallible;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                // cycle, where "back" leads back to "a"
+                let children = match self.0 {
+                    "root" => vec!["a"],
+                    "a" => vec!["b", "c"],
+                    "b" => vec!["back"],
+                    "back" => vec!["a"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(Self).map(Result::Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+
+            fn is_terminal(&self) -> bool {
+                self.0 == "back"
+            }
+        }
+
+        // the cycle is cut without tracking visited nodes
+        let bfs = Bfs::<NamedNode>::new(NamedNode("root"), None, true);
+        let nodes = bfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let names: Vec<_> = nodes.iter().map(|node| node.0).collect();
+        similar_asserts::assert_eq!(names, ["a", "b", "c", "back"]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_in_depth_range() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+
+        async fn depths<R>(range: R) -> Result<Vec<usize>>
+        where
+            R: std::ops::RangeBounds<usize>,
+        {
+            let nodes = Bfs::<crate::utils::test::Node>::in_depth_range(0, range, true)
+                .with_depth()
+                .take(8)
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(nodes.into_iter().map(|(depth, _)| depth).collect())
+        }
+
+        similar_asserts::assert_eq!(depths(1..=2).await?, [1, 1, 2, 2, 2, 2]);
+        similar_asserts::assert_eq!(depths(2..3).await?, [2, 2, 2, 2]);
+        similar_asserts::assert_eq!(depths(0..2).await?, [0, 1, 1]);
+        // without an upper bound, the depth is unlimited
+        similar_asserts::assert_eq!(depths(3..).await?, [3; 8]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_restart() -> Result<()> {
+        use futures::StreamExt;
+        for allow_circles in [true, false] {
+            let mut bfs = Bfs::<crate::utils::test::Node>::new(0, 3, allow_circles);
+            // restarting a partially consumed traversal starts from the root again
+            bfs.next().await.transpose()?;
+            let restarted = bfs.restart();
+            let first = bfs.collect::<Vec<_>>().await;
+            let second = restarted.collect::<Vec<_>>().await;
+            similar_asserts::assert_eq!(first[..], second[1..]);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_max_depth_reached() -> Result<()> {
+        use futures::StreamExt;
+        let mut bfs =
+            Bfs::<crate::utils::test::Node>::new(0, None, false).prune(|_node, depth| depth == 2);
+        similar_asserts::assert_eq!(bfs.max_depth_reached(), None);
+        while let Some(node) = bfs.next().await {
+            node?;
+        }
+        similar_asserts::assert_eq!(bfs.max_depth_reached(), Some(2));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_was_truncated() -> Result<()> {
+        use futures::StreamExt;
+        let mut bfs = Bfs::<crate::utils::test::Node>::new(0, 2, false);
+        similar_asserts::assert_eq!(bfs.was_truncated(), false);
+        while let Some(node) = bfs.next().await {
+            node?;
+        }
+        similar_asserts::assert_eq!(bfs.was_truncated(), true);
+
+        // the nodes at the maximum depth are never reached
+        let mut bfs =
+            Bfs::<crate::utils::test::Node>::new(0, 3, false).prune(|_node, depth| depth == 2);
+        while let Some(node) = bfs.next().await {
+            node?;
+        }
+        similar_asserts::assert_eq!(bfs.was_truncated(), false);
+
+        // the children of the root are beyond the maximum depth
+        let bfs = Bfs::<crate::utils::test::Node>::new(0, 0, false);
+        similar_asserts::assert_eq!(bfs.was_truncated(), true);
+        similar_asserts::assert_eq!(bfs.restart().was_truncated(), true);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_stats() -> Result<()> {
+        use futures::StreamExt;
+        let mut bfs = Bfs::<crate::utils::test::Node>::new(0, 3, true).max_concurrency(2);
+        similar_asserts::assert_eq!(*bfs.stats(), crate::r#async::Stats::default());
+        while let Some(node) = bfs.next().await {
+            node?;
+        }
+        let stats = bfs.into_stats();
+        similar_asserts::assert_eq!(stats.nodes_emitted, 14);
+        // the root and all nodes above the maximum depth are expanded
+        similar_asserts::assert_eq!(stats.children_calls, 7);
+        similar_asserts::assert_eq!(stats.max_frontier_size, 2);
+        similar_asserts::assert_eq!(stats.max_stack_depth, 0);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_size_hint() -> Result<()> {
+        use futures::{Stream, StreamExt};
+        let mut bfs = Bfs::<crate::utils::test::Node>::new_multi([10, 20], 3, false);
+        similar_asserts::assert_eq!(bfs.size_hint(), (2, None));
+        while bfs.next().await.is_some() {}
+        similar_asserts::assert_eq!(bfs.size_hint(), (0, Some(0)));
+
+        let bfs = Bfs::<crate::utils::test::Node>::new_multi([10, 20], 3, false).max_nodes(1);
+        // errors could be yielded in addition to the nodes
+        similar_asserts::assert_eq!(bfs.size_hint(), (1, None));
+        let bfs = bfs.skip_errors(true);
+        similar_asserts::assert_eq!(bfs.size_hint(), (1, Some(1)));
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_snapshot_resume() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, Snapshot};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug, serde::Serialize, serde::Deserialize)]
+        struct Label(String);
+
+        #[async_trait::async_trait]
+        impl Node for Label {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let nodes = ['a', 'b'].map(|c| Ok(Self(format!("{}{c}", self.0))));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        async fn labels<S>(stream: S) -> Result<Vec<String>>
+        where
+            S: futures::Stream<Item = Result<Label, crate::utils::test::Error>>,
+        {
+            let nodes = stream
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(nodes.into_iter().map(|node| node.0).collect())
+        }
+
+        let expected = labels(Bfs::<Label>::new(Label(String::new()), 3, false)).await?;
+
+        let mut bfs = Bfs::<Label>::new(Label(String::new()), 3, false);
+        let mut before = vec![];
+        for _ in 0..3 {
+            before.push(bfs.next().await.unwrap()?.0);
+        }
+        // the child stream of "a" is partially consumed
+        similar_asserts::assert_eq!(before, ["a", "b", "aa"]);
+
+        let snapshot = serde_json::to_string(&bfs.snapshot())?;
+        drop(bfs);
+        let snapshot: Snapshot<Label> = serde_json::from_str(&snapshot)?;
+        let after = labels(Bfs::resume(snapshot)).await?;
+
+        similar_asserts::assert_eq!([before, after].concat(), expected);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_deterministic_order() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::hash::BuildHasher;
+        use std::sync::Arc;
+        use tokio::time::{sleep, Duration};
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct Jittered(String);
+
+        #[async_trait::async_trait]
+        impl Node for Jittered {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                // the completion order differs between nodes and runs,
+                // since each random state uses different keys
+                let jitter = std::collections::hash_map::RandomState::new().hash_one(&self);
+                sleep(Duration::from_millis(jitter % 20)).await;
+                let nodes = ['a', 'b', 'c'].map(|c| Ok(Self(format!("{}{c}", self.0))));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let mut outputs = vec![];
+        for _ in 0..10 {
+            let bfs = Bfs::<Jittered>::new(Jittered(String::new()), 3, true);
+            let labels = bfs
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .map(|node| node.map(|node| node.0))
+                .collect::<Result<Vec<_>, _>>()?;
+            outputs.push(labels.join(","));
+        }
+        assert!(outputs.windows(2).all(|pair| pair[0] == pair[1]));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_max_children_per_node() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        /// A node with infinitely many children, numbered from one.
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct Infinite(usize);
+
+        #[async_trait::async_trait]
+        impl Node for Infinite {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let nodes = (1..).map(Self).map(Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let bfs = Bfs::<Infinite>::new(Infinite(0), 2, true).max_children_per_node(5);
+        let nodes = bfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        // each of the 5 children has 5 children itself
+        similar_asserts::assert_eq!(nodes.len(), 5 + 5 * 5);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_reverse_children() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        /// A binary tree in heap order, where node `n` has the children `2n + 1` and `2n + 2`.
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct Tree(usize);
+
+        #[async_trait::async_trait]
+        impl Node for Tree {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let nodes = [2 * self.0 + 1, 2 * self.0 + 2]
+                    .into_iter()
+                    .map(Self)
+                    .map(Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        for (reverse_children, expected) in
+            [(false, [1, 2, 3, 4, 5, 6]), (true, [2, 1, 6, 5, 4, 3])]
+        {
+            let bfs = Bfs::<Tree>::new(Tree(0), 2, false).reverse_children(reverse_children);
+            let nodes = bfs
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            let nodes: Vec<_> = nodes.into_iter().map(|node| node.0).collect();
+            similar_asserts::assert_eq!(nodes, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_peek_depth() -> Result<()> {
+        use crate::r#async::Traverse;
+        let mut bfs = Bfs::<crate::utils::test::Node>::new_multi([10, 20], 2, true);
+        similar_asserts::assert_eq!(bfs.peek_depth(), Some(0));
+        similar_asserts::assert_eq!(bfs.frontier_len(), 2);
+
+        let mut num_nodes = 0;
+        while let Some(peeked) = bfs.peek_depth() {
+            let Some(node) = futures::future::poll_fn(|cx| {
+                std::pin::Pin::new(&mut bfs).poll_next_with_depth(cx)
+            })
+            .await
+            else {
+                break;
+            };
+            let (depth, _) = node?;
+            // the peeked depth is a lower bound, since child streams are not polled
+            assert!(peeked <= depth, "peeked {peeked} for depth {depth}");
+            num_nodes += 1;
+        }
+        similar_asserts::assert_eq!(num_nodes, 14);
+        similar_asserts::assert_eq!(bfs.peek_depth(), None);
+        similar_asserts::assert_eq!(bfs.frontier_len(), 0);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_is_visited() -> Result<()> {
+        use crate::utils::test::Node;
+        use futures::StreamExt;
+        for (allow_circles, expected_count) in [(false, 4), (true, 0)] {
+            let mut bfs = Bfs::<Node>::new(0, 3, allow_circles);
+            assert!(!bfs.is_visited(&Node(1)));
+            while let Some(node) = bfs.next().await {
+                node?;
+            }
+            similar_asserts::assert_eq!(bfs.is_visited(&Node(1)), !allow_circles);
+            assert!(!bfs.is_visited(&Node(4)));
+            similar_asserts::assert_eq!(bfs.visited_count(), expected_count);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_wrap_errors() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, Traverse};
+        use crate::utils::test::Error;
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec![Ok("failing"), Err(Error), Ok("ok")],
+                    "failing" => return Err(Error),
+                    "ok" => vec![Ok("ok child")],
+                    _ => vec![],
+                };
+                let nodes = children
+                    .into_iter()
+                    .map(|child| child.map(|name| Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let root = NamedNode("root".into());
+        let bfs = Bfs::<NamedNode>::new(root, None, false);
+        let items: Vec<_> = bfs
+            .wrap_errors()
+            .map(|item| {
+                item.map(|node| node.0).map_err(|err| {
+                    assert_eq!(err.source, Error);
+                    (err.node.map(|node| node.0), err.depth)
+                })
+            })
+            .collect()
+            .await;
+        similar_asserts::assert_eq!(
+            items,
+            [
+                Ok("failing".to_string()),
+                Err((Some("root".to_string()), 1)),
+                Ok("ok".to_string()),
+                Err((Some("failing".to_string()), 2)),
+                Ok("ok child".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_is_fused() -> Result<()> {
+        use futures::stream::FusedStream;
+        use futures::StreamExt;
+
+        let mut bfs = Bfs::<crate::utils::test::Node>::new(0, 2, true);
+        assert!(!bfs.is_terminated());
+        similar_asserts::assert_eq!(bfs.by_ref().count().await, 6);
+        assert!(bfs.is_terminated());
+        assert!(bfs.next().await.is_none());
+        assert!(bfs.next().await.is_none());
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_expand_once() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(&'static str);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = std::convert::Infallible;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                // diamond, where "c" is reached via both "a" and "b"
+                let children = match self.0 {
+                    "root" => vec!["a", "b"],
+                    "a" | "b" => vec!["c"],
+                    "c" => vec!["d"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(Self).map(Result::Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let mut bfs = Bfs::<NamedNode>::new(NamedNode("root"), None, true).expand_once(true);
+        let nodes = bfs
+            .by_ref()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let names: Vec<_> = nodes.iter().map(|node| node.0).collect();
+        similar_asserts::assert_eq!(names, ["a", "b", "c", "c", "d"]);
+        similar_asserts::assert_eq!(bfs.visited_count(), 5);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bfs_debug_snapshot() -> Result<()> {
+        use crate::r#async::TraversalSnapshot;
+        use futures::StreamExt;
+
+        let mut bfs = Bfs::<crate::utils::test::Node>::new(0, 2, true);
+        similar_asserts::assert_eq!(
+            bfs.debug_snapshot(),
+            TraversalSnapshot {
+                depth: Some(1),
+                pending_children: 0,
+                stack_depth: 0,
+                frontier_len: 1,
+                nodes_emitted: 0,
+            }
+        );
+        bfs.next().await.transpose()?;
+        similar_asserts::assert_eq!(
+            bfs.debug_snapshot(),
+            TraversalSnapshot {
+                depth: Some(1),
+                pending_children: 1,
+                stack_depth: 0,
+                frontier_len: 2,
+                nodes_emitted: 1,
+            }
+        );
+        while bfs.next().await.transpose()?.is_some() {}
+        similar_asserts::assert_eq!(
+            bfs.debug_snapshot(),
+            TraversalSnapshot {
+                nodes_emitted: 6,
+                ..TraversalSnapshot::default()
+            }
+        );
+        Ok(())
+    }
 }
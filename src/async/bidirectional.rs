@@ -0,0 +1,353 @@
+use super::{BidirectionalNode, Node, Path};
+
+use futures::stream::{Stream, StreamExt};
+use futures::{Future, FutureExt};
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// A node where the forward and the backward search met,
+/// together with the paths from the `start` and from the `goal` to it.
+type Meeting<N> = (Arc<Path<N>>, Arc<Path<N>>);
+
+type SearchFut<N> =
+    Pin<Box<dyn Future<Output = Result<Vec<Meeting<N>>, <N as Node>::Error>> + Send + 'static>>;
+
+/// One direction of a [`BidirectionalBfs`].
+///
+/// [`BidirectionalBfs`]: struct@crate::async::BidirectionalBfs
+struct Frontier<N>
+where
+    N: Node,
+{
+    /// Whether to follow [`Node::children`] or [`BidirectionalNode::parents`].
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    /// [`BidirectionalNode::parents`]: fn@crate::async::BidirectionalNode::parents
+    forward: bool,
+    depth: usize,
+    level: Vec<Arc<Path<N>>>,
+    /// The visited nodes, together with the path to reach them.
+    visited: HashMap<N::Key, Arc<Path<N>>>,
+}
+
+impl<N> Frontier<N>
+where
+    N: BidirectionalNode + Send + Sync + 'static,
+    N::Key: Send + Sync,
+    N::Error: Send + 'static,
+{
+    fn new(root: Arc<Path<N>>, forward: bool) -> Self {
+        let visited = HashMap::from([(root.node().key(), Arc::clone(&root))]);
+        Self {
+            forward,
+            depth: 0,
+            level: vec![root],
+            visited,
+        }
+    }
+
+    /// Expands the current level, returning the nodes already visited by `other`.
+    ///
+    /// The returned meetings are the ones with the shortest combined path.
+    async fn expand(&mut self, other: &Self) -> Result<Vec<Meeting<N>>, N::Error> {
+        self.depth += 1;
+        let depth = self.depth;
+        let forward = self.forward;
+        let level = std::mem::take(&mut self.level);
+
+        let streams = futures::future::try_join_all(level.iter().map(|path| {
+            let node = Arc::clone(path.node());
+            if forward {
+                node.children(depth)
+            } else {
+                node.parents(depth)
+            }
+        }))
+        .await?;
+
+        let mut meetings: Vec<Meeting<N>> = Vec::new();
+        for (parent, mut stream) in level.iter().zip(streams) {
+            while let Some(node) = stream.next().await {
+                let node = node?;
+                let key = node.key();
+                if self.visited.contains_key(&key) {
+                    continue;
+                }
+                let path = Arc::new(Path::child(parent, Arc::new(node)));
+                if let Some(other_path) = other.visited.get(&key) {
+                    let length = other_path.depth();
+                    match meetings.first().map(|(_, other)| other.depth()) {
+                        Some(shortest) if shortest < length => {}
+                        Some(shortest) if shortest > length => {
+                            meetings = vec![(Arc::clone(&path), Arc::clone(other_path))];
+                        }
+                        _ => meetings.push((Arc::clone(&path), Arc::clone(other_path))),
+                    }
+                }
+                self.visited.insert(key, Arc::clone(&path));
+                self.level.push(path);
+            }
+        }
+        Ok(meetings)
+    }
+}
+
+/// Searches from `start` and `goal` until the frontiers meet.
+async fn search<N>(
+    start: Arc<N>,
+    goal: Arc<N>,
+    max_depth: Option<usize>,
+) -> Result<Vec<Meeting<N>>, N::Error>
+where
+    N: BidirectionalNode + Send + Sync + 'static,
+    N::Key: Send + Sync,
+    N::Error: Send + 'static,
+{
+    let start = Arc::new(Path::root(start));
+    let goal = Arc::new(Path::root(goal));
+    if start.node().key() == goal.node().key() {
+        return Ok(vec![(start, goal)]);
+    }
+
+    let mut forward = Frontier::new(start, true);
+    let mut backward = Frontier::new(goal, false);
+    loop {
+        if forward.level.is_empty() || backward.level.is_empty() {
+            return Ok(vec![]);
+        }
+        if max_depth.is_some_and(|max_depth| forward.depth + backward.depth >= max_depth) {
+            return Ok(vec![]);
+        }
+        // expand the smaller frontier
+        let meetings = if forward.level.len() <= backward.level.len() {
+            forward.expand(&backward).await?
+        } else {
+            backward
+                .expand(&forward)
+                .await?
+                .into_iter()
+                .map(|(to_goal, to_start)| (to_start, to_goal))
+                .collect()
+        };
+        if !meetings.is_empty() {
+            return Ok(meetings);
+        }
+    }
+}
+
+/// Asynchronous bidirectional breadth-first search for types implementing
+/// the [`BidirectionalNode`] trait.
+///
+/// Searches forward from the `start` node using [`Node::children`] and backward from the
+/// `goal` node using [`BidirectionalNode::parents`], one level at a time,
+/// always expanding the smaller of the two frontiers.
+/// The search stops at the first level where the frontiers meet.
+///
+/// Yields each meeting node together with the path from `start` to `goal` through it.
+/// All yielded paths are shortest paths, and no two share the same meeting node.
+/// Yields nothing when the `goal` can not be reached.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{BidirectionalBfs, BidirectionalNode, Node, NodeStream};
+///
+/// #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+/// struct Number(u32);
+///
+/// #[async_trait::async_trait]
+/// impl Node for Number {
+///     type Error = std::convert::Infallible;
+///
+///     async fn children(
+///         self: std::sync::Arc<Self>,
+///         _depth: usize
+///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+///         let nodes = [self.0 + 1, self.0 * 2].into_iter().map(Self).map(Result::Ok);
+///         Ok(Box::pin(futures::stream::iter(nodes)))
+///     }
+/// }
+///
+/// #[async_trait::async_trait]
+/// impl BidirectionalNode for Number {
+///     async fn parents(
+///         self: std::sync::Arc<Self>,
+///         _depth: usize
+///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+///         let mut nodes = vec![self.0.saturating_sub(1)];
+///         if self.0 % 2 == 0 {
+///             nodes.push(self.0 / 2);
+///         }
+///         let nodes = nodes.into_iter().map(Self).map(Result::Ok);
+///         Ok(Box::pin(futures::stream::iter(nodes)))
+///     }
+/// }
+///
+/// let result = tokio_test::block_on(async {
+///     let search = BidirectionalBfs::<Number>::new(Number(1), Number(10), None);
+///     let (_, path) = search.collect::<Vec<_>>().await.remove(0).unwrap();
+///     path.into_iter().map(|n| n.0).collect::<Vec<_>>()
+/// });
+/// assert_eq!(result, [1, 2, 4, 5, 10]);
+/// ```
+///
+/// [`BidirectionalNode`]: trait@crate::async::BidirectionalNode
+/// [`BidirectionalNode::parents`]: fn@crate::async::BidirectionalNode::parents
+/// [`Node::children`]: fn@crate::async::Node::children
+#[allow(clippy::module_name_repetitions)]
+pub struct BidirectionalBfs<N>
+where
+    N: Node,
+{
+    search: Option<SearchFut<N>>,
+    meetings: VecDeque<Meeting<N>>,
+}
+
+impl<N> BidirectionalBfs<N>
+where
+    N: BidirectionalNode + Send + Sync + 'static,
+    N::Key: Send + Sync,
+    N::Error: Send + 'static,
+{
+    #[inline]
+    /// Creates a new [`BidirectionalBfs`] stream.
+    ///
+    /// The search will be performed between the `start` and the `goal` node
+    /// for paths of length up to `max_depth`.
+    ///
+    /// [`BidirectionalBfs`]: struct@crate::async::BidirectionalBfs
+    pub fn new<S, G, D>(start: S, goal: G, max_depth: D) -> Self
+    where
+        S: Into<N>,
+        G: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        let start = Arc::new(start.into());
+        let goal = Arc::new(goal.into());
+        Self {
+            search: Some(Box::pin(search(start, goal, max_depth.into()))),
+            meetings: VecDeque::new(),
+        }
+    }
+}
+
+impl<N> Unpin for BidirectionalBfs<N> where N: Node {}
+
+impl<N> Stream for BidirectionalBfs<N>
+where
+    N: BidirectionalNode + Clone,
+{
+    type Item = Result<(N, Vec<N>), N::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Some(search) = this.search.as_mut() {
+            let meetings = futures::ready!(search.poll_unpin(cx));
+            this.search = None;
+            match meetings {
+                Ok(meetings) => this.meetings.extend(meetings),
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            }
+        }
+
+        let Some((to_start, to_goal)) = this.meetings.pop_front() else {
+            return Poll::Ready(None);
+        };
+        let meeting = N::clone(to_start.node());
+        let mut path = to_start.to_vec();
+        // the meeting node is already the last node of the path from the start
+        path.extend(to_goal.iter().skip(1).map(|node| N::clone(node)));
+        Poll::Ready(Some(Ok((meeting, path))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BidirectionalBfs;
+    use crate::r#async::{BidirectionalNode, Node, NodeStream};
+    use anyhow::Result;
+    use futures::StreamExt;
+    use std::sync::Arc;
+
+    /// A diamond `A -> {B, C} -> D -> E`.
+    #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    struct Diamond(char);
+
+    fn stream(nodes: &[char]) -> NodeStream<Diamond, crate::utils::test::Error> {
+        let nodes: Vec<_> = nodes.iter().copied().map(Diamond).map(Ok).collect();
+        Box::pin(futures::stream::iter(nodes))
+    }
+
+    #[async_trait::async_trait]
+    impl Node for Diamond {
+        type Error = crate::utils::test::Error;
+
+        async fn children(
+            self: Arc<Self>,
+            _depth: usize,
+        ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+            Ok(match self.0 {
+                'A' => stream(&['B', 'C']),
+                'B' | 'C' => stream(&['D']),
+                'D' => stream(&['E']),
+                _ => stream(&[]),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl BidirectionalNode for Diamond {
+        async fn parents(
+            self: Arc<Self>,
+            _depth: usize,
+        ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+            Ok(match self.0 {
+                'B' | 'C' => stream(&['A']),
+                'D' => stream(&['B', 'C']),
+                'E' => stream(&['D']),
+                _ => stream(&[]),
+            })
+        }
+    }
+
+    async fn meetings(search: BidirectionalBfs<Diamond>) -> Result<Vec<(char, String)>> {
+        let meetings = search
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(meetings
+            .into_iter()
+            .map(|(meeting, path)| (meeting.0, path.into_iter().map(|node| node.0).collect()))
+            .collect())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bidirectional_bfs_diamond() -> Result<()> {
+        let search = BidirectionalBfs::new(Diamond('A'), Diamond('D'), None);
+        similar_asserts::assert_eq!(
+            meetings(search).await?,
+            [('B', "ABD".to_string()), ('C', "ACD".to_string())]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bidirectional_bfs_unreachable() -> Result<()> {
+        let search = BidirectionalBfs::new(Diamond('E'), Diamond('A'), None);
+        similar_asserts::assert_eq!(meetings(search).await?, []);
+        let search = BidirectionalBfs::new(Diamond('A'), Diamond('E'), 2);
+        similar_asserts::assert_eq!(meetings(search).await?, []);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bidirectional_bfs_same_node() -> Result<()> {
+        let search = BidirectionalBfs::new(Diamond('A'), Diamond('A'), None);
+        similar_asserts::assert_eq!(meetings(search).await?, [('A', "A".to_string())]);
+        Ok(())
+    }
+}
@@ -0,0 +1,350 @@
+use super::{Bfs, Dfs, KeyedNode, Node, VisitedSet};
+
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use std::sync::Arc;
+
+macro_rules! builder {
+    ($builder:ident, $stream:ident) => {
+        #[doc = concat!("Builder for a [`", stringify!($stream), "`] stream.")]
+        ///
+        /// Created by
+        #[doc = concat!("[`", stringify!($stream), "::builder`].")]
+        /// By default, the traversal has no depth limit, circles are not allowed,
+        /// and the root is not yielded.
+        ///
+        #[doc = concat!("[`", stringify!($stream), "`]: struct@crate::async::", stringify!($stream))]
+        #[doc = concat!(
+            "[`", stringify!($stream), "::builder`]: fn@crate::async::", stringify!($stream), "::builder"
+        )]
+        #[allow(clippy::module_name_repetitions)]
+        #[must_use = "builders do nothing unless built"]
+        pub struct $builder<N, V = HashSet<<N as KeyedNode>::Key>> {
+            root: N,
+            max_depth: Option<usize>,
+            allow_circles: bool,
+            include_root: bool,
+            initial_depth: usize,
+            visited: V,
+        }
+
+        impl<N> $builder<N>
+        where
+            N: Node,
+        {
+            #[inline]
+            pub(super) fn new(root: N) -> Self {
+                Self {
+                    root,
+                    max_depth: None,
+                    allow_circles: false,
+                    include_root: false,
+                    initial_depth: 0,
+                    visited: HashSet::new(),
+                }
+            }
+
+            #[inline]
+            /// Tracks the keys of the visited nodes in a [`HashSet`] using the given `hasher`.
+            ///
+            /// [`HashSet`]: struct@std::collections::HashSet
+            pub fn hasher<S>(self, hasher: S) -> $builder<N, HashSet<N::Key, S>>
+            where
+                S: BuildHasher,
+            {
+                self.visited(HashSet::with_hasher(hasher))
+            }
+        }
+
+        impl<N, S> $builder<N, HashSet<N::Key, S>>
+        where
+            N: Node,
+            S: BuildHasher,
+        {
+            #[inline]
+            /// Pre-allocates the [`HashSet`] of visited nodes for at least `capacity` nodes.
+            ///
+            /// This avoids rehashing while the set grows, if the number of nodes is roughly known.
+            /// Without a hint, the set starts empty and grows as nodes are visited.
+            /// Can be combined with a custom [`hasher`].
+            ///
+            /// [`HashSet`]: struct@std::collections::HashSet
+            #[doc = concat!("[`hasher`]: fn@crate::async::", stringify!($builder), "::hasher")]
+            pub fn visited_capacity_hint(mut self, capacity: usize) -> Self {
+                self.visited.reserve(capacity);
+                self
+            }
+        }
+
+        impl<N, V> $builder<N, V>
+        where
+            N: Node,
+        {
+            #[inline]
+            /// Limits the traversal to nodes up to depth `max_depth`.
+            pub fn max_depth<D>(mut self, max_depth: D) -> Self
+            where
+                D: Into<Option<usize>>,
+            {
+                self.max_depth = max_depth.into();
+                self
+            }
+
+            #[inline]
+            /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+            pub fn allow_circles(mut self, allow_circles: bool) -> Self {
+                self.allow_circles = allow_circles;
+                self
+            }
+
+            #[inline]
+            /// Yields the root at depth 0 before its descendants.
+            ///
+            /// The root is tracked as visited either way, hence it is not yielded again
+            /// when it is reached via a cycle. Together with a `max_depth` of 0,
+            /// only the root is yielded.
+            pub fn include_root(mut self, include_root: bool) -> Self {
+                self.include_root = include_root;
+                self
+            }
+
+            #[inline]
+            /// Starts the traversal with the root at `initial_depth` instead of 0.
+            ///
+            /// The children of the root are requested at `initial_depth + 1`, and all depths,
+            /// including the `max_depth` and the minimum depth, are absolute.
+            /// This allows to continue a traversal of another graph at the depth it reached.
+            pub fn initial_depth(mut self, initial_depth: usize) -> Self {
+                self.initial_depth = initial_depth;
+                self
+            }
+
+            #[inline]
+            /// Tracks the visited nodes in the given [`VisitedSet`].
+            ///
+            /// [`VisitedSet`]: trait@crate::async::VisitedSet
+            pub fn visited<W>(self, visited: W) -> $builder<N, W>
+            where
+                W: VisitedSet<N>,
+            {
+                $builder {
+                    root: self.root,
+                    max_depth: self.max_depth,
+                    allow_circles: self.allow_circles,
+                    include_root: self.include_root,
+                    initial_depth: self.initial_depth,
+                    visited,
+                }
+            }
+        }
+
+        impl<N, V> $builder<N, V>
+        where
+            N: Node + Send + Unpin + 'static,
+            N::Error: Send + 'static,
+            V: VisitedSet<N>,
+        {
+            #[inline]
+            #[doc = concat!("Builds the [`", stringify!($stream), "`] stream.")]
+            ///
+            /// Further options can be configured on the stream before it is polled.
+            ///
+            #[doc = concat!("[`", stringify!($stream), "`]: struct@crate::async::", stringify!($stream))]
+            pub fn build(self) -> $stream<N, V> {
+                $stream::from_roots(
+                    [Arc::new(self.root)],
+                    self.max_depth,
+                    self.allow_circles,
+                    self.visited,
+                    self.include_root,
+                    self.initial_depth,
+                )
+            }
+        }
+    };
+}
+
+builder!(BfsBuilder, Bfs);
+builder!(DfsBuilder, Dfs);
+
+#[cfg(test)]
+mod tests {
+    use crate::r#async::{Bfs, Dfs, Traverse};
+    use crate::utils::test::{r#async::AllVisited, Node};
+    use anyhow::Result;
+    use futures::StreamExt;
+
+    async fn depths<S>(stream: S) -> Result<Vec<usize>>
+    where
+        S: Traverse<Node = Node>,
+    {
+        let nodes = stream
+            .with_depth()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(nodes.into_iter().map(|(depth, _)| depth).collect())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_builder_defaults_match_new() -> Result<()> {
+        let bfs = Bfs::<Node>::builder(0).max_depth(3).build();
+        similar_asserts::assert_eq!(depths(bfs).await?, [1, 2, 3]);
+        let dfs = Dfs::<Node>::builder(0).max_depth(3).build();
+        similar_asserts::assert_eq!(depths(dfs).await?, [1, 2, 3]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_builder_visited() -> Result<()> {
+        let bfs = Bfs::<Node>::builder(0)
+            .max_depth(2)
+            .hasher(std::collections::hash_map::RandomState::new())
+            .allow_circles(true)
+            .build();
+        similar_asserts::assert_eq!(depths(bfs).await?, [1, 1, 2, 2, 2, 2]);
+        let dfs = Dfs::<Node>::builder(0)
+            .max_depth(2)
+            .visited(AllVisited)
+            .build();
+        similar_asserts::assert_eq!(depths(dfs).await?, Vec::<usize>::new());
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_builder_visited_capacity_hint() -> Result<()> {
+        let bfs = Bfs::<Node>::builder(0).max_depth(2).build();
+        // without a hint, the set only grows for the root
+        assert!(bfs.into_visited().capacity() < 1000);
+        let bfs = Bfs::<Node>::builder(0)
+            .max_depth(2)
+            .hasher(std::collections::hash_map::RandomState::new())
+            .visited_capacity_hint(1000)
+            .build();
+        assert!(bfs.into_visited().capacity() >= 1000);
+        // the hint does not affect the traversal
+        let dfs = Dfs::<Node>::builder(0)
+            .max_depth(2)
+            .allow_circles(true)
+            .visited_capacity_hint(1000)
+            .build();
+        similar_asserts::assert_eq!(depths(dfs).await?, [1, 2, 2, 1, 2, 2]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_builder_include_root() -> Result<()> {
+        for (max_depth, expected) in [(0, vec![0]), (1, vec![0, 1, 1])] {
+            let bfs = Bfs::<Node>::builder(0)
+                .max_depth(max_depth)
+                .allow_circles(true)
+                .include_root(true)
+                .build();
+            similar_asserts::assert_eq!(depths(bfs).await?, expected);
+            let dfs = Dfs::<Node>::builder(0)
+                .max_depth(max_depth)
+                .allow_circles(true)
+                .include_root(true)
+                .build();
+            similar_asserts::assert_eq!(depths(dfs).await?, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_builder_max_depth_zero() -> Result<()> {
+        for (max_depth, expected) in [(0, vec![]), (1, vec![1, 1])] {
+            let bfs = Bfs::<Node>::new(0, max_depth, true);
+            similar_asserts::assert_eq!(depths(bfs).await?, expected);
+            let dfs = Dfs::<Node>::new(0, max_depth, true);
+            similar_asserts::assert_eq!(depths(dfs).await?, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_builder_include_root_is_visited() -> Result<()> {
+        use crate::r#async::{from_fn, FromFn, NodeStream};
+
+        // a cycle of three nodes leading back to the root
+        let root = from_fn(0usize, |value, _| async move {
+            let stream: NodeStream<usize, std::convert::Infallible> =
+                Box::pin(futures::stream::iter([Ok((value + 1) % 3)]));
+            Ok(stream)
+        });
+        for (include_root, expected) in [(true, vec![0, 1, 2]), (false, vec![1, 2])] {
+            let bfs: Bfs<FromFn<usize, _>> = Bfs::builder(root.clone())
+                .include_root(include_root)
+                .build();
+            let nodes = bfs
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            let values: Vec<_> = nodes.into_iter().map(FromFn::into_value).collect();
+            similar_asserts::assert_eq!(values, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_builder_filter_rejects_root() -> Result<()> {
+        use crate::r#async::AdjacencyNode;
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        let graph = Arc::new(HashMap::from([("a", vec!["b"]), ("b", vec!["c"])]));
+        let root = AdjacencyNode::new(graph, "a");
+        for filter_root in [false, true] {
+            let keep =
+                move |node: &AdjacencyNode<&str>, _depth: usize| !filter_root || *node.key() != "a";
+            let bfs = Bfs::builder(root.clone())
+                .include_root(true)
+                .build()
+                .filter_nodes(keep);
+            let dfs = Dfs::builder(root.clone())
+                .include_root(true)
+                .build()
+                .filter_nodes(keep);
+            let expected = if filter_root {
+                vec![]
+            } else {
+                vec!["a", "b", "c"]
+            };
+            for nodes in [bfs.collect::<Vec<_>>().await, dfs.collect::<Vec<_>>().await] {
+                let keys: Vec<_> = nodes
+                    .into_iter()
+                    .map(|node| node.map(AdjacencyNode::into_key))
+                    .collect::<Result<_, _>>()?;
+                similar_asserts::assert_eq!(keys, expected);
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_builder_initial_depth() -> Result<()> {
+        // the maximum depth is absolute, hence two levels are traversed
+        let bfs = Bfs::<Node>::builder(0)
+            .initial_depth(3)
+            .max_depth(5)
+            .allow_circles(true)
+            .include_root(true)
+            .build();
+        similar_asserts::assert_eq!(depths(bfs).await?, [3, 4, 4, 5, 5, 5, 5]);
+        let dfs = Dfs::<Node>::builder(0)
+            .initial_depth(3)
+            .max_depth(5)
+            .allow_circles(true)
+            .build();
+        similar_asserts::assert_eq!(depths(dfs).await?, [4, 5, 5, 4, 5, 5]);
+        // the root is not expanded beyond the maximum depth
+        let bfs = Bfs::<Node>::builder(0)
+            .initial_depth(3)
+            .max_depth(3)
+            .build();
+        similar_asserts::assert_eq!(depths(bfs).await?, Vec::<usize>::new());
+        Ok(())
+    }
+}
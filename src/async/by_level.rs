@@ -0,0 +1,84 @@
+use super::{Node, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Stream for the [`by_level`] method.
+///
+/// Yields all nodes of a depth at once, together with the depth.
+///
+/// [`by_level`]: fn@crate::async::Bfs::by_level
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct ByLevel<S>
+where
+    S: Traverse,
+{
+    #[pin]
+    inner: S,
+    /// The nodes of the current level.
+    level: Option<(usize, Vec<S::Node>)>,
+    done: bool,
+}
+
+impl<S> ByLevel<S>
+where
+    S: Traverse,
+{
+    pub(super) fn new(inner: S) -> Self {
+        Self {
+            inner,
+            level: None,
+            done: false,
+        }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    ///
+    /// The nodes of the current level that have not been yielded yet are lost.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for ByLevel<S>
+where
+    S: Traverse,
+    S::Node: Clone,
+{
+    type Item = Result<(usize, Vec<S::Node>), <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+        loop {
+            match futures::ready!(this.inner.as_mut().poll_next_with_depth(cx)) {
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                Some(Ok((depth, node))) => {
+                    let node = Arc::unwrap_or_clone(node);
+                    match this.level {
+                        Some((level, nodes)) if *level == depth => nodes.push(node),
+                        // the next level begins, hence the current level is complete
+                        _ => {
+                            let complete = this.level.replace((depth, vec![node]));
+                            if let Some(complete) = complete {
+                                return Poll::Ready(Some(Ok(complete)));
+                            }
+                        }
+                    }
+                }
+                None => {
+                    // flush the last level
+                    *this.done = true;
+                    return Poll::Ready(this.level.take().map(Ok));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use super::{KeyedNode, Node, NodeStream};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::Arc;
+
+#[async_trait]
+/// A node which produces a [`Stream`] of children nodes given a [`NodeContext`],
+/// which holds the ancestors of the node and data shared by the whole traversal.
+///
+/// The shared data allows per-traversal state, such as a rate limiter or a client,
+/// without resorting to globals.
+/// A [`ContextNode`] is traversed by wrapping its root using [`with_context`].
+///
+/// Note that every [`WithContext`] node still holds an `Arc` to the shared data
+/// and an `Arc` to its parent, so nodes that are lightweight indices into a graph
+/// stored elsewhere are not traversed without this overhead.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{with_context, Bfs, ContextNode, NodeContext, NodeStream, WithContext};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+/// struct Number(u32);
+///
+/// #[async_trait::async_trait]
+/// impl ContextNode for Number {
+///     type Data = AtomicUsize;
+///     type Error = std::convert::Infallible;
+///
+///     async fn children(
+///         self: Arc<Self>,
+///         ctx: &NodeContext<Self>,
+///         _depth: usize,
+///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+///         ctx.data().fetch_add(1, Ordering::SeqCst);
+///         let nodes = [Self(2 * self.0), Self(2 * self.0 + 1)].into_iter().map(Ok);
+///         Ok(Box::pin(futures::stream::iter(nodes)))
+///     }
+/// }
+///
+/// let calls = Arc::new(AtomicUsize::new(0));
+/// let root = with_context(Number(1), Arc::clone(&calls));
+/// let bfs: Bfs<WithContext<Number>> = Bfs::new(root, 2, false);
+/// let count = tokio_test::block_on(bfs.count()).unwrap();
+/// assert_eq!(count, 6);
+/// assert_eq!(calls.load(Ordering::SeqCst), 3);
+/// ```
+///
+/// [`Stream`]: trait@futures::stream::Stream
+/// [`NodeContext`]: struct@crate::async::NodeContext
+/// [`ContextNode`]: trait@crate::async::ContextNode
+/// [`with_context`]: fn@crate::async::with_context
+/// [`WithContext`]: struct@crate::async::WithContext
+pub trait ContextNode
+where
+    Self: Sized + KeyedNode + std::fmt::Debug + Send + Sync + 'static,
+{
+    /// The type of the data shared by all nodes of the traversal.
+    type Data: Send + Sync + 'static;
+
+    /// The type of the error when creating the stream fails.
+    type Error: std::fmt::Debug;
+
+    /// Returns a [`NodeStream`] of its children.
+    ///
+    /// The `ctx` holds the ancestors of this node and the shared data.
+    ///
+    /// # Errors
+    ///
+    /// Should return [`Self::Error`] if the stream can not be created.
+    ///
+    /// [`NodeStream`]: type@crate::async::NodeStream
+    /// [`Self::Error`]: type@crate::async::ContextNode::Error
+    async fn children(
+        self: Arc<Self>,
+        ctx: &NodeContext<Self>,
+        depth: usize,
+    ) -> Result<NodeStream<Self, Self::Error>, Self::Error>;
+}
+
+/// Wraps the `root` [`ContextNode`] into a [`Node`], sharing `data` with all of its descendants.
+///
+/// [`ContextNode`]: trait@crate::async::ContextNode
+/// [`Node`]: trait@crate::async::Node
+pub fn with_context<N>(root: N, data: Arc<N::Data>) -> WithContext<N>
+where
+    N: ContextNode,
+{
+    WithContext {
+        node: Arc::new(root),
+        ctx: NodeContext { parent: None, data },
+    }
+}
+
+/// The context a [`ContextNode`] is expanded in.
+///
+/// [`ContextNode`]: trait@crate::async::ContextNode
+pub struct NodeContext<N>
+where
+    N: ContextNode,
+{
+    parent: Option<Arc<WithContext<N>>>,
+    data: Arc<N::Data>,
+}
+
+impl<N> NodeContext<N>
+where
+    N: ContextNode,
+{
+    #[inline]
+    #[must_use]
+    /// Returns the data shared by the whole traversal.
+    pub fn data(&self) -> &N::Data {
+        &self.data
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the parent of the node, or `None` for the root.
+    pub fn parent(&self) -> Option<&N> {
+        self.parent.as_deref().map(WithContext::node)
+    }
+
+    /// Returns an iterator over the ancestors of the node,
+    /// starting with its parent and ending with the root.
+    pub fn ancestors(&self) -> impl Iterator<Item = &N> {
+        std::iter::successors(self.parent.as_deref(), |node| node.ctx.parent.as_deref())
+            .map(WithContext::node)
+    }
+}
+
+impl<N> Clone for NodeContext<N>
+where
+    N: ContextNode,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            parent: self.parent.clone(),
+            data: Arc::clone(&self.data),
+        }
+    }
+}
+
+impl<N> Drop for NodeContext<N>
+where
+    N: ContextNode,
+{
+    fn drop(&mut self) {
+        // drop long chains of ancestors iteratively to not overflow the stack
+        let mut parent = self.parent.take();
+        while let Some(node) = parent {
+            match Arc::try_unwrap(node) {
+                Ok(mut node) => parent = node.ctx.parent.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl<N> std::fmt::Debug for NodeContext<N>
+where
+    N: ContextNode,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeContext")
+            .field("parent", &self.parent())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`Node`] that wraps a [`ContextNode`] together with its [`NodeContext`].
+///
+/// Nodes are identified by the key of the wrapped node.
+///
+/// See [`with_context`] for more details.
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`ContextNode`]: trait@crate::async::ContextNode
+/// [`NodeContext`]: struct@crate::async::NodeContext
+/// [`with_context`]: fn@crate::async::with_context
+pub struct WithContext<N>
+where
+    N: ContextNode,
+{
+    node: Arc<N>,
+    ctx: NodeContext<N>,
+}
+
+impl<N> WithContext<N>
+where
+    N: ContextNode,
+{
+    #[inline]
+    #[must_use]
+    /// Returns the wrapped node.
+    pub fn node(&self) -> &N {
+        &self.node
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the context of the node.
+    pub fn context(&self) -> &NodeContext<N> {
+        &self.ctx
+    }
+}
+
+impl<N> Clone for WithContext<N>
+where
+    N: ContextNode,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            node: Arc::clone(&self.node),
+            ctx: self.ctx.clone(),
+        }
+    }
+}
+
+impl<N> std::fmt::Debug for WithContext<N>
+where
+    N: ContextNode,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WithContext").field(&self.node).finish()
+    }
+}
+
+impl<N> KeyedNode for WithContext<N>
+where
+    N: ContextNode,
+{
+    type Key = N::Key;
+
+    #[inline]
+    fn key(&self) -> Self::Key {
+        self.node.key()
+    }
+}
+
+#[async_trait]
+impl<N> Node for WithContext<N>
+where
+    N: ContextNode,
+    N::Error: Send + 'static,
+{
+    type Error = N::Error;
+
+    async fn children(
+        self: Arc<Self>,
+        depth: usize,
+    ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+        let children = Arc::clone(&self.node).children(&self.ctx, depth).await?;
+        let ctx = NodeContext {
+            parent: Some(Arc::clone(&self)),
+            data: Arc::clone(&self.ctx.data),
+        };
+        let children = children.map(move |child| {
+            child.map(|child| Self {
+                node: Arc::new(child),
+                ctx: ctx.clone(),
+            })
+        });
+        Ok(Box::pin(children))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{with_context, ContextNode, NodeContext, WithContext};
+    use crate::r#async::{Dfs, NodeStream};
+    use anyhow::Result;
+    use futures::StreamExt;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    struct Number(u32);
+
+    #[async_trait::async_trait]
+    impl ContextNode for Number {
+        type Data = Mutex<Vec<(u32, Vec<u32>)>>;
+        type Error = std::convert::Infallible;
+
+        async fn children(
+            self: Arc<Self>,
+            ctx: &NodeContext<Self>,
+            _depth: usize,
+        ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+            let ancestors = ctx.ancestors().map(|node| node.0).collect();
+            ctx.data().lock().unwrap().push((self.0, ancestors));
+            let nodes = [Self(2 * self.0), Self(2 * self.0 + 1)].into_iter().map(Ok);
+            Ok(Box::pin(futures::stream::iter(nodes)))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_context_node_sees_ancestors_and_data() -> Result<()> {
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let root = with_context(Number(1), Arc::clone(&data));
+        let dfs: Dfs<WithContext<Number>> = Dfs::new(root, 3, false);
+        let output = dfs.collect::<Vec<_>>().await;
+        let output = output.into_iter().collect::<Result<Vec<_>, _>>()?;
+        similar_asserts::assert_eq!(
+            output.iter().map(|node| node.node().0).collect::<Vec<_>>(),
+            [2, 4, 8, 9, 5, 10, 11, 3, 6, 12, 13, 7, 14, 15]
+        );
+        similar_asserts::assert_eq!(
+            output
+                .iter()
+                .find(|node| node.node().0 == 9)
+                .unwrap()
+                .context()
+                .parent(),
+            Some(&Number(4))
+        );
+        let calls = data.lock().unwrap().clone();
+        similar_asserts::assert_eq!(
+            calls,
+            [
+                (1, vec![]),
+                (2, vec![1]),
+                (4, vec![2, 1]),
+                (5, vec![2, 1]),
+                (3, vec![1]),
+                (6, vec![3, 1]),
+                (7, vec![3, 1]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_context_node_drops_deep_chain() {
+        let length = 100_000;
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let mut node = with_context(Number(0), Arc::clone(&data));
+        for id in 1..length {
+            node = WithContext {
+                node: Arc::new(Number(id)),
+                ctx: NodeContext {
+                    parent: Some(Arc::new(node)),
+                    data: Arc::clone(&data),
+                },
+            };
+        }
+        similar_asserts::assert_eq!(node.context().ancestors().count(), length as usize - 1);
+        // the last node holds the only reference to all of its ancestors
+        drop(node);
+    }
+}
@@ -0,0 +1,19 @@
+use super::{Node, Traverse};
+
+/// Consumes the `traversal`, counting the yielded nodes.
+///
+/// Each node is dropped as soon as it is counted.
+pub(super) async fn count<T>(traversal: T) -> Result<usize, <T::Node as Node>::Error>
+where
+    T: Traverse,
+{
+    let mut traversal = std::pin::pin!(traversal);
+    let mut count = 0;
+    while let Some(path) =
+        futures::future::poll_fn(|cx| traversal.as_mut().poll_next_path(cx)).await
+    {
+        path?;
+        count += 1;
+    }
+    Ok(count)
+}
@@ -0,0 +1,72 @@
+use super::{KeyedNode, Node, Path, TraversalError, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Stream for the [`dedup_emitted`] method.
+///
+/// Yields each node of a traversal the first time its key is seen.
+///
+/// [`dedup_emitted`]: fn@crate::async::Traverse::dedup_emitted
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct DedupEmitted<S, K> {
+    #[pin]
+    inner: S,
+    /// The keys of the nodes yielded so far.
+    seen: HashSet<K>,
+}
+
+impl<S, K> DedupEmitted<S, K> {
+    pub(super) fn new(inner: S) -> Self {
+        Self {
+            inner,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Traverse for DedupEmitted<S, <S::Node as KeyedNode>::Key>
+where
+    S: Traverse,
+{
+    type Node = S::Node;
+
+    fn poll_next_path_with_context(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Path<S::Node>, TraversalError<Arc<S::Node>, <S::Node as Node>::Error>>>>
+    {
+        let mut this = self.project();
+        loop {
+            match futures::ready!(this.inner.as_mut().poll_next_path_with_context(cx)) {
+                // skip nodes that were already yielded
+                Some(Ok(path)) if !this.seen.insert(path.node().key()) => {}
+                item => return Poll::Ready(item),
+            }
+        }
+    }
+}
+
+impl<S> Stream for DedupEmitted<S, <S::Node as KeyedNode>::Key>
+where
+    S: Traverse,
+    S::Node: Clone,
+{
+    type Item = Result<S::Node, <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with_depth(cx)
+            .map(|item| item.map(|item| item.map(|(_, node)| Arc::unwrap_or_clone(node))))
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Sentinel for an unlimited maximum depth.
+const UNLIMITED: usize = usize::MAX;
+
+/// A shared handle to adjust the maximum depth of a running traversal.
+///
+/// Clones share the same maximum depth, hence a clone can be kept
+/// to change the maximum depth while the traversal is polled elsewhere.
+/// See [`Bfs::depth_control`] and [`Dfs::depth_control`].
+///
+/// [`Bfs::depth_control`]: fn@crate::async::Bfs::depth_control
+/// [`Dfs::depth_control`]: fn@crate::async::Dfs::depth_control
+#[derive(Debug, Clone)]
+pub struct DepthControl {
+    max_depth: Arc<AtomicUsize>,
+}
+
+impl DepthControl {
+    #[inline]
+    #[must_use]
+    /// Creates a new [`DepthControl`] with the initial `max_depth`.
+    ///
+    /// [`DepthControl`]: struct@crate::async::DepthControl
+    pub fn new<D>(max_depth: D) -> Self
+    where
+        D: Into<Option<usize>>,
+    {
+        let max_depth = max_depth.into().unwrap_or(UNLIMITED);
+        Self {
+            max_depth: Arc::new(AtomicUsize::new(max_depth)),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the current maximum depth, or `None` if it is unlimited.
+    pub fn get(&self) -> Option<usize> {
+        let max_depth = self.max_depth.load(Ordering::Acquire);
+        (max_depth != UNLIMITED).then_some(max_depth)
+    }
+
+    #[inline]
+    /// Sets the maximum depth, where `None` removes the limit.
+    pub fn set<D>(&self, max_depth: D)
+    where
+        D: Into<Option<usize>>,
+    {
+        let max_depth = max_depth.into().unwrap_or(UNLIMITED);
+        self.max_depth.store(max_depth, Ordering::Release);
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+/// A field containing children of a node, as marked by `#[children]` for the [`Node`] derive.
+///
+/// The children are cloned, since the field is only borrowed from its node.
+///
+/// [`Node`]: derive@crate::async::Node
+pub trait Children<N> {
+    /// Appends the children in this field to `children`.
+    fn collect_children(&self, children: &mut Vec<N>);
+}
+
+impl<N> Children<N> for Box<N>
+where
+    N: Clone,
+{
+    #[inline]
+    fn collect_children(&self, children: &mut Vec<N>) {
+        children.push(N::clone(self));
+    }
+}
+
+impl<N> Children<N> for Arc<N>
+where
+    N: Clone,
+{
+    #[inline]
+    fn collect_children(&self, children: &mut Vec<N>) {
+        children.push(N::clone(self));
+    }
+}
+
+impl<N> Children<N> for Vec<N>
+where
+    N: Clone,
+{
+    #[inline]
+    fn collect_children(&self, children: &mut Vec<N>) {
+        children.extend(self.iter().cloned());
+    }
+}
+
+impl<N, T> Children<N> for Option<T>
+where
+    T: Children<N>,
+{
+    #[inline]
+    fn collect_children(&self, children: &mut Vec<N>) {
+        if let Some(field) = self {
+            field.collect_children(children);
+        }
+    }
+}
@@ -1,13 +1,40 @@
-use super::{Node, Stack, StreamQueue};
+use super::{
+    into_node_stream, pop_deferred, ChildStreamFut, DepthControl, DfsBuilder, Expansion,
+    FrontierQueue, KeyedNode, LruVisitedSet, Node, Path, Prefetch, Stack, State, Traversal,
+    TraversalError, Traverse, VisitedSet,
+};
 
-use futures::stream::{FuturesOrdered, Stream, StreamExt};
+use futures::stream::Stream;
 use futures::FutureExt;
 use pin_project::pin_project;
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
+use std::hash::BuildHasher;
+use std::ops::RangeBounds;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+/// A node pulled ahead of time from the child stream of a stack frame,
+/// together with its own child stream if it is expanded ahead of time.
+type PrefetchedItem<N, E> = Result<(Path<N>, Option<Prefetch<N, E>>), E>;
+
+/// The nodes pulled ahead of time from the child stream of a stack frame.
+struct Prefetched<N, E> {
+    items: VecDeque<PrefetchedItem<N, E>>,
+    /// Whether the child stream of the stack frame completed.
+    exhausted: bool,
+}
+
+impl<N, E> Default for Prefetched<N, E> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            items: VecDeque::new(),
+            exhausted: false,
+        }
+    }
+}
+
 /// Asynchronous depth-first stream for types implementing the [`Node`] trait.
 ///
 /// ### Example
@@ -59,145 +86,593 @@ use std::task::{Context, Poll};
 ///
 /// [`Node`]: trait@crate::async::Node
 #[derive(Default)]
+pub struct Dfs<N, V = HashSet<<N as KeyedNode>::Key>>
+where
+    N: Node,
+{
+    pub(super) traversal: Traversal<N, V, Lifo<N, N::Error>>,
+}
+
+impl<N> Dfs<N>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    #[inline]
+    /// Creates a new [`Dfs`] stream.
+    ///
+    /// The DFS will be performed from the `root` node up to depth `max_depth`.
+    /// The `root` itself is not yielded, unless enabled via [`DfsBuilder::include_root`].
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    ///
+    /// [`Dfs`]: struct@crate::async::Dfs
+    /// [`DfsBuilder::include_root`]: fn@crate::async::DfsBuilder::include_root
+    pub fn new<R, D>(root: R, max_depth: D, allow_circles: bool) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        Self::builder(root)
+            .max_depth(max_depth)
+            .allow_circles(allow_circles)
+            .build()
+    }
+
+    #[inline]
+    /// Creates a new [`Dfs`] stream that yields the nodes with a depth in `range`.
+    ///
+    /// Nodes above the range are traversed but not yielded, as with [`Dfs::min_depth`],
+    /// and nodes below the range are not reached. Without an upper bound, the depth is unlimited.
+    /// The `root` itself is only yielded when the range contains depth 0,
+    /// unlike with [`Dfs::new`].
+    ///
+    /// ### Example
+    /// ```
+    /// use futures::StreamExt;
+    /// use par_dfs::r#async::{AdjacencyNode, Dfs};
+    /// use std::collections::HashMap;
+    /// use std::sync::Arc;
+    ///
+    /// let graph = Arc::new(HashMap::from([("a", vec!["b"]), ("b", vec!["c"]), ("c", vec!["d"])]));
+    /// let nodes = tokio_test::block_on(async {
+    ///     Dfs::<AdjacencyNode<&str>>::in_depth_range(AdjacencyNode::new(graph, "a"), 2..=3, false)
+    ///         .map(|node| *node.unwrap().key())
+    ///         .collect::<Vec<_>>()
+    ///         .await
+    /// });
+    /// assert_eq!(nodes, ["c", "d"]);
+    /// ```
+    ///
+    /// [`Dfs`]: struct@crate::async::Dfs
+    /// [`Dfs::min_depth`]: fn@crate::async::Dfs::min_depth
+    /// [`Dfs::new`]: fn@crate::async::Dfs::new
+    pub fn in_depth_range<R, B>(root: R, range: B, allow_circles: bool) -> Self
+    where
+        R: Into<N>,
+        B: RangeBounds<usize>,
+    {
+        let (min_depth, max_depth) = super::depth_bounds(&range);
+        Self::builder(root)
+            .max_depth(max_depth)
+            .allow_circles(allow_circles)
+            .include_root(min_depth == 0)
+            .build()
+            .min_depth(min_depth)
+    }
+
+    #[inline]
+    /// Creates a new [`Dfs`] stream whose maximum depth is read from `control`.
+    ///
+    /// See [`Dfs::depth_control`].
+    ///
+    /// [`Dfs`]: struct@crate::async::Dfs
+    /// [`Dfs::depth_control`]: fn@crate::async::Dfs::depth_control
+    pub fn new_with_control<R>(root: R, control: DepthControl, allow_circles: bool) -> Self
+    where
+        R: Into<N>,
+    {
+        Self::new(root, control.get(), allow_circles).depth_control(control)
+    }
+
+    #[inline]
+    /// Creates a [`DfsBuilder`] for a [`Dfs`] stream from the `root` node.
+    ///
+    /// By default, the traversal has no depth limit and circles are not allowed.
+    ///
+    /// ### Example
+    /// ```
+    /// use futures::StreamExt;
+    /// use par_dfs::r#async::{Dfs, Node, NodeStream};
+    ///
+    /// #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    /// struct Number(u32);
+    ///
+    /// #[async_trait::async_trait]
+    /// impl Node for Number {
+    ///     type Error = std::convert::Infallible;
+    ///
+    ///     async fn children(
+    ///         self: std::sync::Arc<Self>,
+    ///         depth: usize
+    ///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+    ///         let nodes = [Ok(Self(depth as u32)), Ok(Self(depth as u32))];
+    ///         Ok(Box::pin(futures::stream::iter(nodes)))
+    ///     }
+    /// }
+    ///
+    /// let result = tokio_test::block_on(async {
+    ///     let dfs = Dfs::<Number>::builder(Number(0))
+    ///         .max_depth(2)
+    ///         .allow_circles(true)
+    ///         .build()
+    ///         .max_nodes(4);
+    ///     let output = dfs
+    ///         .collect::<Vec<_>>()
+    ///         .await
+    ///         .into_iter()
+    ///         .collect::<Result<Vec<_>, _>>()
+    ///         .unwrap();
+    ///     output.into_iter().map(|n| n.0).collect::<Vec<_>>()
+    /// });
+    /// assert_eq!(result, [1, 2, 2, 1]);
+    /// ```
+    ///
+    /// [`DfsBuilder`]: struct@crate::async::DfsBuilder
+    /// [`Dfs`]: struct@crate::async::Dfs
+    pub fn builder<R>(root: R) -> DfsBuilder<N>
+    where
+        R: Into<N>,
+    {
+        DfsBuilder::new(root.into())
+    }
+
+    #[inline]
+    /// Creates a new [`Dfs`] stream from multiple `roots`.
+    ///
+    /// The roots are yielded first at depth 0, followed by the DFS from each root in turn
+    /// up to depth `max_depth`. Nodes reachable from multiple roots are only yielded once,
+    /// unless `allow_circles`, in which case visited nodes will not be tracked.
+    /// Without any roots, the stream is empty.
+    ///
+    /// [`Dfs`]: struct@crate::async::Dfs
+    pub fn new_multi<I, R, D>(roots: I, max_depth: D, allow_circles: bool) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        let roots = roots.into_iter().map(|root| Arc::new(root.into()));
+        Self::from_roots(
+            roots,
+            max_depth.into(),
+            allow_circles,
+            HashSet::new(),
+            true,
+            0,
+        )
+    }
+}
+
+impl<N, S> Dfs<N, HashSet<N::Key, S>>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+    S: BuildHasher,
+{
+    #[inline]
+    /// Creates a new [`Dfs`] stream with the given `hasher` for the visited nodes.
+    ///
+    /// The DFS will be performed from the `root` node up to depth `max_depth`.
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    /// Otherwise, the keys of the visited nodes are tracked in a [`HashSet`] using the given `hasher`.
+    ///
+    /// [`HashSet`]: struct@std::collections::HashSet
+    /// [`Dfs`]: struct@crate::async::Dfs
+    pub fn with_hasher<R, D>(root: R, max_depth: D, allow_circles: bool, hasher: S) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        Self::with_visited(root, max_depth, allow_circles, HashSet::with_hasher(hasher))
+    }
+}
+
+impl<N> Dfs<N, LruVisitedSet<N::Key>>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Key: Clone,
+    N::Error: Send + 'static,
+{
+    #[inline]
+    /// Creates a new [`Dfs`] stream that tracks at most `capacity` visited nodes.
+    ///
+    /// The DFS will be performed from the `root` node up to depth `max_depth`.
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    /// Otherwise, the least recently seen nodes are evicted once `capacity` is reached,
+    /// which bounds the memory at the cost of best-effort cycle detection:
+    /// a node that was evicted is yielded and expanded again when it is reached another time.
+    /// See [`LruVisitedSet`].
+    ///
+    /// [`LruVisitedSet`]: struct@crate::async::LruVisitedSet
+    /// [`Dfs`]: struct@crate::async::Dfs
+    pub fn with_visited_capacity<R, D>(
+        root: R,
+        max_depth: D,
+        allow_circles: bool,
+        capacity: usize,
+    ) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        Self::with_visited(root, max_depth, allow_circles, LruVisitedSet::new(capacity))
+    }
+}
+
+impl<N, V> Dfs<N, V>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+    V: VisitedSet<N>,
+{
+    #[inline]
+    /// Creates a new [`Dfs`] stream with the given set of `visited` nodes.
+    ///
+    /// The DFS will be performed from the `root` node up to depth `max_depth`.
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    /// Otherwise, the visited nodes are tracked in the given [`VisitedSet`].
+    ///
+    /// [`VisitedSet`]: trait@crate::async::VisitedSet
+    /// [`Dfs`]: struct@crate::async::Dfs
+    pub fn with_visited<R, D>(root: R, max_depth: D, allow_circles: bool, visited: V) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        Self::from_shared(
+            Arc::new(root.into()),
+            max_depth.into(),
+            allow_circles,
+            visited,
+        )
+    }
+
+    /// Creates a new [`Dfs`] stream from a shared `root` node.
+    ///
+    /// [`Dfs`]: struct@crate::async::Dfs
+    pub(super) fn from_shared(
+        root: Arc<N>,
+        max_depth: Option<usize>,
+        allow_circles: bool,
+        visited: V,
+    ) -> Self {
+        Self::from_roots([root], max_depth, allow_circles, visited, false, 0)
+    }
+
+    /// Creates a new [`Dfs`] stream from shared `roots` at `initial_depth`,
+    /// which are only yielded when `emit_roots`.
+    ///
+    /// [`Dfs`]: struct@crate::async::Dfs
+    pub(super) fn from_roots<I>(
+        roots: I,
+        max_depth: Option<usize>,
+        allow_circles: bool,
+        visited: V,
+        emit_roots: bool,
+        initial_depth: usize,
+    ) -> Self
+    where
+        I: IntoIterator<Item = Arc<N>>,
+    {
+        Self {
+            traversal: Traversal::from_roots(
+                Lifo::default(),
+                roots,
+                max_depth,
+                allow_circles,
+                visited,
+                emit_roots,
+                initial_depth,
+            ),
+        }
+    }
+
+    /// Creates a new [`Dfs`] stream from the `roots` that are yet to be yielded
+    /// and the `deferred` nodes that are yet to be expanded.
+    ///
+    /// [`Dfs`]: struct@crate::async::Dfs
+    pub(super) fn from_parts(
+        roots: VecDeque<Arc<Path<N>>>,
+        deferred: VecDeque<Arc<Path<N>>>,
+        max_depth: Option<usize>,
+        allow_circles: bool,
+        visited: V,
+    ) -> Self {
+        Self {
+            traversal: Traversal::from_parts(
+                Lifo::default(),
+                roots,
+                deferred,
+                max_depth,
+                allow_circles,
+                visited,
+            ),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Expands up to `lookahead` nodes ahead of time to hide the latency of [`Node::children`].
+    ///
+    /// By default, the children of a node are only requested once the node is yielded,
+    /// hence there is at most a single pending [`Node::children`] call at a time.
+    /// With a `lookahead`, the upcoming siblings of the yielded node are pulled from
+    /// their child stream early, and their [`Node::children`] calls are polled concurrently.
+    ///
+    /// Nodes are still yielded in pre-order, since visited nodes, filters and limits are
+    /// only applied once a node is reached. Child streams of nodes that end up not being
+    /// expanded, e.g. because they are pruned or visited via another path in the meantime,
+    /// are requested in vain and dropped.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    pub fn lookahead(mut self, lookahead: usize) -> Self {
+        self.traversal.frontier.lookahead = lookahead;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the depth of the next node to be yielded, without polling the stream.
+    ///
+    /// Since child streams are lazy, this is the depth of the next node under the
+    /// assumption that the most recently expanded child stream yields another node.
+    /// If it does not, the next node is yielded from a shallower child stream,
+    /// hence the returned depth is an upper bound.
+    /// Returns `None` once the frontier is empty.
+    pub fn peek_depth(&self) -> Option<usize> {
+        self.traversal.peek_depth()
+    }
+}
+
+#[cfg(feature = "tree")]
+impl<N, V> Dfs<N, V>
+where
+    N: Node + Send + Unpin + std::fmt::Display + 'static,
+    N::Error: Send + 'static,
+    V: VisitedSet<N>,
+{
+    #[cfg_attr(docsrs, doc(cfg(feature = "tree")))]
+    /// Consumes the traversal, formatting the yielded nodes as an indented tree.
+    ///
+    /// Each node is formatted via its [`Display`] implementation on its own line,
+    /// connected to its parent by `├─` or, for the last of its siblings, `└─`.
+    /// Roots yielded via [`DfsBuilder::include_root`] are formatted without a connector.
+    /// Since the connectors depend on the following nodes, the entire traversal
+    /// is buffered before the tree is formatted.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the traversal.
+    ///
+    /// ### Example
+    /// ```
+    /// use par_dfs::r#async::{Dfs, Node, NodeStream};
+    ///
+    /// #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    /// struct Number(u32);
+    ///
+    /// impl std::fmt::Display for Number {
+    ///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    ///         write!(f, "{}", self.0)
+    ///     }
+    /// }
+    ///
+    /// #[async_trait::async_trait]
+    /// impl Node for Number {
+    ///     type Error = std::convert::Infallible;
+    ///
+    ///     async fn children(
+    ///         self: std::sync::Arc<Self>,
+    ///         _depth: usize
+    ///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+    ///         let nodes = [Ok(Self(self.0 * 2)), Ok(Self(self.0 * 2 + 1))];
+    ///         Ok(Box::pin(futures::stream::iter(nodes)))
+    ///     }
+    /// }
+    ///
+    /// let tree = tokio_test::block_on(async {
+    ///     let dfs = Dfs::<Number>::builder(Number(1))
+    ///         .max_depth(2)
+    ///         .include_root(true)
+    ///         .build();
+    ///     dfs.fmt_tree().await.unwrap()
+    /// });
+    /// assert_eq!(tree, "1\n├─ 2\n│  ├─ 4\n│  └─ 5\n└─ 3\n   ├─ 6\n   └─ 7\n");
+    /// ```
+    ///
+    /// [`Display`]: trait@std::fmt::Display
+    /// [`DfsBuilder::include_root`]: fn@crate::async::DfsBuilder::include_root
+    pub async fn fmt_tree(self) -> Result<String, N::Error> {
+        super::tree::fmt_tree(self).await
+    }
+}
+
+/// Asynchronous post-order depth-first stream for types implementing the [`Node`] trait.
+///
+/// In contrast to [`Dfs`], a node is only yielded after all of its
+/// descendants have been yielded, which is useful for computing
+/// aggregates over subtrees.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{Node, DfsPostOrder, NodeStream};
+///
+/// #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+/// struct WordNode(String);
+///
+/// #[async_trait::async_trait]
+/// impl Node for WordNode {
+///     type Error = std::convert::Infallible;
+///
+///     async fn children(
+///         self: std::sync::Arc<Self>,
+///         _depth: usize
+///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+///         let len = self.0.len();
+///         let nodes: Vec<String> = if len < 2 {
+///             vec![]
+///         } else {
+///             let mid = len/2;
+///             vec![self.0[..mid].into(), self.0[mid..].into()]
+///         };
+///         let nodes = nodes.into_iter()
+///             .map(Self)
+///             .map(Result::Ok);
+///         let stream = futures::stream::iter(nodes);
+///         Ok(Box::pin(stream.boxed()))
+///     }
+/// }
+///
+/// let result = tokio_test::block_on(async {
+///     let root = WordNode("Hello World".into());
+///     let dfs = DfsPostOrder::<WordNode>::new(root, None, true);
+///     let output = dfs
+///         .collect::<Vec<_>>()
+///         .await
+///         .into_iter()
+///         .collect::<Result<Vec<_>, _>>()
+///         .unwrap();
+///     output.into_iter().map(|s| s.0).collect::<Vec<_>>()
+/// });
+/// assert_eq!(&result[..3], ["H", "e", "He"]);
+/// assert_eq!(result.last().map(String::as_str), Some(" World"));
+/// ```
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`Dfs`]: struct@crate::async::Dfs
+#[allow(clippy::module_name_repetitions)]
+#[derive(Default)]
 #[pin_project]
-pub struct Dfs<N>
+pub struct DfsPostOrder<N>
 where
     N: Node,
 {
+    /// Child streams together with the path to the node they belong to,
+    /// which is yielded once its child stream is drained.
     stack: Stack<N, N::Error>,
-    child_streams_futs: StreamQueue<N, N::Error>,
+    child_stream_fut: Option<ChildStreamFut<N, N::Error>>,
+    expansion: Expansion<N>,
     max_depth: Option<usize>,
     allow_circles: bool,
-    visited: HashSet<N>,
+    visited: HashSet<N::Key>,
 }
 
-impl<N> Dfs<N>
+impl<N> DfsPostOrder<N>
 where
-    N: Node + Send + Unpin + Clone + 'static,
+    N: Node + Send + Unpin + 'static,
     N::Error: Send + 'static,
 {
     #[inline]
-    /// Creates a new [`Dfs`] stream.
+    /// Creates a new [`DfsPostOrder`] stream.
     ///
     /// The DFS will be performed from the `root` node up to depth `max_depth`.
     ///
     /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
     ///
-    /// [`Dfs`]: struct@crate::async::Dfs
+    /// [`DfsPostOrder`]: struct@crate::async::DfsPostOrder
     pub fn new<R, D>(root: R, max_depth: D, allow_circles: bool) -> Self
     where
         R: Into<N>,
         D: Into<Option<usize>>,
     {
-        let root = root.into();
+        let root = Arc::new(root.into());
         let max_depth = max_depth.into();
-        let mut child_streams_futs: StreamQueue<N, N::Error> = FuturesOrdered::new();
-        let depth = 1;
-        let child_stream_fut = Arc::new(root.clone())
-            .children(depth)
-            .map(move |stream| (depth, stream));
-        child_streams_futs.push_front(Box::pin(child_stream_fut));
+        let visited = HashSet::from_iter([root.key()]);
+        let expansion = Expansion::default();
+        let root_stream_fut = expansion.child_stream_fut(Arc::new(Path::root(root)));
 
         Self {
             stack: vec![],
-            child_streams_futs,
+            child_stream_fut: Some(root_stream_fut),
+            expansion,
             max_depth,
-            visited: HashSet::from_iter([root]),
+            visited,
             allow_circles,
         }
     }
 }
 
-impl<N> Stream for Dfs<N>
+impl<N> Traverse for DfsPostOrder<N>
 where
-    N: Node + Send + Clone + Unpin + 'static,
+    N: Node + Send + Unpin + 'static,
     N::Error: Send + 'static,
 {
-    type Item = Result<N, N::Error>;
+    type Node = N;
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    fn poll_next_path_with_context(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Path<N>, TraversalError<Arc<N>, N::Error>>>> {
         let this = self.project();
 
-        // println!("------- poll");
-        // println!("stack size: {:?}", this.stack.len());
-
-        // we first poll for the newest child stream in dfs
-        // println!("child stream futs: {:?}", this.child_streams_futs.len());
-        match this.child_streams_futs.poll_next_unpin(cx) {
-            Poll::Ready(Some((depth, stream))) => {
-                // println!(
-                //     "child stream fut depth {} completed: {:?}",
-                //     depth,
-                //     stream.is_ok()
-                // );
-                let stream = match stream {
-                    Ok(stream) => stream.boxed(),
-                    Err(err) => futures::stream::iter([Err(err)]).boxed(),
-                };
-                this.stack.push((depth, Box::pin(stream)));
-                // println!("stack size: {}", this.stack.len());
-            }
-            // when there is no child stream future,
-            // continue to poll the current stream
-            Poll::Ready(None) => {
-                // println!("no child stream to wait for");
-            }
-            // still waiting for the new child stream
-            Poll::Pending => {
-                // println!("child stream is still pending");
-                return Poll::Pending;
+        loop {
+            // wait for the child stream of the root or the last node
+            if let Some(fut) = this.child_stream_fut.as_mut() {
+                let (parent, stream) = futures::ready!(fut.poll_unpin(cx));
+                *this.child_stream_fut = None;
+                let stream = into_node_stream(stream);
+                this.stack.push((parent, stream));
             }
-        }
 
-        // at this point, the last element in the stack is the current level
-        loop {
             let next_item = match this.stack.last_mut() {
-                Some((depth, current_stream)) => {
+                Some((parent, current_stream)) => {
                     let next_item = current_stream.as_mut().poll_next(cx);
-                    Some(next_item.map(|node| (depth, node)))
+                    Some(next_item.map(|node| (Arc::clone(parent), node)))
                 }
                 None => None,
             };
 
-            // println!("next item: {:?}", next_item);
             match next_item {
                 // stream item is ready but failure success
-                Some(Poll::Ready((_, Some(Err(err))))) => {
-                    return Poll::Ready(Some(Err(err)));
+                Some(Poll::Ready((parent, Some(Err(err))))) => {
+                    return Poll::Ready(Some(Err(TraversalError::child_of(&parent, err))));
                 }
                 // stream item is ready and success
-                Some(Poll::Ready((depth, Some(Ok(node))))) => {
-                    if *this.allow_circles || !this.visited.contains(&node) {
-                        if !*this.allow_circles {
-                            this.visited.insert(node.clone());
-                        }
-
-                        if let Some(max_depth) = this.max_depth {
-                            if depth >= max_depth {
-                                return Poll::Ready(Some(Ok(node)));
-                            }
+                Some(Poll::Ready((parent, Some(Ok(node))))) => {
+                    let path = Path::child(&parent, Arc::new(node));
+                    let terminal = path.node().is_terminal();
+                    if *this.allow_circles
+                        || path.node().allow_revisit()
+                        || terminal
+                        || this.visited.insert(path.node().key())
+                    {
+                        let expand = !terminal
+                            && this
+                                .max_depth
+                                .is_none_or(|max_depth| path.depth() < max_depth);
+                        if !expand {
+                            // leaf nodes can be yielded right away
+                            return Poll::Ready(Some(Ok(path)));
                         }
 
-                        // add child stream future to be polled
-                        let arc_node = Arc::new(node.clone());
-                        let next_depth = *depth + 1;
-                        let child_stream_fut = arc_node
-                            .children(next_depth)
-                            .map(move |stream| (next_depth, stream));
-                        this.child_streams_futs
-                            .push_front(Box::pin(child_stream_fut));
-
-                        return Poll::Ready(Some(Ok(node)));
+                        // the node is yielded once its child stream is drained
+                        let child_stream_fut = this.expansion.child_stream_fut(Arc::new(path));
+                        *this.child_stream_fut = Some(child_stream_fut);
                     }
                 }
-                // stream completed for this level completed
+                // all descendants of the node have been yielded
                 Some(Poll::Ready((_, None))) => {
-                    this.stack.pop();
-                    // println!("pop stack to size: {}", this.stack.len());
-                    // try again in the next round
-                    // returning Poll::Pending here is bad because the runtime can not know when to poll
-                    // us again to make progress since we never passed the cx to poll of the next
-                    // level stream
+                    if let Some((parent, _)) = this.stack.pop() {
+                        // the root itself is not yielded
+                        if parent.depth() > 0 {
+                            return Poll::Ready(Some(Ok(Arc::unwrap_or_clone(parent))));
+                        }
+                    }
                 }
                 // stream item is pending
                 Some(Poll::Pending) => {
@@ -212,16 +687,288 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::Dfs;
-    use anyhow::Result;
+impl<N> Stream for DfsPostOrder<N>
+where
+    N: Node + Send + Clone + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    type Item = Result<N, N::Error>;
 
-    macro_rules! depths {
-        ($stream:ident) => {{
-            $stream
-                // collect the entire stream
-                .collect::<Vec<_>>()
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with_depth(cx)
+            .map(|item| item.map(|item| item.map(|(_, node)| Arc::unwrap_or_clone(node))))
+    }
+}
+
+/// The frontier of a [`Dfs`], which expands the most recently reached node first.
+///
+/// [`Dfs`]: struct@crate::async::Dfs
+pub(super) struct Lifo<N, E> {
+    stack: Stack<N, E>,
+    /// The child stream of the last expanded node, which becomes the top of the stack.
+    ///
+    /// Only a single child stream is pending at a time, so that the nodes are yielded
+    /// in strict pre-order regardless of how fast the [`Node::children`] calls complete.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    child_stream_fut: Option<ChildStreamFut<N, E>>,
+    /// The nodes pulled ahead of time, with one entry per frame of the `stack`.
+    prefetched: Vec<Prefetched<N, E>>,
+    /// The maximum number of child streams that are expanded ahead of time.
+    lookahead: usize,
+    /// The number of child streams that are currently expanded ahead of time.
+    num_prefetching: usize,
+    /// The child stream of the last reached node, if it was expanded ahead of time.
+    reached: Option<Prefetch<N, E>>,
+}
+
+impl<N, E> Default for Lifo<N, E> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            stack: vec![],
+            child_stream_fut: None,
+            prefetched: vec![],
+            lookahead: 0,
+            num_prefetching: 0,
+            reached: None,
+        }
+    }
+}
+
+impl<N> Lifo<N, N::Error>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    /// Polls for the next item of the child stream at the top of the stack,
+    /// together with the path to the node it belongs to.
+    ///
+    /// Returns `None` if the stack is empty.
+    #[allow(clippy::type_complexity)]
+    fn poll_top<V>(
+        &mut self,
+        cx: &mut Context<'_>,
+        state: &mut State<N, V>,
+    ) -> Option<Poll<(Arc<Path<N>>, Option<PrefetchedItem<N, N::Error>>)>>
+    where
+        V: VisitedSet<N>,
+    {
+        // at this point, the last element in the stack is the current level
+        let ((parent, current_stream), prefetched) =
+            self.stack.last_mut().zip(self.prefetched.last_mut())?;
+        let next_item = match prefetched.items.pop_front() {
+            Some(item) => {
+                if matches!(item, Ok((_, Some(_)))) {
+                    self.num_prefetching -= 1;
+                }
+                Poll::Ready(Some(item))
+            }
+            None if prefetched.exhausted => Poll::Ready(None),
+            None => current_stream.as_mut().poll_next(cx).map(|item| {
+                item.map(|node| node.map(|node| (Path::child(parent, Arc::new(node)), None)))
+            }),
+        };
+        if next_item.is_ready() {
+            // pull the upcoming siblings and expand them ahead of time
+            while !prefetched.exhausted
+                && prefetched.items.len() < self.lookahead
+                && self.num_prefetching < self.lookahead
+            {
+                let Poll::Ready(item) = current_stream.as_mut().poll_next(cx) else {
+                    break;
+                };
+                let Some(item) = item else {
+                    prefetched.exhausted = true;
+                    break;
+                };
+                let item = item.map(|node| {
+                    let path = Path::child(parent, Arc::new(node));
+                    let tracked = state.admission.tracks_visited() && !path.node().allow_revisit();
+                    let expand = state
+                        .max_depth
+                        .is_none_or(|max_depth| path.depth() < max_depth)
+                        && !path.node().is_terminal()
+                        && !(tracked && state.visited.contains(path.node()));
+                    let prefetch = expand.then(|| {
+                        self.num_prefetching += 1;
+                        state.stats.record_children_call(1 + self.num_prefetching);
+                        let fut = state.expansion.child_stream_fut(Arc::new(path.clone()));
+                        Prefetch::Pending(fut)
+                    });
+                    (path, prefetch)
+                });
+                prefetched.items.push_back(item);
+            }
+        }
+        Some(next_item.map(|item| (Arc::clone(parent), item)))
+    }
+}
+
+impl<N> FrontierQueue<N> for Lifo<N, N::Error>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    fn push<V>(&mut self, parent: &Path<N>, state: &mut State<N, V>) {
+        // add child stream future to be polled
+        debug_assert!(self.child_stream_fut.is_none());
+        let fut = if let Some(prefetch) = self.reached.take() {
+            prefetch.into_future()
+        } else {
+            state.stats.record_children_call(1);
+            state.expansion.child_stream_fut(Arc::new(parent.clone()))
+        };
+        self.child_stream_fut = Some(fut);
+    }
+
+    fn poll_next<V>(
+        &mut self,
+        cx: &mut Context<'_>,
+        state: &mut State<N, V>,
+    ) -> Poll<Option<Result<Path<N>, TraversalError<Arc<N>, N::Error>>>>
+    where
+        V: VisitedSet<N>,
+    {
+        #[cfg(feature = "trace")]
+        let _span = tracing::trace_span!("dfs", stack_size = self.stack.len()).entered();
+
+        // the child stream of the last reached node is dropped unless it was expanded
+        self.reached = None;
+
+        loop {
+            // drive the child streams that are expanded ahead of time
+            for prefetched in &mut self.prefetched {
+                for (_, prefetch) in prefetched.items.iter_mut().flatten() {
+                    if let Some(prefetch) = prefetch {
+                        prefetch.poll(cx);
+                    }
+                }
+            }
+
+            // we first poll for the child stream of the last expanded node,
+            // which must become the top of the stack before continuing in dfs
+            trace!(has_child_stream_fut = self.child_stream_fut.is_some());
+            if let Some(fut) = self.child_stream_fut.as_mut() {
+                let Poll::Ready((parent, stream)) = fut.poll_unpin(cx) else {
+                    trace!("child stream is still pending");
+                    return Poll::Pending;
+                };
+                self.child_stream_fut = None;
+                trace!(
+                    depth = parent.depth() + 1,
+                    ok = stream.is_ok(),
+                    "child stream completed"
+                );
+                match stream {
+                    Ok(stream) => {
+                        self.stack.push((parent, stream));
+                        self.prefetched.push(Prefetched::default());
+                        state.stats.record_stack_depth(self.stack.len());
+                        trace!(stack_size = self.stack.len());
+                    }
+                    // the failed node has no frame, the traversal continues with its siblings
+                    Err(err) => {
+                        return Poll::Ready(Some(Err(TraversalError::child_of(&parent, err))));
+                    }
+                }
+            }
+
+            match self.poll_top(cx, state) {
+                // stream item is ready but failure success
+                Some(Poll::Ready((parent, Some(Err(err))))) => {
+                    return Poll::Ready(Some(Err(TraversalError::child_of(&parent, err))));
+                }
+                // stream item is ready and success
+                Some(Poll::Ready((_, Some(Ok((path, prefetch)))))) => {
+                    self.reached = prefetch;
+                    return Poll::Ready(Some(Ok(path)));
+                }
+                // stream completed for this level completed
+                Some(Poll::Ready((_, None))) => {
+                    self.stack.pop();
+                    self.prefetched.pop();
+                    trace!(stack_size = self.stack.len(), "stream completed");
+                    // try again in the next round
+                    // returning Poll::Pending here is bad because the runtime can not know when to poll
+                    // us again to make progress since we never passed the cx to poll of the next
+                    // level stream
+                }
+                // stream item is pending
+                Some(Poll::Pending) => {
+                    return Poll::Pending;
+                }
+                // stack is empty, continue with the next root
+                None => {
+                    let Some(root) = pop_deferred(
+                        &mut state.deferred,
+                        state.max_depth,
+                        &mut state.truncated,
+                        |_| true,
+                    ) else {
+                        // no more roots and we are done
+                        return Poll::Ready(None);
+                    };
+                    self.child_stream_fut = Some(state.expansion.child_stream_fut(root));
+                    state.stats.record_children_call(1);
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.stack.clear();
+        self.prefetched.clear();
+        self.num_prefetching = 0;
+        self.child_stream_fut = None;
+        self.reached = None;
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        usize::from(self.child_stream_fut.is_some()) + self.stack.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    fn pending_children(&self) -> usize {
+        usize::from(self.child_stream_fut.is_some()) + self.num_prefetching
+    }
+
+    #[inline]
+    fn stack_depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    #[inline]
+    fn peek_depth(&self) -> Option<usize> {
+        if let Some(fut) = self.child_stream_fut.as_ref() {
+            return Some(fut.depth());
+        }
+        self.stack.last().map(|(parent, _)| parent.depth() + 1)
+    }
+
+    #[inline]
+    fn restart(&self) -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dfs, DfsPostOrder};
+    use anyhow::Result;
+
+    macro_rules! depths {
+        ($stream:ident) => {{
+            $stream
+                // collect the entire stream
+                .collect::<Vec<_>>()
                 .await
                 .into_iter()
                 // fail on first error
@@ -310,4 +1057,1284 @@ mod tests {
         test_depths_ordered,
         test_depths_unordered,
     );
+
+    test_depths!(
+        dfs_with_hasher:
+        (
+            Dfs::<crate::utils::test::Node, _>::with_hasher(
+                0,
+                3,
+                false,
+                std::hash::BuildHasherDefault::<std::collections::hash_map::DefaultHasher>::default(),
+            ),
+            [1, 2, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    // evicted nodes are yielded again when reached from a sibling
+    test_depths!(
+        dfs_with_visited_capacity:
+        (
+            Dfs::<crate::utils::test::Node, _>::with_visited_capacity(0, 3, false, 1),
+            [1, 2, 3, 2, 3, 1, 2, 3, 2, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        dfs_with_visited_all:
+        (
+            Dfs::<crate::utils::test::Node, _>::with_visited(
+                0,
+                3,
+                false,
+                crate::utils::test::r#async::AllVisited,
+            ),
+            Vec::<usize>::new()
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        dfs_with_visited_none:
+        (
+            Dfs::<crate::utils::test::Node, _>::with_visited(
+                0,
+                3,
+                false,
+                crate::utils::test::r#async::NoneVisited,
+            ),
+            [1, 2, 3, 3, 2, 3, 3, 1, 2, 3, 3, 2, 3, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        dfs_prune:
+        (
+            Dfs::<crate::utils::test::Node>::new(0, 3, true).prune(|node, _depth| node.0 == 2),
+            [1, 2, 2, 1, 2, 2]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        dfs_prune_no_circles:
+        (
+            Dfs::<crate::utils::test::Node>::new(0, 3, false).prune(|_node, depth| depth == 1),
+            [1]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        dfs_filter_nodes:
+        (
+            Dfs::<crate::utils::test::Node>::new(0, 3, true).filter_nodes(|node, _depth| node.0 != 2),
+            [1, 1]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        dfs_min_depth:
+        (
+            Dfs::<crate::utils::test::Node>::new(0, 3, true).min_depth(2),
+            [2, 3, 3, 2, 3, 3, 2, 3, 3, 2, 3, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        dfs_min_depth_no_circles:
+        (
+            Dfs::<crate::utils::test::Node>::new(0, 3, false).min_depth(2),
+            [2, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        dfs_min_depth_exceeds_max_depth:
+        (
+            Dfs::<crate::utils::test::Node>::new(0, 3, true).min_depth(4),
+            Vec::<usize>::new()
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_skip_errors() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use crate::utils::test::Error;
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec![Ok("failing"), Err(Error), Ok("ok")],
+                    "failing" => return Err(Error),
+                    "ok" => vec![Ok("ok child")],
+                    _ => vec![],
+                };
+                let nodes = children
+                    .into_iter()
+                    .map(|child| child.map(|name| Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let root = NamedNode("root".into());
+        let mut dfs = Dfs::<NamedNode>::new(root, None, false).skip_errors(true);
+        let nodes = dfs
+            .by_ref()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let names: Vec<_> = nodes.iter().map(|node| node.0.as_str()).collect();
+        similar_asserts::assert_eq!(names, ["failing", "ok", "ok child"]);
+        similar_asserts::assert_eq!(dfs.skipped_errors(), [Error, Error]);
+        similar_asserts::assert_eq!(dfs.take_skipped_errors(), [Error, Error]);
+        assert!(dfs.skipped_errors().is_empty());
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_retry() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, RetryPolicy};
+        use crate::utils::test::Error;
+        use futures::StreamExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio::time::Duration;
+
+        static FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct FlakyNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for FlakyNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec!["flaky"],
+                    "flaky" => {
+                        // fails twice before succeeding
+                        if FAILURES.fetch_add(1, Ordering::SeqCst) < 2 {
+                            return Err(Error);
+                        }
+                        vec!["flaky child"]
+                    }
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|name| Ok(Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let backoff = Duration::from_millis(10);
+        for (max_retries, expected) in [
+            (
+                2,
+                vec![Ok("flaky".to_string()), Ok("flaky child".to_string())],
+            ),
+            (1, vec![Ok("flaky".to_string()), Err(Error)]),
+        ] {
+            FAILURES.store(0, Ordering::SeqCst);
+            let root = FlakyNode("root".into());
+            let dfs = Dfs::<FlakyNode>::new(root, None, false)
+                .retry(RetryPolicy::new(max_retries, backoff));
+            let items = dfs
+                .map(|item| item.map(|node| node.0))
+                .collect::<Vec<_>>()
+                .await;
+            similar_asserts::assert_eq!(items, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_paths() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, Traverse};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(&'static str);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0 {
+                    "root" => vec!["a", "b"],
+                    "a" => vec!["a1"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|name| Ok(Self(name)));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let names = |paths: Vec<Vec<NamedNode>>| -> Vec<Vec<&'static str>> {
+            paths
+                .into_iter()
+                .map(|path| path.into_iter().map(|node| node.0).collect())
+                .collect()
+        };
+
+        let paths = Dfs::<NamedNode>::new(NamedNode("root"), None, false)
+            .paths()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        similar_asserts::assert_eq!(
+            names(paths),
+            [
+                vec!["root", "a"],
+                vec!["root", "a", "a1"],
+                vec!["root", "b"]
+            ]
+        );
+
+        let paths = DfsPostOrder::<NamedNode>::new(NamedNode("root"), None, false)
+            .paths()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        similar_asserts::assert_eq!(
+            names(paths),
+            [
+                vec!["root", "a", "a1"],
+                vec!["root", "a"],
+                vec!["root", "b"]
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_catch_panics() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, PanicError};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(thiserror::Error, Debug, PartialEq, Eq)]
+        enum Error {
+            #[error(transparent)]
+            Panic(#[from] PanicError),
+        }
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec!["a", "b"],
+                    "a" => panic!("bad node"),
+                    "b" => vec!["b child"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(|name| Ok(Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let root = NamedNode("root".into());
+        let dfs = Dfs::<NamedNode>::new(root, None, false).catch_panics();
+        let items = dfs
+            .map(|item| item.map(|node| node.0))
+            .collect::<Vec<_>>()
+            .await;
+        similar_asserts::assert_eq!(
+            items,
+            [
+                Ok("a".to_string()),
+                Err(Error::Panic(PanicError {
+                    depth: 2,
+                    message: "bad node".to_string(),
+                })),
+                Ok("b".to_string()),
+                Ok("b child".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_stop_flag_after_items() -> Result<()> {
+        use futures::StreamExt;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+        use tokio::time::{timeout, Duration};
+
+        let stop = Arc::new(AtomicBool::new(false));
+        // without a depth limit, the traversal never completes on its own
+        let mut dfs =
+            Dfs::<crate::utils::test::Node>::new(0, None, true).stop_flag(Arc::clone(&stop));
+        let handle = tokio::spawn(async move {
+            let mut count = 0;
+            while let Some(node) = dfs.next().await {
+                node?;
+                count += 1;
+                if count == 5 {
+                    stop.store(true, Ordering::Release);
+                }
+            }
+            Ok::<_, anyhow::Error>((dfs.stats().children_calls, count))
+        });
+        let (children_calls, count) = timeout(Duration::from_secs(10), handle).await???;
+        similar_asserts::assert_eq!(count, 5);
+        // the remaining frontier is dropped instead of drained,
+        // hence only the root and the yielded nodes were expanded
+        assert!(children_calls <= count + 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_deadline() -> Result<()> {
+        use tokio::time::{timeout, Duration, Instant};
+
+        // without a depth limit, the traversal never completes on its own
+        let deadline = Instant::now() + Duration::from_millis(400);
+        let dfs = Dfs::<crate::utils::test::Node>::new(0, None, true).deadline(deadline);
+        let count = timeout(Duration::from_secs(10), dfs.count()).await??;
+        assert!(count > 0);
+        assert!(Instant::now() >= deadline);
+        Ok(())
+    }
+
+    #[cfg(feature = "cancellation")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_cancellation_after_items() -> Result<()> {
+        use futures::StreamExt;
+        use tokio::time::{timeout, Duration};
+        use tokio_util::sync::CancellationToken;
+
+        let token = CancellationToken::new();
+        // without a depth limit, the traversal never completes on its own
+        let mut dfs =
+            Dfs::<crate::utils::test::Node>::new(0, None, true).cancellation_token(token.clone());
+        let handle = tokio::spawn(async move {
+            let mut count = 0;
+            while let Some(node) = dfs.next().await {
+                node?;
+                count += 1;
+                if count == 5 {
+                    token.cancel();
+                }
+            }
+            Ok::<_, anyhow::Error>(count)
+        });
+        let count = timeout(Duration::from_secs(10), handle).await???;
+        similar_asserts::assert_eq!(count, 5);
+        Ok(())
+    }
+
+    test_depths!(
+        dfs_max_nodes:
+        (
+            Dfs::<crate::utils::test::Node>::new(0, 3, true).max_nodes(5),
+            [1, 2, 3, 3, 2]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        dfs_max_nodes_without_max_depth:
+        (
+            Dfs::<crate::utils::test::Node>::new(0, None, true).max_nodes(5),
+            [1, 2, 3, 4, 5]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        dfs_max_depth_before_max_nodes:
+        (
+            Dfs::<crate::utils::test::Node>::new(0, 3, true).max_nodes(100),
+            [1, 2, 3, 3, 2, 3, 3, 1, 2, 3, 3, 2, 3, 3]
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    test_depths!(
+        dfs_zero_max_nodes:
+        (
+            Dfs::<crate::utils::test::Node>::new(0, 3, true).max_nodes(0),
+            Vec::<usize>::new()
+        ),
+        test_depths_ordered,
+        test_depths_unordered,
+    );
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_with_depth() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+        for (allow_circles, expected_depths) in [
+            (true, [1, 2, 3, 3, 2, 3, 3, 1, 2, 3, 3, 2, 3, 3].to_vec()),
+            (false, [1, 2, 3].to_vec()),
+        ] {
+            let dfs = Dfs::<crate::utils::test::Node>::new(0, 3, allow_circles);
+            let nodes = dfs
+                .with_depth()
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            // test nodes are created with the depth passed to children
+            assert!(nodes.iter().all(|(depth, node)| *depth == node.0));
+            let depths: Vec<_> = nodes.into_iter().map(|(depth, _)| depth).collect();
+            similar_asserts::assert_eq!(depths, expected_depths);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_post_order() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+        for (allow_circles, expected_depths) in [
+            (true, [3, 3, 2, 3, 3, 2, 1, 3, 3, 2, 3, 3, 2, 1].to_vec()),
+            (false, [3, 2, 1].to_vec()),
+        ] {
+            let dfs = DfsPostOrder::<crate::utils::test::Node>::new(0, 3, allow_circles);
+            let nodes = dfs
+                .with_depth()
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            assert!(nodes.iter().all(|(depth, node)| *depth == node.0));
+
+            // post-order is the reverse of the pre-order of the reversed children
+            let depths: Vec<_> = nodes.into_iter().map(|(depth, _)| depth).collect();
+            similar_asserts::assert_eq!(depths, expected_depths);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_new_multi() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+        let dfs = Dfs::<crate::utils::test::Node>::new_multi([10, 20, 10], 3, false);
+        let nodes = dfs
+            .with_depth()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let nodes: Vec<_> = nodes
+            .into_iter()
+            .map(|(depth, node)| (depth, node.0))
+            .collect();
+        // the descendants are shared by both roots
+        similar_asserts::assert_eq!(nodes, [(0, 10), (0, 20), (1, 1), (2, 2), (3, 3)]);
+
+        let dfs = Dfs::<crate::utils::test::Node>::new_multi(Vec::<usize>::new(), 3, false);
+        similar_asserts::assert_eq!(dfs.collect::<Vec<_>>().await.len(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dfs_silent_by_default() -> Result<()> {
+        use futures::StreamExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        /// Counts all events and spans.
+        #[derive(Default)]
+        struct Counter(Arc<AtomicUsize>);
+
+        impl tracing::Subscriber for Counter {
+            fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                tracing::span::Id::from_u64(1)
+            }
+            fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+            fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {
+            }
+            fn event(&self, _event: &tracing::Event<'_>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+            fn enter(&self, _span: &tracing::span::Id) {}
+            fn exit(&self, _span: &tracing::span::Id) {}
+        }
+
+        let counter = Counter::default();
+        let count = Arc::clone(&counter.0);
+        let nodes = tracing::subscriber::with_default(counter, || {
+            let dfs = Dfs::<crate::utils::test::Node>::new(0, 3, false);
+            tokio_test::block_on(dfs.collect::<Vec<_>>())
+        });
+        similar_asserts::assert_eq!(nodes.len(), 3);
+        // trace events are compiled out unless the `trace` feature is enabled
+        similar_asserts::assert_eq!(count.load(Ordering::SeqCst) > 0, cfg!(feature = "trace"));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_staggered_children_pre_order() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+        use tokio::time::{sleep, Duration};
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct Label(String);
+
+        #[async_trait::async_trait]
+        impl Node for Label {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                // shallower and earlier nodes take longer to expand
+                let slow = u64::from(self.0.ends_with('a'));
+                let delay = (4 - depth as u64) * 20 + slow * 10;
+                sleep(Duration::from_millis(delay)).await;
+                let nodes = ['a', 'b'].map(|c| Ok(Self(format!("{}{c}", self.0))));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let dfs = Dfs::<Label>::new(Label(String::new()), 3, false);
+        let labels = dfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let labels: Vec<_> = labels.into_iter().map(|label| label.0).collect();
+        similar_asserts::assert_eq!(
+            labels,
+            [
+                "a", "aa", "aaa", "aab", "ab", "aba", "abb", "b", "ba", "baa", "bab", "bb", "bba",
+                "bbb"
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_find() -> Result<()> {
+        use crate::utils::test::Node;
+        let dfs = Dfs::<Node>::new(0, 3, true);
+        similar_asserts::assert_eq!(dfs.find(|node| node.0 == 3).await?, Some(Node(3)));
+        let dfs = Dfs::<Node>::new(0, 3, true);
+        similar_asserts::assert_eq!(dfs.find(|node| node.0 == 4).await?, None);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_diamond_at_max_depth_is_yielded_once() -> Result<()> {
+        use crate::r#async::{AdjacencyMap, AdjacencyNode};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        // both parents of 3 reach it at the maximum depth
+        let graph: AdjacencyMap<u32> =
+            AdjacencyMap::from([(0, vec![1, 2]), (1, vec![3]), (2, vec![3])]);
+        let root = AdjacencyNode::new(Arc::new(graph), 0);
+        let dfs = Dfs::<AdjacencyNode<u32>>::new(root, 2, false);
+        let nodes = dfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let keys: Vec<_> = nodes.into_iter().map(AdjacencyNode::into_key).collect();
+        similar_asserts::assert_eq!(keys, [1, 3, 2]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_lower_depth_control() -> Result<()> {
+        use crate::r#async::{DepthControl, Traverse};
+        use futures::StreamExt;
+        let control = DepthControl::new(None);
+        let mut dfs = Dfs::<crate::utils::test::Node>::new_with_control(0, control.clone(), true)
+            .with_depth();
+        let mut depths = vec![];
+        while let Some(node) = dfs.next().await {
+            depths.push(node?.0);
+            if depths.len() == 3 {
+                control.set(2);
+            }
+        }
+        // the children of the expanded node at depth 3 still drain
+        similar_asserts::assert_eq!(depths, [1, 2, 3, 4, 4, 3, 2, 1, 2, 2]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_lower_depth_control_multi() -> Result<()> {
+        use crate::r#async::{AdjacencyNode, DepthControl, Traverse};
+        use futures::StreamExt;
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        let graph = Arc::new(HashMap::from([("a", vec!["b"]), ("x", vec!["y"])]));
+        let roots = ["a", "x"].map(|key| AdjacencyNode::new(Arc::clone(&graph), key));
+        let control = DepthControl::new(None);
+        let mut dfs = Dfs::<AdjacencyNode<&str>>::new_multi(roots, None, false)
+            .depth_control(control.clone())
+            .with_depth();
+        let mut nodes = vec![];
+        while let Some(node) = dfs.next().await {
+            let (depth, node) = node?;
+            nodes.push((depth, node.into_key()));
+            control.set(0);
+        }
+        // the deferred roots are not expanded beyond the lowered maximum depth
+        similar_asserts::assert_eq!(nodes, [(0, "a"), (0, "x")]);
+        assert!(dfs.into_inner().was_truncated());
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_allow_revisit() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(&'static str);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = std::convert::Infallible;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                // diamond, where "c" is reached via both "a" and "b"
+                let children = match self.0 {
+                    "root" => vec!["a", "b"],
+                    "a" | "b" => vec!["c"],
+                    "c" => vec!["d"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(Self).map(Result::Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+
+            fn allow_revisit(&self) -> bool {
+                self.0 == "c"
+            }
+        }
+
+        let dfs = Dfs::<NamedNode>::new(NamedNode("root"), None, false);
+        let nodes = dfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let names: Vec<_> = nodes.iter().map(|node| node.0).collect();
+        // "c" is yielded and expanded twice, but "d" only once
+        similar_asserts::assert_eq!(names, ["a", "c", "d", "b", "c"]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_is_terminal() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(&'static str);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = std::convert::Infallible;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                // cycle, where "back" leads back to "a"
+                let children = match self.0 {
+                    "root" => vec!["a"],
+                    "a" => vec!["b", "c"],
+                    "b" => vec!["back"],
+                    "back" => vec!["a"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(Self).map(Result::Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+
+            fn is_terminal(&self) -> bool {
+                self.0 == "back"
+            }
+        }
+
+        // the cycle is cut without tracking visited nodes
+        let dfs = Dfs::<NamedNode>::new(NamedNode("root"), None, true);
+        let nodes = dfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let names: Vec<_> = nodes.iter().map(|node| node.0).collect();
+        similar_asserts::assert_eq!(names, ["a", "b", "back", "c"]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_on_depth_change() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+        let mut depths = Vec::new();
+        let dfs = Dfs::<crate::utils::test::Node>::new(0, 2, true);
+        let count = dfs
+            .on_depth_change(|depth| depths.push(depth))
+            .count()
+            .await;
+        similar_asserts::assert_eq!(count, 6);
+        // fires again when backtracking to a shallower depth
+        similar_asserts::assert_eq!(depths, [1, 2, 1, 2]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_in_depth_range() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+
+        async fn depths<R>(range: R) -> Result<Vec<usize>>
+        where
+            R: std::ops::RangeBounds<usize>,
+        {
+            let nodes = Dfs::<crate::utils::test::Node>::in_depth_range(0, range, true)
+                .with_depth()
+                .take(8)
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(nodes.into_iter().map(|(depth, _)| depth).collect())
+        }
+
+        similar_asserts::assert_eq!(depths(1..=2).await?, [1, 2, 2, 1, 2, 2]);
+        similar_asserts::assert_eq!(depths(2..3).await?, [2, 2, 2, 2]);
+        similar_asserts::assert_eq!(depths(0..2).await?, [0, 1, 1]);
+        // without an upper bound, the depth is unlimited
+        similar_asserts::assert_eq!(depths(3..).await?, [3, 4, 5, 6, 7, 8, 9, 10]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_restart() -> Result<()> {
+        use futures::StreamExt;
+        for allow_circles in [true, false] {
+            let mut dfs = Dfs::<crate::utils::test::Node>::new(0, 3, allow_circles);
+            // restarting a partially consumed traversal starts from the root again
+            dfs.next().await.transpose()?;
+            let restarted = dfs.restart();
+            let first = dfs.collect::<Vec<_>>().await;
+            let second = restarted.collect::<Vec<_>>().await;
+            similar_asserts::assert_eq!(first[..], second[1..]);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_max_depth_reached() -> Result<()> {
+        use futures::StreamExt;
+        let mut dfs =
+            Dfs::<crate::utils::test::Node>::new(0, None, false).prune(|_node, depth| depth == 2);
+        similar_asserts::assert_eq!(dfs.max_depth_reached(), None);
+        while let Some(node) = dfs.next().await {
+            node?;
+        }
+        similar_asserts::assert_eq!(dfs.max_depth_reached(), Some(2));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_was_truncated() -> Result<()> {
+        use futures::StreamExt;
+        let mut dfs = Dfs::<crate::utils::test::Node>::new(0, 2, false);
+        similar_asserts::assert_eq!(dfs.was_truncated(), false);
+        while let Some(node) = dfs.next().await {
+            node?;
+        }
+        similar_asserts::assert_eq!(dfs.was_truncated(), true);
+
+        // the nodes at the maximum depth are never reached
+        let mut dfs =
+            Dfs::<crate::utils::test::Node>::new(0, 3, false).prune(|_node, depth| depth == 2);
+        while let Some(node) = dfs.next().await {
+            node?;
+        }
+        similar_asserts::assert_eq!(dfs.was_truncated(), false);
+
+        // the children of the root are beyond the maximum depth
+        let dfs = Dfs::<crate::utils::test::Node>::new(0, 0, false);
+        similar_asserts::assert_eq!(dfs.was_truncated(), true);
+        similar_asserts::assert_eq!(dfs.restart().was_truncated(), true);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_stats() -> Result<()> {
+        use futures::StreamExt;
+        let mut dfs = Dfs::<crate::utils::test::Node>::new(0, 3, true);
+        similar_asserts::assert_eq!(*dfs.stats(), crate::r#async::Stats::default());
+        while let Some(node) = dfs.next().await {
+            node?;
+        }
+        let stats = dfs.into_stats();
+        similar_asserts::assert_eq!(stats.nodes_emitted, 14);
+        // the root and all nodes above the maximum depth are expanded
+        similar_asserts::assert_eq!(stats.children_calls, 7);
+        similar_asserts::assert_eq!(stats.max_frontier_size, 1);
+        similar_asserts::assert_eq!(stats.max_stack_depth, 3);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_size_hint() -> Result<()> {
+        use futures::{Stream, StreamExt};
+        let mut dfs = Dfs::<crate::utils::test::Node>::new_multi([10, 20], 3, false);
+        similar_asserts::assert_eq!(dfs.size_hint(), (2, None));
+        while dfs.next().await.is_some() {}
+        similar_asserts::assert_eq!(dfs.size_hint(), (0, Some(0)));
+
+        let dfs = Dfs::<crate::utils::test::Node>::new_multi([10, 20], 3, false).max_nodes(1);
+        // errors could be yielded in addition to the nodes
+        similar_asserts::assert_eq!(dfs.size_hint(), (1, None));
+        let dfs = dfs.skip_errors(true);
+        similar_asserts::assert_eq!(dfs.size_hint(), (1, Some(1)));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_max_children_per_node() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        /// A node with infinitely many children, numbered from one.
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct Infinite(usize);
+
+        #[async_trait::async_trait]
+        impl Node for Infinite {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let nodes = (1..).map(Self).map(Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let dfs = Dfs::<Infinite>::new(Infinite(0), 2, true).max_children_per_node(5);
+        let nodes = dfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        // each of the 5 children has 5 children itself
+        similar_asserts::assert_eq!(nodes.len(), 5 + 5 * 5);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_reverse_children() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        /// A binary tree in heap order, where node `n` has the children `2n + 1` and `2n + 2`.
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct Tree(usize);
+
+        #[async_trait::async_trait]
+        impl Node for Tree {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let nodes = [2 * self.0 + 1, 2 * self.0 + 2]
+                    .into_iter()
+                    .map(Self)
+                    .map(Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        for (reverse_children, expected) in
+            [(false, [1, 3, 4, 2, 5, 6]), (true, [2, 6, 5, 1, 4, 3])]
+        {
+            let dfs = Dfs::<Tree>::new(Tree(0), 2, false).reverse_children(reverse_children);
+            let nodes = dfs
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            let nodes: Vec<_> = nodes.into_iter().map(|node| node.0).collect();
+            similar_asserts::assert_eq!(nodes, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_peek_depth() -> Result<()> {
+        use crate::r#async::Traverse;
+        let mut dfs = Dfs::<crate::utils::test::Node>::new_multi([10, 20], 2, true);
+        similar_asserts::assert_eq!(dfs.peek_depth(), Some(0));
+        similar_asserts::assert_eq!(dfs.frontier_len(), 2);
+
+        let mut num_nodes = 0;
+        while let Some(peeked) = dfs.peek_depth() {
+            let Some(node) = futures::future::poll_fn(|cx| {
+                std::pin::Pin::new(&mut dfs).poll_next_with_depth(cx)
+            })
+            .await
+            else {
+                break;
+            };
+            let (depth, _) = node?;
+            // the peeked depth is an upper bound, since child streams are not polled
+            assert!(peeked >= depth, "peeked {peeked} for depth {depth}");
+            num_nodes += 1;
+        }
+        similar_asserts::assert_eq!(num_nodes, 14);
+        similar_asserts::assert_eq!(dfs.peek_depth(), None);
+        similar_asserts::assert_eq!(dfs.frontier_len(), 0);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_is_visited() -> Result<()> {
+        use crate::utils::test::Node;
+        use futures::StreamExt;
+        for (allow_circles, expected_count) in [(false, 4), (true, 0)] {
+            let mut dfs = Dfs::<Node>::new(0, 3, allow_circles);
+            assert!(!dfs.is_visited(&Node(1)));
+            while let Some(node) = dfs.next().await {
+                node?;
+            }
+            similar_asserts::assert_eq!(dfs.is_visited(&Node(1)), !allow_circles);
+            assert!(!dfs.is_visited(&Node(4)));
+            similar_asserts::assert_eq!(dfs.visited_count(), expected_count);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_wrap_errors() -> Result<()> {
+        use crate::r#async::{Node, NodeStream, Traverse};
+        use crate::utils::test::Error;
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(String);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0.as_str() {
+                    "root" => vec![Ok("failing"), Err(Error), Ok("ok")],
+                    "failing" => return Err(Error),
+                    "ok" => vec![Ok("ok child")],
+                    _ => vec![],
+                };
+                let nodes = children
+                    .into_iter()
+                    .map(|child| child.map(|name| Self(name.into())));
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let root = NamedNode("root".into());
+        let dfs = Dfs::<NamedNode>::new(root, None, false);
+        let items: Vec<_> = dfs
+            .wrap_errors()
+            .map(|item| {
+                item.map(|node| node.0).map_err(|err| {
+                    assert_eq!(err.source, Error);
+                    (err.node.map(|node| node.0), err.depth)
+                })
+            })
+            .collect()
+            .await;
+        similar_asserts::assert_eq!(
+            items,
+            [
+                Ok("failing".to_string()),
+                Err((Some("failing".to_string()), 2)),
+                Err((Some("root".to_string()), 1)),
+                Ok("ok".to_string()),
+                Ok("ok child".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_children_error_continues_with_siblings() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use crate::utils::test::Error;
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(&'static str);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let children = match self.0 {
+                    "root" => vec!["a", "b"],
+                    "a" => return Err(Error),
+                    "b" => vec!["b1", "b2"],
+                    "b1" => vec!["b11"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(Self).map(Result::Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let mut dfs = Dfs::<NamedNode>::new(NamedNode("root"), None, false);
+        let mut items = Vec::new();
+        while let Some(item) = dfs.next().await {
+            items.push(item.map(|node| node.0));
+        }
+        similar_asserts::assert_eq!(
+            items,
+            [Ok("a"), Err(Error), Ok("b"), Ok("b1"), Ok("b11"), Ok("b2")]
+        );
+        similar_asserts::assert_eq!(dfs.frontier_len(), 0);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_is_fused() -> Result<()> {
+        use futures::stream::FusedStream;
+        use futures::StreamExt;
+
+        let mut dfs = Dfs::<crate::utils::test::Node>::new(0, 2, true);
+        assert!(!dfs.is_terminated());
+        similar_asserts::assert_eq!(dfs.by_ref().count().await, 6);
+        assert!(dfs.is_terminated());
+        assert!(dfs.next().await.is_none());
+        assert!(dfs.next().await.is_none());
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_expand_once() -> Result<()> {
+        use crate::r#async::{Node, NodeStream};
+        use futures::StreamExt;
+        use std::sync::Arc;
+
+        #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+        struct NamedNode(&'static str);
+
+        #[async_trait::async_trait]
+        impl Node for NamedNode {
+            type Error = std::convert::Infallible;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                // diamond, where "c" is reached via both "a" and "b"
+                let children = match self.0 {
+                    "root" => vec!["a", "b"],
+                    "a" | "b" => vec!["c"],
+                    "c" => vec!["d"],
+                    _ => vec![],
+                };
+                let nodes = children.into_iter().map(Self).map(Result::Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let mut dfs = Dfs::<NamedNode>::new(NamedNode("root"), None, true).expand_once(true);
+        let nodes = dfs
+            .by_ref()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let names: Vec<_> = nodes.iter().map(|node| node.0).collect();
+        similar_asserts::assert_eq!(names, ["a", "c", "d", "b", "c"]);
+        similar_asserts::assert_eq!(dfs.visited_count(), 5);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_lookahead_keeps_order() -> Result<()> {
+        use crate::r#async::Traverse;
+        use futures::StreamExt;
+
+        for allow_circles in [true, false] {
+            let serial = Dfs::<crate::utils::test::Node>::new(0, 3, allow_circles)
+                .with_depth()
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            for lookahead in [1, 4] {
+                let dfs =
+                    Dfs::<crate::utils::test::Node>::new(0, 3, allow_circles).lookahead(lookahead);
+                let nodes = dfs
+                    .with_depth()
+                    .collect::<Vec<_>>()
+                    .await
+                    .into_iter()
+                    .collect::<Result<Vec<_>, _>>()?;
+                similar_asserts::assert_eq!(nodes, serial);
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_lookahead_overlaps_children_calls() -> Result<()> {
+        use crate::r#async::{from_fn, FromFn, NodeStream};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let pending = Arc::new(AtomicUsize::new(0));
+        let max_pending = Arc::new(AtomicUsize::new(0));
+        let children = {
+            let pending = Arc::clone(&pending);
+            let max_pending = Arc::clone(&max_pending);
+            move |_, _| {
+                let pending = Arc::clone(&pending);
+                let max_pending = Arc::clone(&max_pending);
+                async move {
+                    let current = pending.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_pending.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    pending.fetch_sub(1, Ordering::SeqCst);
+                    let stream: NodeStream<usize, crate::utils::test::Error> =
+                        Box::pin(futures::stream::iter((0..4).map(Ok)));
+                    Ok(stream)
+                }
+            }
+        };
+        let dfs: Dfs<FromFn<usize, _>> = Dfs::new(from_fn(0, children), 3, true).lookahead(3);
+        similar_asserts::assert_eq!(dfs.count().await?, 4 + 16 + 64);
+        let max_pending = max_pending.load(Ordering::SeqCst);
+        assert!(max_pending > 1, "children calls did not overlap");
+        assert!(
+            max_pending <= 4,
+            "{max_pending} children calls exceed the lookahead"
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dfs_debug_snapshot() -> Result<()> {
+        use crate::r#async::TraversalSnapshot;
+        use futures::StreamExt;
+
+        let mut dfs = Dfs::<crate::utils::test::Node>::new(0, 2, true);
+        similar_asserts::assert_eq!(
+            dfs.debug_snapshot(),
+            TraversalSnapshot {
+                depth: Some(1),
+                pending_children: 0,
+                stack_depth: 0,
+                frontier_len: 1,
+                nodes_emitted: 0,
+            }
+        );
+        dfs.next().await.transpose()?;
+        similar_asserts::assert_eq!(
+            dfs.debug_snapshot(),
+            TraversalSnapshot {
+                depth: Some(2),
+                pending_children: 1,
+                stack_depth: 1,
+                frontier_len: 2,
+                nodes_emitted: 1,
+            }
+        );
+        while dfs.next().await.transpose()?.is_some() {}
+        similar_asserts::assert_eq!(
+            dfs.debug_snapshot(),
+            TraversalSnapshot {
+                nodes_emitted: 6,
+                ..TraversalSnapshot::default()
+            }
+        );
+        Ok(())
+    }
 }
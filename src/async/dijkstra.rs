@@ -0,0 +1,319 @@
+use super::{KeyedNode, VisitedSet, WeightedNode, WeightedNodeStream};
+
+use futures::stream::Stream;
+use futures::{Future, FutureExt};
+use pin_project::pin_project;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+type WeightedChildStream<N> =
+    WeightedNodeStream<N, <N as WeightedNode>::Cost, <N as WeightedNode>::Error>;
+
+type WeightedChildStreamFut<N> = Pin<
+    Box<
+        dyn Future<Output = Result<WeightedChildStream<N>, <N as WeightedNode>::Error>>
+            + Send
+            + 'static,
+    >,
+>;
+
+/// A node in the frontier of a [`Dijkstra`] traversal.
+///
+/// [`Dijkstra`]: struct@crate::async::Dijkstra
+struct Candidate<N>
+where
+    N: WeightedNode,
+{
+    cost: N::Cost,
+    /// The insertion order, used to break ties between nodes of equal cost.
+    seq: usize,
+    depth: usize,
+    node: Arc<N>,
+}
+
+impl<N> PartialEq for Candidate<N>
+where
+    N: WeightedNode,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N> Eq for Candidate<N> where N: WeightedNode {}
+
+impl<N> PartialOrd for Candidate<N>
+where
+    N: WeightedNode,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Candidate<N>
+where
+    N: WeightedNode,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        // the cheapest node is the greatest, since the heap yields the greatest first
+        other
+            .cost
+            .cmp(&self.cost)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Asynchronous shortest-path stream for types implementing the [`WeightedNode`] trait.
+///
+/// Yields the nodes reachable from the `root` in increasing order of
+/// the cumulative cost of their cheapest path, together with that cost.
+/// Each node is yielded at most once, as soon as its cheapest path is known.
+/// As usual for Dijkstra's algorithm, the costs must not be negative.
+///
+/// This generalizes the depth-based traversals:
+/// the depth of a node in a [`Bfs`] without circles is the cost of its cheapest path
+/// when every edge has unit cost.
+///
+/// Since the frontier must be complete before the next node can be chosen,
+/// only a single [`WeightedNode::children`] call is in flight at a time.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{Dijkstra, WeightedNode, WeightedNodeStream};
+///
+/// #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+/// struct City(&'static str);
+///
+/// #[async_trait::async_trait]
+/// impl WeightedNode for City {
+///     type Cost = u32;
+///     type Error = std::convert::Infallible;
+///
+///     async fn children(
+///         self: std::sync::Arc<Self>,
+///         _depth: usize
+///     ) -> Result<WeightedNodeStream<Self, Self::Cost, Self::Error>, Self::Error> {
+///         let roads = match self.0 {
+///             "A" => vec![(5, "B"), (1, "C")],
+///             "C" => vec![(1, "B")],
+///             _ => vec![],
+///         };
+///         let nodes = roads.into_iter()
+///             .map(|(cost, city)| Ok((cost, Self(city))));
+///         let stream = futures::stream::iter(nodes);
+///         Ok(Box::pin(stream.boxed()))
+///     }
+/// }
+///
+/// let result = tokio_test::block_on(async {
+///     let dijkstra = Dijkstra::<City>::new(City("A"));
+///     let output = dijkstra
+///         .collect::<Vec<_>>()
+///         .await
+///         .into_iter()
+///         .collect::<Result<Vec<_>, _>>()
+///         .unwrap();
+///     output.into_iter().map(|(cost, city)| (cost, city.0)).collect::<Vec<_>>()
+/// });
+/// assert_eq!(result, [(1, "C"), (2, "B")]);
+/// ```
+///
+/// [`WeightedNode`]: trait@crate::async::WeightedNode
+/// [`WeightedNode::children`]: fn@crate::async::WeightedNode::children
+/// [`Bfs`]: struct@crate::async::Bfs
+#[pin_project]
+pub struct Dijkstra<N, V = HashSet<<N as KeyedNode>::Key>>
+where
+    N: WeightedNode,
+{
+    frontier: BinaryHeap<Candidate<N>>,
+    num_discovered: usize,
+    /// The child stream of the last yielded node,
+    /// together with the node's cost and depth.
+    child_stream_fut: Option<(N::Cost, usize, WeightedChildStreamFut<N>)>,
+    current_stream: Option<(N::Cost, usize, WeightedChildStream<N>)>,
+    /// The nodes whose cheapest path is known.
+    visited: V,
+}
+
+impl<N> Dijkstra<N>
+where
+    N: WeightedNode + Send + Sync + 'static,
+    N::Cost: Send + 'static,
+    N::Error: Send + 'static,
+{
+    #[inline]
+    /// Creates a new [`Dijkstra`] stream from the `root` node.
+    ///
+    /// [`Dijkstra`]: struct@crate::async::Dijkstra
+    pub fn new<R>(root: R) -> Self
+    where
+        R: Into<N>,
+    {
+        Self::with_visited(root, HashSet::new())
+    }
+}
+
+impl<N, V> Dijkstra<N, V>
+where
+    N: WeightedNode + Send + Sync + 'static,
+    N::Cost: Send + 'static,
+    N::Error: Send + 'static,
+    V: VisitedSet<N>,
+{
+    #[inline]
+    /// Creates a new [`Dijkstra`] stream from the `root` node
+    /// with the given set of `visited` nodes.
+    ///
+    /// [`Dijkstra`]: struct@crate::async::Dijkstra
+    pub fn with_visited<R>(root: R, mut visited: V) -> Self
+    where
+        R: Into<N>,
+    {
+        let root = Arc::new(root.into());
+        visited.insert(&root);
+        let depth = 1;
+        let root_stream_fut = Box::pin(root.children(depth));
+
+        Self {
+            frontier: BinaryHeap::new(),
+            num_discovered: 0,
+            child_stream_fut: Some((N::Cost::default(), depth, root_stream_fut)),
+            current_stream: None,
+            visited,
+        }
+    }
+}
+
+impl<N, V> Stream for Dijkstra<N, V>
+where
+    N: WeightedNode + Send + Sync + Clone + 'static,
+    N::Cost: Send + 'static,
+    N::Error: Send + 'static,
+    V: VisitedSet<N>,
+{
+    type Item = Result<(N::Cost, N), N::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        loop {
+            // add all children of the last yielded node to the frontier
+            if let Some((_, _, fut)) = this.child_stream_fut.as_mut() {
+                let stream = futures::ready!(fut.poll_unpin(cx));
+                let (cost, depth, _) = this.child_stream_fut.take().unwrap();
+                let stream: WeightedChildStream<N> = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => Box::pin(futures::stream::iter([Err(err)])),
+                };
+                *this.current_stream = Some((cost, depth, stream));
+            }
+
+            let Some((cost, depth, stream)) = this.current_stream.as_mut() else {
+                break;
+            };
+            match futures::ready!(stream.as_mut().poll_next(cx)) {
+                Some(Err(err)) => {
+                    return Poll::Ready(Some(Err(err)));
+                }
+                Some(Ok((edge_cost, node))) => {
+                    if !this.visited.contains(&node) {
+                        let seq = *this.num_discovered;
+                        *this.num_discovered += 1;
+                        this.frontier.push(Candidate {
+                            cost: cost.clone() + edge_cost,
+                            seq,
+                            depth: *depth,
+                            node: Arc::new(node),
+                        });
+                    }
+                }
+                None => {
+                    *this.current_stream = None;
+                }
+            }
+        }
+
+        // the frontier is complete, continue with the cheapest node
+        while let Some(Candidate {
+            cost, depth, node, ..
+        }) = this.frontier.pop()
+        {
+            // the node was already reached on a cheaper path
+            if !this.visited.insert(&node) {
+                continue;
+            }
+            let child_stream_fut = Box::pin(Arc::clone(&node).children(depth + 1));
+            *this.child_stream_fut = Some((cost.clone(), depth + 1, child_stream_fut));
+            return Poll::Ready(Some(Ok((cost, Arc::unwrap_or_clone(node)))));
+        }
+        Poll::Ready(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dijkstra;
+    use crate::r#async::{WeightedNode, WeightedNodeStream};
+    use anyhow::Result;
+    use futures::StreamExt;
+    use std::sync::Arc;
+
+    #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    struct City(char);
+
+    #[async_trait::async_trait]
+    impl WeightedNode for City {
+        type Cost = u32;
+        type Error = crate::utils::test::Error;
+
+        async fn children(
+            self: Arc<Self>,
+            _depth: usize,
+        ) -> Result<WeightedNodeStream<Self, Self::Cost, Self::Error>, Self::Error> {
+            let roads = match self.0 {
+                'A' => vec![(5, 'B'), (1, 'C')],
+                'B' => vec![(1, 'D'), (0, 'A')],
+                'C' => vec![(1, 'B'), (9, 'D')],
+                _ => vec![],
+            };
+            let nodes = roads.into_iter().map(|(cost, city)| Ok((cost, Self(city))));
+            Ok(Box::pin(futures::stream::iter(nodes)))
+        }
+    }
+
+    async fn distances<S>(stream: S) -> Result<Vec<(u32, char)>>
+    where
+        S: futures::Stream<Item = Result<(u32, City), crate::utils::test::Error>>,
+    {
+        let nodes = stream
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(nodes
+            .into_iter()
+            .map(|(cost, city)| (cost, city.0))
+            .collect())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dijkstra_cheapest_paths() -> Result<()> {
+        let dijkstra = Dijkstra::<City>::new(City('A'));
+        similar_asserts::assert_eq!(distances(dijkstra).await?, [(1, 'C'), (2, 'B'), (3, 'D')]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dijkstra_with_visited() -> Result<()> {
+        let visited = std::collections::HashSet::from([City('B')]);
+        let dijkstra = Dijkstra::with_visited(City('A'), visited);
+        similar_asserts::assert_eq!(distances(dijkstra).await?, [(1, 'C'), (10, 'D')]);
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use super::{Node, Traverse};
+
+use futures::stream::{self, Stream, StreamExt};
+use pin_project::pin_project;
+use std::fmt::Display;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Formats `node` as a quoted [DOT] identifier.
+///
+/// [DOT]: https://graphviz.org/doc/info/lang.html
+fn quoted<N>(node: &N) -> String
+where
+    N: Display,
+{
+    let label = node.to_string().replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{label}\"")
+}
+
+/// Stream for the [`dot_edges`] method.
+///
+/// Yields one [DOT] edge statement per discovered edge.
+///
+/// [`dot_edges`]: fn@crate::async::Traverse::dot_edges
+/// [DOT]: https://graphviz.org/doc/info/lang.html
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct DotEdges<S> {
+    #[pin]
+    inner: S,
+}
+
+impl<S> DotEdges<S> {
+    pub(super) fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for DotEdges<S>
+where
+    S: Traverse,
+    S::Node: Display,
+{
+    type Item = Result<String, <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            let path = match futures::ready!(this.inner.as_mut().poll_next_path(cx)) {
+                Some(Ok(path)) => path,
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => return Poll::Ready(None),
+            };
+            // roots are not the child of any edge
+            if let Some(parent) = path.parent() {
+                let edge = format!(
+                    "{} -> {};",
+                    quoted(&**parent.node()),
+                    quoted(&**path.node())
+                );
+                return Poll::Ready(Some(Ok(edge)));
+            }
+        }
+    }
+}
+
+/// Wraps a stream of [DOT] statements, such as [`dot_edges`], in a `digraph { ... }`.
+///
+/// The statements are indented, and errors are passed through as is.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{digraph, Bfs, Node, NodeStream, Traverse};
+///
+/// #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+/// struct Number(u32);
+///
+/// impl std::fmt::Display for Number {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "{}", self.0)
+///     }
+/// }
+///
+/// #[async_trait::async_trait]
+/// impl Node for Number {
+///     type Error = std::convert::Infallible;
+///
+///     async fn children(
+///         self: std::sync::Arc<Self>,
+///         _depth: usize
+///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+///         let nodes = [Ok(Self(self.0 * 2)), Ok(Self(self.0 * 2 + 1))];
+///         Ok(Box::pin(futures::stream::iter(nodes)))
+///     }
+/// }
+///
+/// let result = tokio_test::block_on(async {
+///     let bfs = Bfs::<Number>::new(Number(1), 1, false);
+///     digraph(bfs.dot_edges())
+///         .collect::<Vec<_>>()
+///         .await
+///         .into_iter()
+///         .collect::<Result<Vec<_>, _>>()
+///         .unwrap()
+/// });
+/// assert_eq!(result, ["digraph {", "  \"1\" -> \"2\";", "  \"1\" -> \"3\";", "}"]);
+/// ```
+///
+/// [`dot_edges`]: fn@crate::async::Traverse::dot_edges
+/// [DOT]: https://graphviz.org/doc/info/lang.html
+pub fn digraph<S, E>(statements: S) -> impl Stream<Item = Result<String, E>>
+where
+    S: Stream<Item = Result<String, E>>,
+{
+    let statements =
+        statements.map(|statement| statement.map(|statement| format!("  {statement}")));
+    stream::once(async { Ok("digraph {".to_string()) })
+        .chain(statements)
+        .chain(stream::once(async { Ok("}".to_string()) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::digraph;
+    use crate::r#async::{Dfs, Node, NodeStream, Traverse};
+    use anyhow::Result;
+    use futures::StreamExt;
+    use std::sync::Arc;
+
+    #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    struct Label(&'static str);
+
+    impl std::fmt::Display for Label {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Node for Label {
+        type Error = crate::utils::test::Error;
+
+        async fn children(
+            self: Arc<Self>,
+            _depth: usize,
+        ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+            let children = match self.0 {
+                "root" => vec!["a", "say \"b\""],
+                "a" => vec!["c"],
+                _ => vec![],
+            };
+            let nodes = children.into_iter().map(|label| Ok(Self(label)));
+            Ok(Box::pin(futures::stream::iter(nodes)))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dot_edges() -> Result<()> {
+        let dfs = Dfs::<Label>::builder(Label("root"))
+            .include_root(true)
+            .build();
+        let lines = digraph(dfs.dot_edges())
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        similar_asserts::assert_eq!(
+            lines,
+            [
+                "digraph {",
+                r#"  "root" -> "a";"#,
+                r#"  "a" -> "c";"#,
+                r#"  "root" -> "say \"b\"";"#,
+                "}",
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use super::{Node, Traverse};
+
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Forwards the nodes of the `traversal` into `tx`, reserving a slot before each poll.
+pub(super) async fn drain_into<T>(
+    traversal: T,
+    tx: mpsc::Sender<Result<T::Node, <T::Node as Node>::Error>>,
+) -> Result<(), mpsc::error::SendError<()>>
+where
+    T: Traverse,
+    T::Node: Clone,
+{
+    let mut traversal = std::pin::pin!(traversal);
+    loop {
+        // wait for the consumer before the traversal makes progress
+        let permit = tx.reserve().await?;
+        let Some(item) = futures::future::poll_fn(|cx| traversal.as_mut().poll_next_path(cx)).await
+        else {
+            return Ok(());
+        };
+        permit.send(item.map(|path| Arc::unwrap_or_clone(path.into_node())));
+    }
+}
@@ -0,0 +1,131 @@
+use super::Path;
+
+use std::sync::Arc;
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+
+/// Error when a [`Node::children`] call did not complete in time.
+///
+/// Returned for nodes whose children could not be produced
+/// within the per-call timeout of a traversal.
+///
+/// [`Node::children`]: fn@crate::async::Node::children
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[error("children at depth {depth} timed out after {timeout:?}")]
+pub struct TimeoutError {
+    /// The depth of the children that timed out.
+    pub depth: usize,
+    /// The timeout that was exceeded.
+    pub timeout: Duration,
+}
+
+/// Error when a [`Node::children`] future panicked.
+///
+/// Returned for nodes whose children panicked while panics are caught
+/// by a traversal.
+///
+/// [`Node::children`]: fn@crate::async::Node::children
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq, Hash)]
+#[error("children at depth {depth} panicked: {message}")]
+pub struct PanicError {
+    /// The depth of the children that panicked.
+    pub depth: usize,
+    /// The panic message, if it is a string.
+    pub message: String,
+}
+
+impl PanicError {
+    /// Creates the error of a panic at `depth` from its `payload`.
+    #[inline]
+    pub(super) fn new(depth: usize, payload: &(dyn std::any::Any + Send)) -> Self {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        Self { depth, message }
+    }
+}
+
+/// Error of a traversal, together with the node whose children failed.
+///
+/// Returned by the [`wrap_errors`] adapter, which attaches the parent node
+/// and the depth of the failed [`Node::children`] call or child stream item to each error.
+///
+/// [`wrap_errors`]: fn@crate::async::Traverse::wrap_errors
+/// [`Node::children`]: fn@crate::async::Node::children
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraversalError<N, E> {
+    /// The node whose children failed, if known.
+    pub node: Option<N>,
+    /// The depth of the children that failed.
+    pub depth: usize,
+    /// The error of the node.
+    pub source: E,
+}
+
+impl<N, E> TraversalError<Arc<N>, E> {
+    /// Creates the error of a child of the last node of `parent`.
+    #[inline]
+    pub(super) fn child_of(parent: &Path<N>, source: E) -> Self {
+        Self {
+            node: Some(Arc::clone(parent.node())),
+            depth: parent.depth() + 1,
+            source,
+        }
+    }
+}
+
+impl<N, E> TraversalError<N, E> {
+    /// Consumes this error, returning the error of the node.
+    #[inline]
+    pub fn into_source(self) -> E {
+        self.source
+    }
+
+    /// Maps the node of this error.
+    #[inline]
+    pub fn map_node<M, F>(self, f: F) -> TraversalError<M, E>
+    where
+        F: FnOnce(N) -> M,
+    {
+        TraversalError {
+            node: self.node.map(f),
+            depth: self.depth,
+            source: self.source,
+        }
+    }
+}
+
+impl<N, E> std::fmt::Display for TraversalError<N, E>
+where
+    N: std::fmt::Debug,
+    E: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.node {
+            Some(node) => write!(
+                f,
+                "children of {node:?} at depth {} failed: {:?}",
+                self.depth, self.source
+            ),
+            None => write!(
+                f,
+                "children at depth {} failed: {:?}",
+                self.depth, self.source
+            ),
+        }
+    }
+}
+
+impl<N, E> std::error::Error for TraversalError<N, E>
+where
+    N: std::fmt::Debug,
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
@@ -0,0 +1,356 @@
+use super::{NewNodesFut, Node, NodeStream, PanicError, Path};
+
+#[cfg(feature = "tokio")]
+use super::{RetryPolicy, TimeoutError};
+use futures::{FutureExt, StreamExt, TryFutureExt};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+#[cfg(feature = "trace")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+
+/// Converts a [`TimeoutError`] into the error type of the nodes.
+#[cfg(feature = "tokio")]
+type IntoTimeoutError<E> = fn(TimeoutError) -> E;
+
+/// Converts a [`PanicError`] into the error type of the nodes.
+type IntoPanicError<E> = fn(PanicError) -> E;
+
+/// Creates the future retrying the [`Node::children`] call of a node.
+///
+/// [`Node::children`]: fn@crate::async::Node::children
+#[cfg(feature = "tokio")]
+type RetryFn<N> =
+    fn(&Expansion<N>, RetryPolicy, Arc<N>, usize) -> NewNodesFut<N, <N as Node>::Error>;
+
+/// The child stream of the last node of a [`Path`].
+///
+/// [`Path`]: struct@crate::async::Path
+pub(super) type ChildStream<N, E> = (Arc<Path<N>>, Result<NodeStream<N, E>, E>);
+
+/// A future resolving to the child stream of the last node of a [`Path`].
+///
+/// [`Path`]: struct@crate::async::Path
+pub(super) struct ChildStreamFut<N, E> {
+    parent: Option<Arc<Path<N>>>,
+    fut: NewNodesFut<N, E>,
+}
+
+impl<N, E> ChildStreamFut<N, E> {
+    /// Returns the depth of the child stream.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.parent.as_ref().map_or(0, |parent| parent.depth() + 1)
+    }
+
+    /// Returns the path to the node whose children this future resolves to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the future already completed.
+    #[inline]
+    pub fn parent(&self) -> &Arc<Path<N>> {
+        self.parent
+            .as_ref()
+            .expect("child stream future polled after completion")
+    }
+}
+
+impl<N, E> Future for ChildStreamFut<N, E> {
+    type Output = ChildStream<N, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let stream = futures::ready!(this.fut.poll_unpin(cx));
+        let parent = this
+            .parent
+            .take()
+            .expect("child stream future polled after completion");
+        Poll::Ready((parent, stream))
+    }
+}
+
+/// A [`ChildStreamFut`] that is polled ahead of time and keeps its output once completed.
+pub(super) enum Prefetch<N, E> {
+    Pending(ChildStreamFut<N, E>),
+    Ready(ChildStream<N, E>),
+}
+
+impl<N, E> std::fmt::Debug for Prefetch<N, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pending(_) => f.write_str("Pending"),
+            Self::Ready(_) => f.write_str("Ready"),
+        }
+    }
+}
+
+impl<N, E> Prefetch<N, E>
+where
+    N: Send + 'static,
+    E: Send + 'static,
+{
+    /// Polls the future, keeping its output once it completed.
+    #[inline]
+    pub fn poll(&mut self, cx: &mut Context<'_>) {
+        if let Self::Pending(fut) = self {
+            if let Poll::Ready(child_stream) = fut.poll_unpin(cx) {
+                *self = Self::Ready(child_stream);
+            }
+        }
+    }
+
+    /// Converts back into a future resolving to the child stream.
+    #[inline]
+    pub fn into_future(self) -> ChildStreamFut<N, E> {
+        match self {
+            Self::Pending(fut) => fut,
+            Self::Ready((parent, stream)) => ChildStreamFut {
+                parent: Some(parent),
+                fut: Box::pin(futures::future::ready(stream)),
+            },
+        }
+    }
+}
+
+/// Creates the futures resolving to the child streams of nodes.
+pub(super) struct Expansion<N>
+where
+    N: Node,
+{
+    #[cfg(feature = "tokio")]
+    timeout: Option<(Duration, IntoTimeoutError<N::Error>)>,
+    #[cfg(feature = "tokio")]
+    retry: Option<(RetryPolicy, RetryFn<N>)>,
+    catch_panics: Option<IntoPanicError<N::Error>>,
+    max_children: Option<usize>,
+    reverse_children: bool,
+    /// The number of [`Node::children`] calls so far, to identify their spans.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    #[cfg(feature = "trace")]
+    calls: AtomicUsize,
+    node: PhantomData<fn() -> N>,
+}
+
+impl<N> Default for Expansion<N>
+where
+    N: Node,
+{
+    #[inline]
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "tokio")]
+            timeout: None,
+            #[cfg(feature = "tokio")]
+            retry: None,
+            catch_panics: None,
+            max_children: None,
+            reverse_children: false,
+            #[cfg(feature = "trace")]
+            calls: AtomicUsize::new(0),
+            node: PhantomData,
+        }
+    }
+}
+
+impl<N> Expansion<N>
+where
+    N: Node + Send + 'static,
+    N::Error: Send + 'static,
+{
+    /// Fails [`Node::children`] calls that do not complete within `timeout`.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    #[cfg(feature = "tokio")]
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Duration)
+    where
+        N::Error: From<TimeoutError>,
+    {
+        self.timeout = Some((timeout, N::Error::from));
+    }
+
+    /// Retries failed [`Node::children`] calls according to `retry`.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    #[cfg(feature = "tokio")]
+    #[inline]
+    pub fn set_retry(&mut self, retry: RetryPolicy)
+    where
+        N: Sync,
+    {
+        self.retry = Some((retry, Self::retrying_child_stream_fut));
+    }
+
+    /// Converts panics of [`Node::children`] futures into errors.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    #[inline]
+    pub fn set_catch_panics(&mut self)
+    where
+        N::Error: From<PanicError>,
+    {
+        self.catch_panics = Some(N::Error::from);
+    }
+
+    /// Truncates the child stream of each node after `max_children` items.
+    #[inline]
+    pub fn set_max_children(&mut self, max_children: usize) {
+        self.max_children = Some(max_children);
+    }
+
+    /// Reverses the order in which the children of each node are emitted.
+    #[inline]
+    pub fn set_reverse_children(&mut self, reverse_children: bool) {
+        self.reverse_children = reverse_children;
+    }
+
+    /// Creates the future resolving to the child stream of the last node of `parent`.
+    ///
+    /// With the `trace` feature, the future is instrumented with a `children` span
+    /// at the debug level, which records the depth of the children and the index
+    /// of the call, and emits an event once the child stream is ready.
+    #[inline]
+    pub fn child_stream_fut(&self, parent: Arc<Path<N>>) -> ChildStreamFut<N, N::Error> {
+        let depth = parent.depth() + 1;
+        let mut fut = self.children_fut(Arc::clone(parent.node()), depth);
+        #[cfg(feature = "trace")]
+        {
+            use tracing::Instrument;
+            let index = self.calls.fetch_add(1, Ordering::Relaxed);
+            let span = tracing::debug_span!("children", depth, index);
+            fut = Box::pin(
+                async move {
+                    let stream = fut.await;
+                    tracing::debug!(ok = stream.is_ok(), "children resolved");
+                    stream
+                }
+                .instrument(span),
+            );
+        }
+        if let Some(max_children) = self.max_children {
+            // truncate before reversing, which buffers the entire child stream
+            fut = Box::pin(fut.map_ok(move |stream| {
+                let stream: NodeStream<N, N::Error> = Box::pin(stream.take(max_children));
+                stream
+            }));
+        }
+        if self.reverse_children {
+            fut = Box::pin(fut.then(|stream| async move {
+                // the entire child stream is buffered to reverse it
+                let mut children: Vec<_> = stream?.collect().await;
+                children.reverse();
+                let stream: NodeStream<N, N::Error> = Box::pin(futures::stream::iter(children));
+                Ok(stream)
+            }));
+        }
+        ChildStreamFut {
+            parent: Some(parent),
+            fut,
+        }
+    }
+
+    /// Creates the future resolving to the child stream of `node` at `depth`.
+    ///
+    /// Without a timeout or retries, this is the future returned by [`Node::children`],
+    /// which is not boxed again.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    #[inline]
+    fn children_fut(&self, node: Arc<N>, depth: usize) -> NewNodesFut<N, N::Error> {
+        #[cfg(feature = "tokio")]
+        if let Some((retry, retrying_child_stream_fut)) = self.retry {
+            return retrying_child_stream_fut(self, retry, node, depth);
+        }
+
+        #[cfg(feature = "tokio")]
+        if let Some((timeout, into_err)) = self.timeout {
+            let fut = self.call_children(node, depth);
+            return Box::pin(async move {
+                tokio::time::timeout(timeout, fut)
+                    .await
+                    .unwrap_or_else(|_| Err(into_err(TimeoutError { depth, timeout })))
+            });
+        }
+
+        self.call_children(node, depth)
+    }
+
+    /// Calls [`Node::children`] of `node` at `depth`,
+    /// converting a panic of the returned future into an error if panics are caught.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    #[inline]
+    fn call_children(&self, node: Arc<N>, depth: usize) -> NewNodesFut<N, N::Error> {
+        let fut = node.children(depth);
+        match self.catch_panics {
+            Some(into_err) => Box::pin(AssertUnwindSafe(fut).catch_unwind().map(move |stream| {
+                stream.unwrap_or_else(|payload| Err(into_err(PanicError::new(depth, &*payload))))
+            })),
+            None => fut,
+        }
+    }
+
+    /// Creates the future resolving to the child stream of `node` at `depth`,
+    /// retrying failed [`Node::children`] calls according to `retry`.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    #[cfg(feature = "tokio")]
+    fn retrying_child_stream_fut(
+        &self,
+        retry: RetryPolicy,
+        node: Arc<N>,
+        depth: usize,
+    ) -> NewNodesFut<N, N::Error>
+    where
+        N: Sync,
+    {
+        let expansion = Self {
+            timeout: self.timeout,
+            retry: None,
+            catch_panics: self.catch_panics,
+            max_children: None,
+            reverse_children: false,
+            #[cfg(feature = "trace")]
+            calls: AtomicUsize::new(0),
+            node: PhantomData,
+        };
+        Box::pin(async move {
+            let mut attempt = 0;
+            loop {
+                let stream = expansion.children_fut(Arc::clone(&node), depth).await;
+                if stream.is_ok() || attempt >= retry.max_retries {
+                    return stream;
+                }
+                drop(stream);
+                tokio::time::sleep(retry.delay(attempt)).await;
+                attempt += 1;
+            }
+        })
+    }
+}
+
+/// Converts the result of a [`Node::children`] call into a [`NodeStream`],
+/// which yields the error as its only item if the call failed.
+///
+/// The stream of a successful call is used as is, without boxing it again.
+///
+/// [`Node::children`]: fn@crate::async::Node::children
+/// [`NodeStream`]: type@crate::async::NodeStream
+#[inline]
+pub(super) fn into_node_stream<N, E>(stream: Result<NodeStream<N, E>, E>) -> NodeStream<N, E>
+where
+    N: Send + 'static,
+    E: Send + 'static,
+{
+    match stream {
+        Ok(stream) => stream,
+        Err(err) => Box::pin(futures::stream::iter([Err(err)])),
+    }
+}
@@ -0,0 +1,30 @@
+use super::{Node, Traverse};
+
+use std::sync::Arc;
+
+/// Consumes the `traversal` until the first node matching `predicate`.
+///
+/// The traversal is dropped as soon as a match is found,
+/// together with its pending [`Node::children`] futures.
+///
+/// [`Node::children`]: fn@crate::async::Node::children
+pub(super) async fn find<T, F>(
+    traversal: T,
+    mut predicate: F,
+) -> Result<Option<T::Node>, <T::Node as Node>::Error>
+where
+    T: Traverse,
+    T::Node: Clone,
+    F: FnMut(&T::Node) -> bool,
+{
+    let mut traversal = std::pin::pin!(traversal);
+    while let Some(path) =
+        futures::future::poll_fn(|cx| traversal.as_mut().poll_next_path(cx)).await
+    {
+        let node = path?.into_node();
+        if predicate(&node) {
+            return Ok(Some(Arc::unwrap_or_clone(node)));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,22 @@
+use super::{Node, Traverse};
+
+/// Consumes the `traversal`, calling `f` with a reference to each node and its depth.
+///
+/// The nodes are borrowed from the traversal, hence they are never cloned.
+pub(super) async fn for_each_ref<T, F>(
+    traversal: T,
+    mut f: F,
+) -> Result<(), <T::Node as Node>::Error>
+where
+    T: Traverse,
+    F: FnMut(&T::Node, usize),
+{
+    let mut traversal = std::pin::pin!(traversal);
+    while let Some(path) =
+        futures::future::poll_fn(|cx| traversal.as_mut().poll_next_path(cx)).await
+    {
+        let path = path?;
+        f(path.node(), path.depth());
+    }
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use super::{from_iter, Node, NodeStream};
+
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Creates a [`Node`] whose children are computed by the async closure `children`.
+///
+/// Unlike [`from_fn`], the closure awaits inside its body and returns the values
+/// of the children as an iterator, e.g. a [`Vec`], instead of a [`NodeStream`].
+/// It is called with the value of each node and its depth, and is shared by all nodes
+/// of the traversal. The future it returns must not borrow from the closure,
+/// hence `async move` closures that capture shared state by [`Arc`] work best.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{from_async_fn, Bfs, FromAsyncFn};
+/// use std::path::PathBuf;
+///
+/// let root = from_async_fn(
+///     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src"),
+///     async move |path: PathBuf, _depth| {
+///         let mut dirs = Vec::new();
+///         let mut entries = tokio::fs::read_dir(&path).await?;
+///         while let Some(entry) = entries.next_entry().await? {
+///             if entry.file_type().await?.is_dir() {
+///                 dirs.push(entry.path());
+///             }
+///         }
+///         Ok::<_, std::io::Error>(dirs)
+///     },
+/// );
+///
+/// let runtime = tokio::runtime::Runtime::new().unwrap();
+/// let mut dirs = runtime.block_on(async {
+///     let bfs: Bfs<FromAsyncFn<PathBuf, _>> = Bfs::new(root, 1, false);
+///     bfs.map(|dir| dir.unwrap().into_value())
+///         .collect::<Vec<_>>()
+///         .await
+/// });
+/// dirs.sort();
+/// let names: Vec<_> = dirs.iter().map(|dir| dir.file_name().unwrap()).collect();
+/// assert_eq!(names, ["async", "sync"]);
+/// ```
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`NodeStream`]: type@crate::async::NodeStream
+/// [`from_fn`]: fn@crate::async::from_fn
+/// [`Vec`]: struct@std::vec::Vec
+/// [`Arc`]: struct@std::sync::Arc
+pub fn from_async_fn<T, F>(root: T, children: F) -> FromAsyncFn<T, F> {
+    FromAsyncFn {
+        value: root,
+        children: Arc::new(children),
+    }
+}
+
+/// A [`Node`] whose children are computed by an async closure.
+///
+/// Nodes are compared and hashed by their value only.
+///
+/// See [`from_async_fn`] for more details.
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`from_async_fn`]: fn@crate::async::from_async_fn
+pub struct FromAsyncFn<T, F> {
+    value: T,
+    children: Arc<F>,
+}
+
+impl<T, F> FromAsyncFn<T, F> {
+    #[inline]
+    #[must_use]
+    /// Returns the value of the node.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    #[inline]
+    #[must_use]
+    /// Consumes the node, returning its value.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<T, F> Clone for FromAsyncFn<T, F>
+where
+    T: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            children: Arc::clone(&self.children),
+        }
+    }
+}
+
+impl<T, F> PartialEq for FromAsyncFn<T, F>
+where
+    T: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T, F> Eq for FromAsyncFn<T, F> where T: Eq {}
+
+impl<T, F> Hash for FromAsyncFn<T, F>
+where
+    T: Hash,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T, F> std::fmt::Debug for FromAsyncFn<T, F>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FromAsyncFn").field(&self.value).finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, F, Fut, I, E> Node for FromAsyncFn<T, F>
+where
+    T: Hash + Eq + Clone + std::fmt::Debug + Send + Sync + 'static,
+    F: Fn(T, usize) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<I, E>> + Send + 'static,
+    I: IntoIterator<Item = T>,
+    I::IntoIter: Send + 'static,
+    E: std::fmt::Debug + Send + 'static,
+{
+    type Error = E;
+
+    async fn children(
+        self: Arc<Self>,
+        depth: usize,
+    ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+        let children = (self.children)(self.value.clone(), depth).await?;
+        let shared = Arc::clone(&self.children);
+        Ok(from_iter(children.into_iter().map(move |value| Self {
+            value,
+            children: Arc::clone(&shared),
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_async_fn, FromAsyncFn};
+    use crate::r#async::{Bfs, Traverse};
+    use anyhow::Result;
+    use futures::StreamExt;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_from_async_fn_bfs() -> Result<()> {
+        let root = from_async_fn(1u32, async move |value, _depth| {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            Ok::<_, crate::utils::test::Error>(vec![value * 10 + 1, value * 10 + 2])
+        });
+        let bfs: Bfs<FromAsyncFn<u32, _>> = Bfs::new(root, 2, false);
+        let nodes = bfs
+            .with_depth()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let nodes: Vec<_> = nodes
+            .into_iter()
+            .map(|(depth, node)| (depth, *node.value()))
+            .collect();
+        similar_asserts::assert_eq!(
+            nodes,
+            [(1, 11), (1, 12), (2, 111), (2, 112), (2, 121), (2, 122)]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use super::{Node, NodeStream};
+
+use futures::StreamExt;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Creates a [`Node`] whose children are given by the closure `children`.
+///
+/// The closure is called with the value of each node and its depth,
+/// and returns a [`NodeStream`] of the values of its children.
+/// It is shared by all nodes of the traversal.
+///
+/// This is the asynchronous analog of [`std::iter::from_fn`] for graphs.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{from_fn, Bfs, FromFn, NodeStream};
+///
+/// let root = from_fn(1u32, |value, _depth| async move {
+///     let children = futures::stream::iter([Ok(2 * value), Ok(2 * value + 1)]);
+///     Ok::<NodeStream<u32, std::convert::Infallible>, _>(Box::pin(children))
+/// });
+///
+/// let result = tokio_test::block_on(async {
+///     let bfs: Bfs<FromFn<u32, _>> = Bfs::new(root, 2, false);
+///     let output = bfs
+///         .collect::<Vec<_>>()
+///         .await
+///         .into_iter()
+///         .collect::<Result<Vec<_>, _>>()
+///         .unwrap();
+///     output.into_iter().map(|node| *node.value()).collect::<Vec<_>>()
+/// });
+/// assert_eq!(result, [2, 3, 4, 5, 6, 7]);
+/// ```
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`NodeStream`]: type@crate::async::NodeStream
+/// [`std::iter::from_fn`]: fn@std::iter::from_fn
+pub fn from_fn<T, F>(root: T, children: F) -> FromFn<T, F> {
+    FromFn {
+        value: root,
+        children: Arc::new(children),
+    }
+}
+
+/// A [`Node`] whose children are given by a closure.
+///
+/// Nodes are compared and hashed by their value only.
+///
+/// See [`from_fn`] for more details.
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`from_fn`]: fn@crate::async::from_fn
+pub struct FromFn<T, F> {
+    value: T,
+    children: Arc<F>,
+}
+
+impl<T, F> FromFn<T, F> {
+    #[inline]
+    #[must_use]
+    /// Returns the value of the node.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    #[inline]
+    #[must_use]
+    /// Consumes the node, returning its value.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<T, F> Clone for FromFn<T, F>
+where
+    T: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            children: Arc::clone(&self.children),
+        }
+    }
+}
+
+impl<T, F> PartialEq for FromFn<T, F>
+where
+    T: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T, F> Eq for FromFn<T, F> where T: Eq {}
+
+impl<T, F> Hash for FromFn<T, F>
+where
+    T: Hash,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T, F> std::fmt::Debug for FromFn<T, F>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FromFn").field(&self.value).finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, F, Fut, E> Node for FromFn<T, F>
+where
+    T: Hash + Eq + Clone + std::fmt::Debug + Send + Sync + 'static,
+    F: Fn(T, usize) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<NodeStream<T, E>, E>> + Send + 'static,
+    E: std::fmt::Debug + Send + 'static,
+{
+    type Error = E;
+
+    async fn children(
+        self: Arc<Self>,
+        depth: usize,
+    ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+        let children = (self.children)(self.value.clone(), depth).await?;
+        let shared = Arc::clone(&self.children);
+        let children = children.map(move |value| {
+            value.map(|value| Self {
+                value,
+                children: Arc::clone(&shared),
+            })
+        });
+        Ok(Box::pin(children))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_fn, FromFn};
+    use crate::r#async::{Dfs, NodeStream, Traverse};
+    use anyhow::Result;
+    use futures::StreamExt;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_from_fn_dfs() -> Result<()> {
+        let root = from_fn(1u32, |value, _depth| async move {
+            let children = futures::stream::iter([Ok(value * 10 + 1), Ok(value * 10 + 2)]);
+            Ok::<NodeStream<u32, crate::utils::test::Error>, _>(Box::pin(children))
+        });
+        let dfs: Dfs<FromFn<u32, _>> = Dfs::new(root, 2, false);
+        let nodes = dfs
+            .with_depth()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let nodes: Vec<_> = nodes
+            .into_iter()
+            .map(|(depth, node)| (depth, *node.value()))
+            .collect();
+        similar_asserts::assert_eq!(
+            nodes,
+            [(1, 11), (2, 111), (2, 112), (1, 12), (2, 121), (2, 122)]
+        );
+        Ok(())
+    }
+}
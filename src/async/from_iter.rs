@@ -0,0 +1,46 @@
+use super::NodeStream;
+
+/// Creates a [`NodeStream`] of the children yielded by a synchronous `iter`.
+///
+/// This is convenient when the children of a node are computed rather than fetched,
+/// e.g. when they are already collected in a [`Vec`]. The iterator is consumed lazily
+/// as the traversal polls the stream, and the stream is boxed only once.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{from_iter, Bfs, BorrowNode, NodeStream};
+///
+/// #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// struct Num(u32);
+///
+/// #[async_trait::async_trait]
+/// impl BorrowNode for Num {
+///     type Error = std::convert::Infallible;
+///
+///     async fn children(&self, _depth: usize) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+///         Ok(from_iter([Num(2 * self.0), Num(2 * self.0 + 1)]))
+///     }
+/// }
+///
+/// let result = tokio_test::block_on(async {
+///     Bfs::<Num>::new(Num(1), 2, false)
+///         .map(|node| node.unwrap().0)
+///         .collect::<Vec<_>>()
+///         .await
+/// });
+/// assert_eq!(result, [2, 3, 4, 5, 6, 7]);
+/// ```
+///
+/// [`NodeStream`]: type@crate::async::NodeStream
+/// [`Vec`]: struct@std::vec::Vec
+#[inline]
+pub fn from_iter<I, E>(iter: I) -> NodeStream<I::Item, E>
+where
+    I: IntoIterator,
+    I::IntoIter: Send + 'static,
+    I::Item: Send + 'static,
+    E: Send + 'static,
+{
+    Box::pin(futures::stream::iter(iter.into_iter().map(Ok)))
+}
@@ -0,0 +1,179 @@
+use super::{KeyedNode, Node, NodeStream};
+
+use futures::StreamExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A [`Node`] of a file system tree, for walking directories with [`tokio::fs`].
+///
+/// The children of a directory are its entries, in the order they are returned by
+/// [`tokio::fs::read_dir`], which is platform dependent.
+/// Files are leaves without children.
+/// Symbolic links are followed.
+///
+/// Nodes are keyed by their canonicalized path, so that symbolic link loops
+/// are detected as cycles unless `allow_circles` is set.
+/// Entries that can not be canonicalized, e.g. broken symbolic links,
+/// are yielded as errors.
+///
+/// ### Example
+/// ```no_run
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{Bfs, FsNode};
+///
+/// # tokio_test::block_on(async {
+/// let root = FsNode::new("./").await?;
+/// let mut bfs = Bfs::<FsNode>::new(root, 2, false);
+/// while let Some(node) = bfs.next().await {
+///     println!("{}", node?.path().display());
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// # });
+/// ```
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`tokio::fs`]: mod@tokio::fs
+/// [`tokio::fs::read_dir`]: fn@tokio::fs::read_dir
+#[derive(Debug, Clone)]
+pub struct FsNode {
+    path: PathBuf,
+    canonical_path: PathBuf,
+    is_dir: bool,
+}
+
+impl FsNode {
+    /// Creates the node of the file or directory at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `path` can not be canonicalized
+    /// or its metadata can not be read.
+    pub async fn new<P>(path: P) -> std::io::Result<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        let path = path.into();
+        let canonical_path = tokio::fs::canonicalize(&path).await?;
+        let is_dir = tokio::fs::metadata(&canonical_path).await?.is_dir();
+        Ok(Self {
+            path,
+            canonical_path,
+            is_dir,
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the path of the node, as it was reached during the traversal.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the canonicalized path of the node.
+    pub fn canonical_path(&self) -> &Path {
+        &self.canonical_path
+    }
+
+    #[inline]
+    #[must_use]
+    /// Consumes the node, returning its path.
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns `true` if the node is a directory.
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+}
+
+impl KeyedNode for FsNode {
+    type Key = PathBuf;
+
+    #[inline]
+    fn key(&self) -> Self::Key {
+        self.canonical_path.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for FsNode {
+    type Error = std::io::Error;
+
+    async fn children(
+        self: Arc<Self>,
+        _depth: usize,
+    ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+        if !self.is_dir {
+            return Ok(Box::pin(futures::stream::empty()));
+        }
+        let entries = tokio::fs::read_dir(&self.path).await?;
+        let children = futures::stream::unfold(Some(entries), |entries| async move {
+            let mut entries = entries?;
+            match entries.next_entry().await {
+                Ok(Some(entry)) => Some((Self::new(entry.path()).await, Some(entries))),
+                Ok(None) => None,
+                // stop reading the directory after the first error
+                Err(err) => Some((Err(err), None)),
+            }
+        });
+        Ok(Box::pin(children.boxed()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FsNode;
+    use crate::r#async::Bfs;
+    use anyhow::Result;
+    use futures::StreamExt;
+    use std::path::PathBuf;
+
+    /// Creates an empty directory for a test, removing any leftovers of previous runs.
+    fn test_dir(name: &str) -> Result<PathBuf> {
+        let dir = std::env::temp_dir().join(format!("par-dfs-{}-{name}", std::process::id()));
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fs_node_walks_directories() -> Result<()> {
+        let dir = test_dir("walk")?;
+        std::fs::create_dir_all(dir.join("a/b"))?;
+        std::fs::write(dir.join("a/file"), "")?;
+        std::fs::write(dir.join("a/b/file"), "")?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.join("a"), dir.join("a/b/loop"))?;
+
+        let bfs = Bfs::<FsNode>::new(FsNode::new(dir.join("a")).await?, None, false);
+        let nodes = bfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut paths: Vec<_> = nodes
+            .iter()
+            .map(|node| node.path().strip_prefix(&dir).map(PathBuf::from))
+            .collect::<Result<_, _>>()?;
+        paths.sort();
+        std::fs::remove_dir_all(&dir)?;
+
+        // the symbolic link back to the root is detected as a cycle
+        similar_asserts::assert_eq!(
+            paths,
+            [
+                PathBuf::from("a/b"),
+                PathBuf::from("a/b/file"),
+                PathBuf::from("a/file")
+            ]
+        );
+        Ok(())
+    }
+}
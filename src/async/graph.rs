@@ -0,0 +1,101 @@
+use super::{Node, Traverse};
+
+use petgraph::graph::{Graph, NodeIndex};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Returns the index of `node` in the `graph`, adding the node if it is not yet part of it.
+fn node_index<N>(
+    graph: &mut Graph<N, ()>,
+    indices: &mut HashMap<N::Key, NodeIndex>,
+    node: &Arc<N>,
+) -> NodeIndex
+where
+    N: Node + Clone,
+{
+    *indices
+        .entry(node.key())
+        .or_insert_with(|| graph.add_node(N::clone(node)))
+}
+
+/// Consumes the `traversal`, collecting the nodes and the edges
+/// along which they were discovered into a [`Graph`].
+///
+/// [`Graph`]: struct@petgraph::graph::Graph
+pub(super) async fn collect_graph<T>(
+    traversal: T,
+) -> Result<Graph<T::Node, ()>, <T::Node as Node>::Error>
+where
+    T: Traverse,
+    T::Node: Clone,
+{
+    let mut graph = Graph::new();
+    let mut indices = HashMap::new();
+    let mut traversal = std::pin::pin!(traversal);
+    while let Some(path) =
+        futures::future::poll_fn(|cx| traversal.as_mut().poll_next_path(cx)).await
+    {
+        let path = path?;
+        let child = node_index(&mut graph, &mut indices, path.node());
+        if let Some(parent) = path.parent() {
+            let parent = node_index(&mut graph, &mut indices, parent.node());
+            graph.update_edge(parent, child, ());
+        }
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::r#async::{Bfs, Dfs, Node, NodeStream};
+    use anyhow::Result;
+    use std::sync::Arc;
+
+    /// A cycle `0 -> 1 -> 2 -> 0`.
+    #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    struct Cycle(usize);
+
+    #[async_trait::async_trait]
+    impl Node for Cycle {
+        type Error = crate::utils::test::Error;
+
+        async fn children(
+            self: Arc<Self>,
+            _depth: usize,
+        ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+            let nodes = [Ok(Self((self.0 + 1) % 3))];
+            Ok(Box::pin(futures::stream::iter(nodes)))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_collect_graph_tree() -> Result<()> {
+        let bfs = Bfs::<crate::utils::test::Node>::new(0, 3, false);
+        let graph = bfs.collect_graph().await?;
+        // test nodes are created with the depth passed to children
+        let mut edges: Vec<_> = graph
+            .raw_edges()
+            .iter()
+            .map(|edge| (graph[edge.source()].0, graph[edge.target()].0))
+            .collect();
+        edges.sort_unstable();
+        similar_asserts::assert_eq!(graph.node_count(), 4);
+        similar_asserts::assert_eq!(edges, [(0, 1), (1, 2), (2, 3)]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_collect_graph_cycles() -> Result<()> {
+        let dfs = Dfs::<Cycle>::new(Cycle(0), 5, false);
+        let graph = dfs.collect_graph().await?;
+        similar_asserts::assert_eq!(graph.edge_count(), 2);
+        assert!(!petgraph::algo::is_cyclic_directed(&graph));
+
+        let dfs = Dfs::<Cycle>::new(Cycle(0), 5, true);
+        let graph = dfs.collect_graph().await?;
+        similar_asserts::assert_eq!(graph.node_count(), 3);
+        similar_asserts::assert_eq!(graph.edge_count(), 3);
+        assert!(petgraph::algo::is_cyclic_directed(&graph));
+        Ok(())
+    }
+}
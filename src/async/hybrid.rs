@@ -0,0 +1,237 @@
+use super::{Bfs, Dfs, KeyedNode, Node, Path, TraversalError, Traverse};
+
+use futures::stream::Stream;
+use std::collections::{HashSet, VecDeque};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Asynchronous hybrid breadth-first and depth-first stream
+/// for types implementing the [`Node`] trait.
+///
+/// Runs a [`Bfs`] from the `root` up to the `switch_depth` to fan out,
+/// and then a [`Dfs`] from each node at the `switch_depth` to go deep quickly.
+/// Hence, all nodes up to the `switch_depth` are yielded level by level,
+/// followed by the subtrees of the nodes at the `switch_depth` in pre-order,
+/// in the order their roots were yielded.
+///
+/// The nodes at the `switch_depth` are kept until the breadth-first phase completes.
+/// The `visited` nodes are shared between both phases.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{Node, Hybrid, NodeStream};
+///
+/// #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+/// struct Number(u32);
+///
+/// #[async_trait::async_trait]
+/// impl Node for Number {
+///     type Error = std::convert::Infallible;
+///
+///     async fn children(
+///         self: std::sync::Arc<Self>,
+///         _depth: usize
+///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+///         let nodes = [self.0 * 10 + 1, self.0 * 10 + 2].into_iter().map(Self).map(Result::Ok);
+///         Ok(Box::pin(futures::stream::iter(nodes)))
+///     }
+/// }
+///
+/// let result = tokio_test::block_on(async {
+///     let hybrid = Hybrid::<Number>::new(Number(0), 3, true, 1);
+///     let output = hybrid
+///         .collect::<Vec<_>>()
+///         .await
+///         .into_iter()
+///         .collect::<Result<Vec<_>, _>>()
+///         .unwrap();
+///     output.into_iter().map(|n| n.0).collect::<Vec<_>>()
+/// });
+/// assert_eq!(result, [1, 2, 11, 111, 112, 12, 121, 122, 21, 211, 212, 22, 221, 222]);
+/// ```
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`Bfs`]: struct@crate::async::Bfs
+/// [`Dfs`]: struct@crate::async::Dfs
+#[allow(clippy::module_name_repetitions)]
+pub struct Hybrid<N>
+where
+    N: Node,
+{
+    bfs: Option<Bfs<N>>,
+    dfs: Option<Dfs<N>>,
+    /// The nodes at the `switch_depth` that were yielded by the breadth-first phase.
+    switch_nodes: VecDeque<Arc<Path<N>>>,
+    switch_depth: usize,
+    max_depth: Option<usize>,
+    allow_circles: bool,
+}
+
+// the fields of a hybrid are never pinned
+impl<N> Unpin for Hybrid<N> where N: Node {}
+
+impl<N> Hybrid<N>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    #[inline]
+    /// Creates a new [`Hybrid`] stream.
+    ///
+    /// The traversal will be performed from the `root` node up to depth `max_depth`,
+    /// breadth-first up to depth `switch_depth` and depth-first beyond.
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    ///
+    /// [`Hybrid`]: struct@crate::async::Hybrid
+    pub fn new<R, D>(root: R, max_depth: D, allow_circles: bool, switch_depth: usize) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        let root = Arc::new(root.into());
+        let max_depth = max_depth.into();
+        let mut hybrid = Self {
+            bfs: None,
+            dfs: None,
+            switch_nodes: VecDeque::new(),
+            switch_depth,
+            max_depth,
+            allow_circles,
+        };
+        if switch_depth == 0 {
+            // there are no levels to fan out
+            hybrid.dfs = Some(Dfs::from_shared(
+                root,
+                max_depth,
+                allow_circles,
+                HashSet::new(),
+            ));
+        } else {
+            let bfs_depth = max_depth.map_or(switch_depth, |max_depth| max_depth.min(switch_depth));
+            hybrid.bfs = Some(Bfs::from_shared(
+                root,
+                Some(bfs_depth),
+                allow_circles,
+                HashSet::new(),
+            ));
+        }
+        hybrid
+    }
+
+    /// Returns `true` if the nodes at the `switch_depth` are expanded depth-first.
+    fn expands_switch_nodes(&self) -> bool {
+        self.max_depth
+            .is_none_or(|max_depth| self.switch_depth < max_depth)
+    }
+
+    /// Switches to the depth-first phase.
+    fn switch(&mut self) {
+        let Some(bfs) = self.bfs.take() else {
+            return;
+        };
+        let visited: HashSet<<N as KeyedNode>::Key> = bfs.into_visited();
+        self.dfs = Some(Dfs::from_parts(
+            VecDeque::new(),
+            std::mem::take(&mut self.switch_nodes),
+            self.max_depth,
+            self.allow_circles,
+            visited,
+        ));
+    }
+}
+
+impl<N> Traverse for Hybrid<N>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    type Node = N;
+
+    fn poll_next_path_with_context(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Path<N>, TraversalError<Arc<N>, N::Error>>>> {
+        let this = self.get_mut();
+        if let Some(bfs) = this.bfs.as_mut() {
+            match futures::ready!(Pin::new(bfs).poll_next_path_with_context(cx)) {
+                Some(Ok(path)) => {
+                    if path.depth() == this.switch_depth && this.expands_switch_nodes() {
+                        this.switch_nodes.push_back(Arc::new(path.clone()));
+                    }
+                    return Poll::Ready(Some(Ok(path)));
+                }
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                // breadth-first phase completed
+                None => this.switch(),
+            }
+        }
+        match this.dfs.as_mut() {
+            Some(dfs) => Pin::new(dfs).poll_next_path_with_context(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl<N> Stream for Hybrid<N>
+where
+    N: Node + Send + Clone + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    type Item = Result<N, N::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with_depth(cx)
+            .map(|item| item.map(|item| item.map(|(_, node)| Arc::unwrap_or_clone(node))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Hybrid;
+    use crate::r#async::Traverse;
+    use anyhow::Result;
+    use futures::StreamExt;
+
+    async fn depths(hybrid: Hybrid<crate::utils::test::Node>) -> Result<Vec<usize>> {
+        let nodes = hybrid
+            .with_depth()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(nodes.into_iter().map(|(depth, _)| depth).collect())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_hybrid_switches_to_dfs() -> Result<()> {
+        let hybrid = Hybrid::<crate::utils::test::Node>::new(0, 4, true, 2);
+        let subtree = [3, 4, 4, 3, 4, 4];
+        let mut expected = vec![1, 1, 2, 2, 2, 2];
+        for _ in 0..4 {
+            expected.extend(subtree);
+        }
+        similar_asserts::assert_eq!(depths(hybrid).await?, expected);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_hybrid_switch_depth_bounds() -> Result<()> {
+        // switching at the root is a plain dfs
+        let hybrid = Hybrid::<crate::utils::test::Node>::new(0, 2, true, 0);
+        similar_asserts::assert_eq!(depths(hybrid).await?, [1, 2, 2, 1, 2, 2]);
+        // switching at or beyond the maximum depth is a plain bfs
+        let hybrid = Hybrid::<crate::utils::test::Node>::new(0, 2, true, 2);
+        similar_asserts::assert_eq!(depths(hybrid).await?, [1, 1, 2, 2, 2, 2]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_hybrid_no_circles() -> Result<()> {
+        let hybrid = Hybrid::<crate::utils::test::Node>::new(0, 4, false, 2);
+        similar_asserts::assert_eq!(depths(hybrid).await?, [1, 2, 3, 4]);
+        Ok(())
+    }
+}
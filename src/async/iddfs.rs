@@ -0,0 +1,281 @@
+use super::{Dfs, Node, Path, TraversalError, Traverse};
+
+use futures::stream::Stream;
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Asynchronous iterative deepening depth-first stream for types implementing the [`Node`] trait.
+///
+/// Runs a depth-limited [`Dfs`] from the `root` for the limits `1, 2, 3, ...`
+/// up to `max_depth` and yields the nodes that are reached at the limit of each round.
+/// This combines the memory profile of a DFS with the level-by-level
+/// completeness of a BFS.
+///
+/// Every round starts over from the `root`, hence all nodes above the
+/// current limit are expanded again, and their `children` are requested
+/// once per round.
+/// This also means that errors of those nodes are yielded again in every round.
+/// When no `max_depth` is given, the traversal stops after the first round
+/// that did not reach any node at its limit.
+///
+/// The `visited` nodes are reset between rounds.
+/// Nodes that can be reached at different depths may therefore be yielded more than once,
+/// unless [`dedup`] is enabled.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{Node, Iddfs, NodeStream};
+///
+/// #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+/// struct WordNode(String);
+///
+/// #[async_trait::async_trait]
+/// impl Node for WordNode {
+///     type Error = std::convert::Infallible;
+///
+///     async fn children(
+///         self: std::sync::Arc<Self>,
+///         _depth: usize
+///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+///         let len = self.0.len();
+///         let nodes: Vec<String> = if len < 2 {
+///             vec![]
+///         } else {
+///             let mid = len/2;
+///             vec![self.0[..mid].into(), self.0[mid..].into()]
+///         };
+///         let nodes = nodes.into_iter()
+///             .map(Self)
+///             .map(Result::Ok);
+///         let stream = futures::stream::iter(nodes);
+///         Ok(Box::pin(stream.boxed()))
+///     }
+/// }
+///
+/// let result = tokio_test::block_on(async {
+///     let root = WordNode("Hello World".into());
+///     let iddfs = Iddfs::<WordNode>::new(root, None, true);
+///     let output = iddfs
+///         .collect::<Vec<_>>()
+///         .await
+///         .into_iter()
+///         .collect::<Result<Vec<_>, _>>()
+///         .unwrap();
+///     output.into_iter().map(|s| s.0).collect::<Vec<_>>()
+/// });
+/// assert_eq!(&result[..2], ["Hello", " World"]);
+/// ```
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`Dfs`]: struct@crate::async::Dfs
+/// [`dedup`]: fn@crate::async::Iddfs::dedup
+#[allow(clippy::module_name_repetitions)]
+pub struct Iddfs<N>
+where
+    N: Node,
+{
+    root: Arc<N>,
+    current: Option<Dfs<N>>,
+    depth_limit: usize,
+    max_depth: Option<usize>,
+    allow_circles: bool,
+    reached_limit: bool,
+    emitted: Option<HashSet<N::Key>>,
+}
+
+// the fields of an iddfs are never pinned
+impl<N> Unpin for Iddfs<N> where N: Node {}
+
+impl<N> Iddfs<N>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    #[inline]
+    /// Creates a new [`Iddfs`] stream.
+    ///
+    /// The iterative deepening will be performed from the `root` node up to depth `max_depth`.
+    ///
+    /// When `allow_circles`, visited nodes will not be tracked, which can lead to cycles.
+    ///
+    /// [`Iddfs`]: struct@crate::async::Iddfs
+    pub fn new<R, D>(root: R, max_depth: D, allow_circles: bool) -> Self
+    where
+        R: Into<N>,
+        D: Into<Option<usize>>,
+    {
+        let mut iddfs = Self {
+            root: Arc::new(root.into()),
+            current: None,
+            depth_limit: 0,
+            max_depth: max_depth.into(),
+            allow_circles,
+            reached_limit: true,
+            emitted: None,
+        };
+        iddfs.next_round();
+        iddfs
+    }
+
+    #[inline]
+    #[must_use]
+    /// Skips nodes that have already been yielded in a previous round.
+    pub fn dedup(mut self, dedup: bool) -> Self {
+        self.emitted = dedup.then(HashSet::new);
+        self
+    }
+
+    /// Starts the next round with an increased depth limit.
+    ///
+    /// Returns `false` if the traversal is complete.
+    fn next_round(&mut self) -> bool {
+        let depth_limit = self.depth_limit + 1;
+        let exceeds_max_depth = self
+            .max_depth
+            .is_some_and(|max_depth| depth_limit > max_depth);
+        if !self.reached_limit || exceeds_max_depth {
+            self.current = None;
+            return false;
+        }
+        let dfs = Dfs::from_shared(
+            Arc::clone(&self.root),
+            Some(depth_limit),
+            self.allow_circles,
+            HashSet::new(),
+        );
+        self.current = Some(dfs.min_depth(depth_limit));
+        self.depth_limit = depth_limit;
+        self.reached_limit = false;
+        true
+    }
+}
+
+impl<N> Traverse for Iddfs<N>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    type Node = N;
+
+    fn poll_next_path_with_context(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Path<N>, TraversalError<Arc<N>, N::Error>>>> {
+        let this = self.get_mut();
+        loop {
+            let Some(current) = this.current.as_mut() else {
+                return Poll::Ready(None);
+            };
+            match Pin::new(current).poll_next_path_with_context(cx) {
+                Poll::Ready(Some(Ok(path))) => {
+                    this.reached_limit = true;
+                    if let Some(emitted) = &mut this.emitted {
+                        if !emitted.insert(path.node().key()) {
+                            continue;
+                        }
+                    }
+                    return Poll::Ready(Some(Ok(path)));
+                }
+                Poll::Ready(Some(Err(err))) => {
+                    return Poll::Ready(Some(Err(err)));
+                }
+                // round completed
+                Poll::Ready(None) => {
+                    if !this.next_round() {
+                        return Poll::Ready(None);
+                    }
+                }
+                Poll::Pending => {
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+impl<N> Stream for Iddfs<N>
+where
+    N: Node + Send + Clone + Unpin + 'static,
+    N::Error: Send + 'static,
+{
+    type Item = Result<N, N::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with_depth(cx)
+            .map(|item| item.map(|item| item.map(|(_, node)| Arc::unwrap_or_clone(node))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Iddfs;
+    use anyhow::Result;
+
+    macro_rules! depths {
+        ($stream:ident) => {{
+            $stream
+                // collect the entire stream
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                // fail on first error
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                // get depth
+                .map(|item| item.0)
+                .collect::<Vec<_>>()
+        }};
+    }
+
+    macro_rules! test_depths_ordered {
+        ($name:ident: $values:expr) => {
+            paste::item! {
+                #[tokio::test(flavor = "multi_thread")]
+                async fn [< test_ $name _ ordered >] () -> Result<()> {
+                    use std::cmp::Ordering;
+                    use futures::StreamExt;
+                    let (iter, expected_depths) = $values;
+                    let depths = depths!(iter);
+                    assert!(crate::utils::test::is_monotonic(&depths, Ordering::Greater));
+                    similar_asserts::assert_eq!(depths, expected_depths);
+                    Ok(())
+                }
+            }
+        };
+    }
+
+    test_depths_ordered!(
+        iddfs:
+        (
+            Iddfs::<crate::utils::test::Node>::new(0, 3, true),
+            [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 3]
+        )
+    );
+
+    test_depths_ordered!(
+        iddfs_no_circles:
+        (
+            Iddfs::<crate::utils::test::Node>::new(0, 3, false),
+            [1, 2, 3]
+        )
+    );
+
+    test_depths_ordered!(
+        iddfs_dedup:
+        (
+            Iddfs::<crate::utils::test::Node>::new(0, 3, true).dedup(true),
+            [1, 2, 3]
+        )
+    );
+
+    test_depths_ordered!(
+        iddfs_zero_max_depth:
+        (
+            Iddfs::<crate::utils::test::Node>::new(0, 0, true),
+            Vec::<usize>::new()
+        )
+    );
+}
@@ -0,0 +1,49 @@
+use super::{Node, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Stream for the [`infallible`] method.
+///
+/// Yields the nodes of a traversal that can not fail.
+///
+/// [`infallible`]: fn@crate::async::Traverse::infallible
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct InfallibleNodes<S> {
+    #[pin]
+    inner: S,
+}
+
+impl<S> InfallibleNodes<S> {
+    pub(super) fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for InfallibleNodes<S>
+where
+    S: Traverse,
+    S::Node: Node<Error = Infallible> + Clone,
+{
+    type Item = S::Node;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().inner.poll_next_with_depth(cx).map(|item| {
+            item.map(|item| match item {
+                Ok((_, node)) => Arc::unwrap_or_clone(node),
+                Err(err) => match err {},
+            })
+        })
+    }
+}
@@ -0,0 +1,67 @@
+use super::{Node, Path, TraversalError, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Stream for the [`inspect_errors`] method.
+///
+/// Calls a function with each error of a traversal before yielding it unchanged.
+///
+/// [`inspect_errors`]: fn@crate::async::Traverse::inspect_errors
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct InspectErrors<S, F> {
+    #[pin]
+    inner: S,
+    f: F,
+}
+
+impl<S, F> InspectErrors<S, F> {
+    pub(super) fn new(inner: S, f: F) -> Self {
+        Self { inner, f }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, F> Traverse for InspectErrors<S, F>
+where
+    S: Traverse,
+    F: FnMut(&<S::Node as Node>::Error),
+{
+    type Node = S::Node;
+
+    fn poll_next_path_with_context(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Path<S::Node>, TraversalError<Arc<S::Node>, <S::Node as Node>::Error>>>>
+    {
+        let this = self.project();
+        let item = futures::ready!(this.inner.poll_next_path_with_context(cx));
+        if let Some(Err(err)) = &item {
+            (this.f)(&err.source);
+        }
+        Poll::Ready(item)
+    }
+}
+
+impl<S, F> Stream for InspectErrors<S, F>
+where
+    S: Traverse,
+    S::Node: Clone,
+    F: FnMut(&<S::Node as Node>::Error),
+{
+    type Item = Result<S::Node, <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with_depth(cx)
+            .map(|item| item.map(|item| item.map(|(_, node)| Arc::unwrap_or_clone(node))))
+    }
+}
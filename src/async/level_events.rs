@@ -0,0 +1,115 @@
+use super::{Node, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// An item of the [`level_events`] stream.
+///
+/// [`level_events`]: fn@crate::async::Bfs::level_events
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LevelEvent<N> {
+    /// A node of the traversal.
+    Node(N),
+    /// All nodes at `depth` were yielded.
+    LevelComplete {
+        /// The depth of the completed level.
+        depth: usize,
+        /// The number of nodes of the completed level.
+        count: usize,
+    },
+}
+
+/// Stream for the [`level_events`] method.
+///
+/// Yields the nodes of a traversal, followed by a [`LevelEvent::LevelComplete`]
+/// after the last node of each depth.
+///
+/// [`level_events`]: fn@crate::async::Bfs::level_events
+/// [`LevelEvent::LevelComplete`]: enum@crate::async::LevelEvent
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct LevelEvents<S>
+where
+    S: Traverse,
+{
+    #[pin]
+    inner: S,
+    /// The depth and the number of nodes of the current level.
+    level: Option<(usize, usize)>,
+    /// The first node of the next level, which is yielded after the current level completed.
+    next: Option<(usize, S::Node)>,
+    done: bool,
+}
+
+impl<S> LevelEvents<S>
+where
+    S: Traverse,
+{
+    pub(super) fn new(inner: S) -> Self {
+        Self {
+            inner,
+            level: None,
+            next: None,
+            done: false,
+        }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    ///
+    /// The first node of the next level is lost if it has not been yielded yet.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for LevelEvents<S>
+where
+    S: Traverse,
+    S::Node: Clone,
+{
+    type Item = Result<LevelEvent<S::Node>, <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if let Some((depth, node)) = this.next.take() {
+            *this.level = Some((depth, 1));
+            return Poll::Ready(Some(Ok(LevelEvent::Node(node))));
+        }
+        if *this.done {
+            return Poll::Ready(None);
+        }
+        match futures::ready!(this.inner.as_mut().poll_next_with_depth(cx)) {
+            Some(Err(err)) => Poll::Ready(Some(Err(err))),
+            Some(Ok((depth, node))) => {
+                let node = Arc::unwrap_or_clone(node);
+                match this.level {
+                    Some((level, count)) if *level == depth => *count += 1,
+                    // the next level begins, hence the current level is complete
+                    Some((level, count)) => {
+                        let complete = LevelEvent::LevelComplete {
+                            depth: *level,
+                            count: *count,
+                        };
+                        *this.next = Some((depth, node));
+                        return Poll::Ready(Some(Ok(complete)));
+                    }
+                    None => *this.level = Some((depth, 1)),
+                }
+                Poll::Ready(Some(Ok(LevelEvent::Node(node))))
+            }
+            None => {
+                // complete the last level
+                *this.done = true;
+                let complete = this
+                    .level
+                    .take()
+                    .map(|(depth, count)| Ok(LevelEvent::LevelComplete { depth, count }));
+                Poll::Ready(complete)
+            }
+        }
+    }
+}
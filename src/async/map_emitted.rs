@@ -0,0 +1,48 @@
+use super::{Node, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Stream for the [`map_emitted`] method.
+///
+/// Yields the result of applying a function to each node,
+/// while the traversal continues to expand the original nodes.
+///
+/// [`map_emitted`]: fn@crate::async::Traverse::map_emitted
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct MapEmitted<S, F> {
+    #[pin]
+    inner: S,
+    f: F,
+}
+
+impl<S, F> MapEmitted<S, F> {
+    pub(super) fn new(inner: S, f: F) -> Self {
+        Self { inner, f }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, F, M> Stream for MapEmitted<S, F>
+where
+    S: Traverse,
+    F: FnMut(&S::Node) -> M,
+{
+    type Item = Result<M, <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let f = this.f;
+        this.inner
+            .poll_next_path(cx)
+            .map(|item| item.map(|item| item.map(|path| f(path.node()))))
+    }
+}
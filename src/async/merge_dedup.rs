@@ -0,0 +1,234 @@
+use super::{KeyedNode, Node, Path, TraversalError, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Merges two traversals over the same nodes, yielding each node only the first time
+/// its key is seen in either traversal.
+///
+/// The traversals are polled in turns: after an item of one traversal was yielded,
+/// the other traversal is polled first. If that traversal is not ready, the first one is
+/// polled again, hence a slow traversal does not hold back the other. The merged stream
+/// ends once both traversals are exhausted.
+///
+/// Errors of either traversal are yielded as they occur and do not end the merged stream,
+/// just as a single traversal continues after an error.
+/// Both traversals track their visited nodes independently,
+/// the deduplication only affects the yielded nodes.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{merge_dedup, AdjacencyNode, Bfs};
+/// use std::collections::HashMap;
+/// use std::sync::Arc;
+///
+/// let forward = Arc::new(HashMap::from([("a", vec!["b", "c"])]));
+/// let secondary = Arc::new(HashMap::from([("a", vec!["c", "d"])]));
+///
+/// let nodes = tokio_test::block_on(async {
+///     let a = Bfs::<AdjacencyNode<&str>>::new(AdjacencyNode::new(forward, "a"), None, false);
+///     let b = Bfs::<AdjacencyNode<&str>>::new(AdjacencyNode::new(secondary, "a"), None, false);
+///     merge_dedup(a, b)
+///         .map(|node| *node.unwrap().key())
+///         .collect::<Vec<_>>()
+///         .await
+/// });
+/// assert_eq!(nodes, ["b", "c", "d"]);
+/// ```
+pub fn merge_dedup<A, B>(a: A, b: B) -> MergeDedup<A, B, <A::Node as KeyedNode>::Key>
+where
+    A: Traverse,
+    B: Traverse<Node = A::Node>,
+{
+    MergeDedup {
+        a: Some(a),
+        b: Some(b),
+        b_first: false,
+        seen: HashSet::new(),
+    }
+}
+
+/// Stream for the [`merge_dedup`] function.
+///
+/// [`merge_dedup`]: fn@crate::async::merge_dedup
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct MergeDedup<A, B, K> {
+    /// The first traversal, until it is exhausted.
+    #[pin]
+    a: Option<A>,
+    /// The second traversal, until it is exhausted.
+    #[pin]
+    b: Option<B>,
+    /// Whether the second traversal is polled first.
+    b_first: bool,
+    /// The keys of the nodes yielded so far.
+    seen: HashSet<K>,
+}
+
+type Item<N> = Result<Path<N>, TraversalError<Arc<N>, <N as Node>::Error>>;
+
+/// Polls the traversal in `slot`, clearing it once it is exhausted.
+fn poll_slot<T>(mut slot: Pin<&mut Option<T>>, cx: &mut Context<'_>) -> Poll<Option<Item<T::Node>>>
+where
+    T: Traverse,
+{
+    let Some(traversal) = slot.as_mut().as_pin_mut() else {
+        return Poll::Ready(None);
+    };
+    let item = futures::ready!(traversal.poll_next_path_with_context(cx));
+    if item.is_none() {
+        slot.set(None);
+    }
+    Poll::Ready(item)
+}
+
+impl<A, B> Traverse for MergeDedup<A, B, <A::Node as KeyedNode>::Key>
+where
+    A: Traverse,
+    B: Traverse<Node = A::Node>,
+{
+    type Node = A::Node;
+
+    fn poll_next_path_with_context(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Item<A::Node>>> {
+        let mut this = self.project();
+        loop {
+            let (item, from_b) = if *this.b_first {
+                match poll_slot(this.b.as_mut(), cx) {
+                    Poll::Ready(Some(item)) => (Some(item), true),
+                    _ => match poll_slot(this.a.as_mut(), cx) {
+                        Poll::Ready(Some(item)) => (Some(item), false),
+                        _ => (None, false),
+                    },
+                }
+            } else {
+                match poll_slot(this.a.as_mut(), cx) {
+                    Poll::Ready(Some(item)) => (Some(item), false),
+                    _ => match poll_slot(this.b.as_mut(), cx) {
+                        Poll::Ready(Some(item)) => (Some(item), true),
+                        _ => (None, false),
+                    },
+                }
+            };
+            let Some(item) = item else {
+                if this.a.is_none() && this.b.is_none() {
+                    return Poll::Ready(None);
+                }
+                return Poll::Pending;
+            };
+            // give the other traversal the first turn next time
+            *this.b_first = !from_b;
+            match item {
+                // skip nodes that were already yielded by either traversal
+                Ok(path) if !this.seen.insert(path.node().key()) => {}
+                item => return Poll::Ready(Some(item)),
+            }
+        }
+    }
+}
+
+impl<A, B> Stream for MergeDedup<A, B, <A::Node as KeyedNode>::Key>
+where
+    A: Traverse,
+    B: Traverse<Node = A::Node>,
+    A::Node: Clone,
+{
+    type Item = Result<A::Node, <A::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with_depth(cx)
+            .map(|item| item.map(|item| item.map(|(_, node)| Arc::unwrap_or_clone(node))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_dedup;
+    use crate::r#async::{Bfs, Node, NodeStream};
+    use crate::utils::test::Error;
+    use anyhow::Result;
+    use futures::StreamExt;
+    use std::sync::Arc;
+
+    #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    struct NamedNode(&'static str);
+
+    #[async_trait::async_trait]
+    impl Node for NamedNode {
+        type Error = Error;
+
+        async fn children(
+            self: Arc<Self>,
+            _depth: usize,
+        ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+            let children = match self.0 {
+                "left" => vec![Ok("x"), Err(Error), Ok("y")],
+                "right" => vec![Ok("y"), Ok("z")],
+                "stuck" => futures::future::pending().await,
+                _ => vec![],
+            };
+            let nodes = children.into_iter().map(|child| child.map(Self));
+            Ok(Box::pin(futures::stream::iter(nodes)))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_merge_dedup() -> Result<()> {
+        use crate::utils::test::Node;
+        let a = Bfs::<Node>::new(0, 2, true);
+        let b = Bfs::<Node>::new(0, 3, true);
+        let nodes = merge_dedup(a, b)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        // the nodes of both traversals are yielded once
+        similar_asserts::assert_eq!(nodes, [Node(1), Node(2), Node(3)]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_merge_dedup_yields_errors() -> Result<()> {
+        let a = Bfs::<NamedNode>::new(NamedNode("left"), None, false);
+        let b = Bfs::<NamedNode>::new(NamedNode("right"), None, false);
+        let items = merge_dedup(a, b).collect::<Vec<_>>().await;
+        // the error does not end the merged stream, and "y" is only yielded once
+        similar_asserts::assert_eq!(
+            items,
+            [
+                Ok(NamedNode("x")),
+                Ok(NamedNode("y")),
+                Err(Error),
+                Ok(NamedNode("z")),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_merge_dedup_pending_does_not_block() -> Result<()> {
+        use tokio::time::{timeout, Duration};
+        let a = Bfs::<NamedNode>::new(NamedNode("stuck"), None, false);
+        let b = Bfs::<NamedNode>::new(NamedNode("right"), None, false);
+        let mut merged = merge_dedup(a, b);
+        let mut nodes = vec![];
+        while let Ok(Some(node)) = timeout(Duration::from_secs(1), merged.next()).await {
+            nodes.push(node?);
+        }
+        // the other traversal is exhausted, while the merged stream still waits for "stuck"
+        similar_asserts::assert_eq!(nodes, [NamedNode("y"), NamedNode("z")]);
+        assert!(timeout(Duration::from_secs(1), merged.next())
+            .await
+            .is_err());
+        Ok(())
+    }
+}
@@ -1,37 +1,314 @@
+/// Emits a [`tracing`] event at the trace level when the `trace` feature is enabled.
+///
+/// [`tracing`]: https://docs.rs/tracing
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "trace")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+mod adjacency;
+mod admission;
+pub mod best_first;
 pub mod bfs;
+pub mod bidirectional;
+mod builder;
+mod by_level;
+mod context;
+mod count;
+mod dedup_emitted;
+mod depth_control;
+#[cfg(feature = "derive")]
+mod derive;
 pub mod dfs;
+pub mod dijkstra;
+#[cfg(feature = "dot")]
+mod dot;
+#[cfg(feature = "tokio")]
+mod drain;
+mod error;
+mod expand;
+mod find;
+mod for_each;
+mod from_async_fn;
+mod from_fn;
+mod from_iter;
+#[cfg(feature = "fs")]
+mod fs;
+#[cfg(feature = "petgraph")]
+mod graph;
+pub mod hybrid;
+pub mod iddfs;
+mod infallible;
+mod inspect_errors;
+mod level_events;
+mod map_emitted;
+mod merge_dedup;
+mod on_depth_change;
+mod path;
+mod queue;
+mod reachable;
+#[cfg(feature = "tokio")]
+mod retry;
+mod shared;
+#[cfg(feature = "serde")]
+mod snapshot;
+#[cfg(feature = "sled")]
+mod spilling;
+mod stats;
+mod stop;
+mod take_while_depth;
+mod tiebreak;
+mod traversal;
+#[cfg(feature = "tree")]
+mod tree;
+mod visited;
+mod with_depth;
+mod with_seq;
+mod wrap_errors;
 
+pub use adjacency::{AdjacencyMap, AdjacencyNode};
+pub use best_first::BestFirst;
 pub use bfs::Bfs;
-pub use dfs::Dfs;
+pub use bidirectional::BidirectionalBfs;
+pub use builder::{BfsBuilder, DfsBuilder};
+pub use by_level::ByLevel;
+pub use context::{with_context, ContextNode, NodeContext, WithContext};
+pub use dedup_emitted::DedupEmitted;
+pub use depth_control::DepthControl;
+pub use dfs::{Dfs, DfsPostOrder};
+pub use dijkstra::Dijkstra;
+#[cfg(feature = "dot")]
+pub use dot::{digraph, DotEdges};
+#[cfg(feature = "tokio")]
+pub use error::TimeoutError;
+pub use error::{PanicError, TraversalError};
+pub use from_async_fn::{from_async_fn, FromAsyncFn};
+pub use from_fn::{from_fn, FromFn};
+pub use from_iter::from_iter;
+#[cfg(feature = "fs")]
+pub use fs::FsNode;
+pub use hybrid::Hybrid;
+pub use iddfs::Iddfs;
+pub use infallible::InfallibleNodes;
+pub use inspect_errors::InspectErrors;
+pub use level_events::{LevelEvent, LevelEvents};
+pub use map_emitted::MapEmitted;
+pub use merge_dedup::{merge_dedup, MergeDedup};
+pub use on_depth_change::OnDepthChange;
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use par_dfs_derive::Node;
+pub use path::{Path, Paths};
+pub use reachable::reachable_within;
+#[cfg(feature = "tokio")]
+pub use retry::RetryPolicy;
+pub use shared::Shared;
+#[cfg(feature = "serde")]
+pub use snapshot::Snapshot;
+#[cfg(feature = "sled")]
+pub use spilling::SpillingVisitedSet;
+pub use stats::{Stats, TraversalSnapshot};
+pub use take_while_depth::TakeWhileDepth;
+pub use tiebreak::Tiebreak;
+pub use visited::{LruVisitedSet, VisitedSet};
+pub use with_depth::WithDepth;
+pub use with_seq::WithSeq;
+pub use wrap_errors::WrapErrors;
+
+/// Items used by the code generated by the [`Node`] derive.
+///
+/// [`Node`]: derive@crate::async::Node
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use super::derive::Children;
+    pub use async_trait::async_trait;
+}
+
+use admission::{pop_deferred, Admission, Admitted, Expand};
+use expand::{into_node_stream, ChildStream, ChildStreamFut, Expansion, Prefetch};
+use queue::StreamQueue;
+use stop::Stop;
+use traversal::{FrontierQueue, State, Traversal};
 
 use async_trait::async_trait;
-use futures::stream::{FuturesOrdered, Stream};
+use futures::stream::{Stream, StreamExt};
 use futures::Future;
+use std::collections::VecDeque;
 use std::hash::Hash;
+use std::ops::{Bound, RangeBounds};
 use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 
-type Stack<N, E> = Vec<(usize, NodeStream<N, E>)>;
+/// A child stream together with the path to the node it belongs to.
+type Frame<N, E> = (Arc<Path<N>>, NodeStream<N, E>);
+
+type Stack<N, E> = Vec<Frame<N, E>>;
+
+/// The nodes a traversal started from, which allow to restart it.
+struct Origin<N> {
+    /// The roots that are yielded first.
+    roots: VecDeque<Arc<Path<N>>>,
+    /// The nodes that are expanded first.
+    deferred: VecDeque<Arc<Path<N>>>,
+    max_depth: Option<usize>,
+}
+
+impl<N> Default for Origin<N> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            roots: VecDeque::new(),
+            deferred: VecDeque::new(),
+            max_depth: None,
+        }
+    }
+}
+
+impl<N> Clone for Origin<N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            roots: self.roots.clone(),
+            deferred: self.deferred.clone(),
+            max_depth: self.max_depth,
+        }
+    }
+}
 
 type NewNodesFut<N, E> =
-    Pin<Box<dyn Future<Output = (usize, Result<NodeStream<N, E>, E>)> + Unpin + Send + 'static>>;
+    Pin<Box<dyn Future<Output = Result<NodeStream<N, E>, E>> + Send + 'static>>;
+
+/// A predicate on a node at a given depth.
+type Predicate<N> = Box<dyn Fn(&N, usize) -> bool + Send + Sync>;
 
-type StreamQueue<N, E> = FuturesOrdered<NewNodesFut<N, E>>;
+/// Returns the minimum and maximum depth of the nodes in the depth `range`.
+fn depth_bounds<R>(range: &R) -> (usize, Option<usize>)
+where
+    R: RangeBounds<usize>,
+{
+    let min_depth = match range.start_bound() {
+        Bound::Included(&depth) => depth,
+        Bound::Excluded(&depth) => depth.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let max_depth = match range.end_bound() {
+        Bound::Included(&depth) => Some(depth),
+        Bound::Excluded(&depth) => Some(depth.saturating_sub(1)),
+        Bound::Unbounded => None,
+    };
+    (min_depth, max_depth)
+}
 
 /// A pinned [`Stream`] of [`Node`]s
 ///
+/// The child streams of all nodes are queued together until they are consumed,
+/// hence they are boxed to have a single type. Unless the children are truncated
+/// or reversed, the box returned by [`Node::children`] is stored as is, so boxing
+/// a concrete stream in the implementation is the only allocation of the stream,
+/// which an associated stream type would not avoid.
+///
 /// [`Stream`]: trait@futures::stream::Stream
 /// [`Node`]: trait@crate::async::Node
+/// [`Node::children`]: fn@crate::async::Node::children
 pub type NodeStream<N, E> = Pin<Box<dyn Stream<Item = Result<N, E>> + Unpin + Send>>;
 
 #[async_trait]
 /// A node which produces a [`Stream`] of children [`Node`]s for a given depth.
 ///
+/// ### Attaching data
+///
+/// Data that is computed while producing the children, such as fetched contents,
+/// can be stored in the child nodes themselves. The yielded nodes are the very nodes
+/// of the child streams, hence the data survives to the consumer without computing it again.
+/// Implement [`KeyedNode`] to identify nodes by a small key instead of their data,
+/// and use [`Traverse::shared`] to receive each node as an [`Arc`] without cloning the data.
+///
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{Bfs, KeyedNode, Node, NodeStream, Traverse};
+///
+/// #[derive(Debug)]
+/// struct Page {
+///     url: String,
+///     /// The body that was fetched while expanding the parent.
+///     body: String,
+/// }
+///
+/// impl KeyedNode for Page {
+///     type Key = String;
+///
+///     fn key(&self) -> Self::Key {
+///         self.url.clone()
+///     }
+/// }
+///
+/// #[async_trait::async_trait]
+/// impl Node for Page {
+///     type Error = std::convert::Infallible;
+///
+///     async fn children(
+///         self: std::sync::Arc<Self>,
+///         _depth: usize
+///     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+///         let pages = ["/a", "/b"].map(|url| {
+///             // fetch the body once, when the child is created
+///             let body = format!("<html>{url}</html>");
+///             Ok(Page { url: url.to_string(), body })
+///         });
+///         Ok(Box::pin(futures::stream::iter(pages)))
+///     }
+/// }
+///
+/// let bodies = tokio_test::block_on(async {
+///     let root = Page { url: "/".to_string(), body: String::new() };
+///     Bfs::<Page>::new(root, 1, false)
+///         .shared()
+///         .map(|page| page.unwrap().body.clone())
+///         .collect::<Vec<_>>()
+///         .await
+/// });
+/// assert_eq!(bodies, ["<html>/a</html>", "<html>/b</html>"]);
+/// ```
+///
+/// ### Deriving
+///
+/// With the `derive` feature, nodes whose children are stored in their fields
+/// can derive this trait. Fields marked with `#[children]` may hold a `Box`,
+/// `Arc`, `Vec` or `Option` of the node, and their children are cloned.
+///
+#[cfg_attr(feature = "derive", doc = "```")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{Bfs, Node};
+///
+/// #[derive(Node, Clone, Debug, PartialEq, Eq, Hash)]
+/// enum Tree {
+///     Leaf(usize),
+///     Branch(#[children] Vec<Tree>),
+/// }
+///
+/// let tree = Tree::Branch(vec![Tree::Leaf(1), Tree::Leaf(2)]);
+/// let leaves = tokio_test::block_on(async {
+///     Bfs::<Tree>::new(tree, None, false)
+///         .map(Result::unwrap)
+///         .collect::<Vec<_>>()
+///         .await
+/// });
+/// assert_eq!(leaves, [Tree::Leaf(1), Tree::Leaf(2)]);
+/// ```
+///
 /// [`Stream`]: trait@futures::stream::Stream
 /// [`Node`]: trait@crate::async::Node
+/// [`KeyedNode`]: trait@crate::async::KeyedNode
+/// [`Traverse::shared`]: fn@crate::async::Traverse::shared
+/// [`Arc`]: struct@std::sync::Arc
 pub trait Node
 where
-    Self: Sized + Hash + Eq + std::fmt::Debug,
+    Self: Sized + KeyedNode + std::fmt::Debug,
 {
     /// The type of the error when creating the stream fails.
     type Error: std::fmt::Debug;
@@ -48,6 +325,698 @@ where
         self: Arc<Self>,
         depth: usize,
     ) -> Result<NodeStream<Self, Self::Error>, Self::Error>;
+
+    #[inline]
+    /// Returns `true` if this node may be yielded and expanded again when it is reached again.
+    ///
+    /// Revisitable nodes are neither checked against nor inserted into the visited nodes
+    /// of [`Bfs`] and [`Dfs`], as if `allow_circles` was set for them only, e.g. for leaves
+    /// that are reachable on many paths. Defaults to `false`.
+    /// Marking a node on an actual cycle as revisitable traverses the cycle
+    /// over and over again, unless a maximum depth is set.
+    ///
+    /// [`Bfs`]: struct@crate::async::Bfs
+    /// [`Dfs`]: struct@crate::async::Dfs
+    fn allow_revisit(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    /// Returns `true` if this node is yielded but never expanded, e.g. a back-edge
+    /// that the node can cheaply detect itself.
+    ///
+    /// Terminal nodes are neither checked against nor inserted into the visited nodes
+    /// of [`Bfs`] and [`Dfs`], hence they are yielded every time they are reached,
+    /// regardless of `allow_circles`. Their children are never requested.
+    /// This allows to cut cycles using domain knowledge together with `allow_circles`,
+    /// without hashing every node. The roots are always expanded. Defaults to `false`.
+    ///
+    /// [`Bfs`]: struct@crate::async::Bfs
+    /// [`Dfs`]: struct@crate::async::Dfs
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+/// A node which produces a [`Stream`] of children for a given depth from a reference to itself.
+///
+/// Unlike [`Node::children`], the future returned by [`BorrowNode::children`] borrows the node,
+/// which is convenient for cheap handles such as integer IDs into a graph stored elsewhere.
+/// Every [`BorrowNode`] is a [`Node`], and can be traversed by [`Bfs`] and [`Dfs`].
+/// Implement [`Node`] instead if the future needs shared ownership of the node.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{BorrowNode, Bfs, NodeStream};
+///
+/// #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+/// struct Id(u32);
+///
+/// #[async_trait::async_trait]
+/// impl BorrowNode for Id {
+///     type Error = std::convert::Infallible;
+///
+///     async fn children(&self, _depth: usize) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+///         let nodes = [self.0 * 2, self.0 * 2 + 1].into_iter().map(Id).map(Ok);
+///         Ok(Box::pin(futures::stream::iter(nodes)))
+///     }
+/// }
+///
+/// let result = tokio_test::block_on(async {
+///     let bfs = Bfs::<Id>::new(Id(1), 2, true);
+///     let output = bfs
+///         .collect::<Vec<_>>()
+///         .await
+///         .into_iter()
+///         .collect::<Result<Vec<_>, _>>()
+///         .unwrap();
+///     output.into_iter().map(|id| id.0).collect::<Vec<_>>()
+/// });
+/// assert_eq!(result, [2, 3, 4, 5, 6, 7]);
+/// ```
+///
+/// [`Stream`]: trait@futures::stream::Stream
+/// [`Node`]: trait@crate::async::Node
+/// [`Node::children`]: fn@crate::async::Node::children
+/// [`BorrowNode`]: trait@crate::async::BorrowNode
+/// [`BorrowNode::children`]: fn@crate::async::BorrowNode::children
+/// [`Bfs`]: struct@crate::async::Bfs
+/// [`Dfs`]: struct@crate::async::Dfs
+pub trait BorrowNode
+where
+    Self: Sized + KeyedNode + std::fmt::Debug,
+{
+    /// The type of the error when creating the stream fails.
+    type Error: std::fmt::Debug;
+
+    /// Returns a [`NodeStream`] of its children.
+    ///
+    /// # Errors
+    ///
+    /// Should return [`Self::Error`] if the stream can not be created.
+    ///
+    /// [`NodeStream`]: type@crate::async::NodeStream
+    /// [`Self::Error`]: type@crate::async::BorrowNode::Error
+    async fn children(&self, depth: usize) -> Result<NodeStream<Self, Self::Error>, Self::Error>;
+
+    #[inline]
+    /// Returns `true` if this node may be yielded and expanded again when it is reached again.
+    ///
+    /// See [`Node::allow_revisit`]. Defaults to `false`.
+    ///
+    /// [`Node::allow_revisit`]: fn@crate::async::Node::allow_revisit
+    fn allow_revisit(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    /// Returns `true` if this node is yielded but never expanded.
+    ///
+    /// See [`Node::is_terminal`]. Defaults to `false`.
+    ///
+    /// [`Node::is_terminal`]: fn@crate::async::Node::is_terminal
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+impl<N> Node for N
+where
+    N: BorrowNode + Send + Sync + 'static,
+{
+    type Error = <N as BorrowNode>::Error;
+
+    #[inline]
+    async fn children(
+        self: Arc<Self>,
+        depth: usize,
+    ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+        BorrowNode::children(self.as_ref(), depth).await
+    }
+
+    #[inline]
+    fn allow_revisit(&self) -> bool {
+        BorrowNode::allow_revisit(self)
+    }
+
+    #[inline]
+    fn is_terminal(&self) -> bool {
+        BorrowNode::is_terminal(self)
+    }
+}
+
+/// A pinned [`Stream`] of [`InfallibleNode`]s
+///
+/// [`Stream`]: trait@futures::stream::Stream
+/// [`InfallibleNode`]: trait@crate::async::InfallibleNode
+pub type InfallibleNodeStream<N> = Pin<Box<dyn Stream<Item = N> + Unpin + Send>>;
+
+#[async_trait]
+/// A node which produces a [`Stream`] of children for a given depth and can not fail.
+///
+/// Every [`InfallibleNode`] is a [`BorrowNode`] with an [`Infallible`] error,
+/// hence it is a [`Node`] and can be traversed by [`Bfs`] and [`Dfs`].
+/// Like [`BorrowNode::children`], the future returned by [`InfallibleNode::children`]
+/// borrows the node, since a blanket [`Node`] implementation would conflict with
+/// the one for [`BorrowNode`].
+/// Use [`Traverse::infallible`] to yield the nodes without wrapping them in a [`Result`].
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{Bfs, InfallibleNode, InfallibleNodeStream, Traverse};
+///
+/// #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+/// struct Id(u32);
+///
+/// #[async_trait::async_trait]
+/// impl InfallibleNode for Id {
+///     async fn children(&self, _depth: usize) -> InfallibleNodeStream<Self> {
+///         let nodes = [self.0 * 2, self.0 * 2 + 1].into_iter().map(Id);
+///         Box::pin(futures::stream::iter(nodes))
+///     }
+/// }
+///
+/// let result = tokio_test::block_on(async {
+///     let bfs = Bfs::<Id>::new(Id(1), 2, true);
+///     bfs.infallible().map(|id| id.0).collect::<Vec<_>>().await
+/// });
+/// assert_eq!(result, [2, 3, 4, 5, 6, 7]);
+/// ```
+///
+/// [`Stream`]: trait@futures::stream::Stream
+/// [`Node`]: trait@crate::async::Node
+/// [`BorrowNode`]: trait@crate::async::BorrowNode
+/// [`BorrowNode::children`]: fn@crate::async::BorrowNode::children
+/// [`InfallibleNode`]: trait@crate::async::InfallibleNode
+/// [`InfallibleNode::children`]: fn@crate::async::InfallibleNode::children
+/// [`Infallible`]: enum@std::convert::Infallible
+/// [`Result`]: enum@std::result::Result
+/// [`Traverse::infallible`]: fn@crate::async::Traverse::infallible
+/// [`Bfs`]: struct@crate::async::Bfs
+/// [`Dfs`]: struct@crate::async::Dfs
+pub trait InfallibleNode
+where
+    Self: Sized + KeyedNode + std::fmt::Debug,
+{
+    /// Returns an [`InfallibleNodeStream`] of its children.
+    ///
+    /// [`InfallibleNodeStream`]: type@crate::async::InfallibleNodeStream
+    async fn children(&self, depth: usize) -> InfallibleNodeStream<Self>;
+
+    #[inline]
+    /// Returns `true` if this node may be yielded and expanded again when it is reached again.
+    ///
+    /// See [`Node::allow_revisit`]. Defaults to `false`.
+    ///
+    /// [`Node::allow_revisit`]: fn@crate::async::Node::allow_revisit
+    fn allow_revisit(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    /// Returns `true` if this node is yielded but never expanded.
+    ///
+    /// See [`Node::is_terminal`]. Defaults to `false`.
+    ///
+    /// [`Node::is_terminal`]: fn@crate::async::Node::is_terminal
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+impl<N> BorrowNode for N
+where
+    N: InfallibleNode + Send + Sync + 'static,
+{
+    type Error = std::convert::Infallible;
+
+    #[inline]
+    async fn children(&self, depth: usize) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+        let stream = InfallibleNode::children(self, depth).await;
+        Ok(Box::pin(stream.map(Ok)))
+    }
+
+    #[inline]
+    fn allow_revisit(&self) -> bool {
+        InfallibleNode::allow_revisit(self)
+    }
+
+    #[inline]
+    fn is_terminal(&self) -> bool {
+        InfallibleNode::is_terminal(self)
+    }
+}
+
+#[async_trait]
+/// A [`Node`] which also produces a [`Stream`] of its parent [`Node`]s for a given depth,
+/// such that the graph can be searched in both directions.
+///
+/// [`Stream`]: trait@futures::stream::Stream
+/// [`Node`]: trait@crate::async::Node
+pub trait BidirectionalNode
+where
+    Self: Node,
+{
+    /// Returns a [`NodeStream`] of its parents.
+    ///
+    /// The `depth` is the distance from the node the backward search started at.
+    ///
+    /// # Errors
+    ///
+    /// Should return [`Node::Error`] if the stream can not be created.
+    ///
+    /// [`NodeStream`]: type@crate::async::NodeStream
+    /// [`Node::Error`]: type@crate::async::Node::Error
+    async fn parents(
+        self: Arc<Self>,
+        depth: usize,
+    ) -> Result<NodeStream<Self, Self::Error>, Self::Error>;
+}
+
+/// A pinned [`Stream`] of [`WeightedNode`]s together with the cost of reaching them.
+///
+/// [`Stream`]: trait@futures::stream::Stream
+/// [`WeightedNode`]: trait@crate::async::WeightedNode
+pub type WeightedNodeStream<N, C, E> =
+    Pin<Box<dyn Stream<Item = Result<(C, N), E>> + Unpin + Send>>;
+
+#[async_trait]
+/// A node which produces a [`Stream`] of children [`WeightedNode`]s for a given depth,
+/// where each child is associated with the cost of the edge leading to it.
+///
+/// [`Stream`]: trait@futures::stream::Stream
+/// [`WeightedNode`]: trait@crate::async::WeightedNode
+pub trait WeightedNode
+where
+    Self: Sized + KeyedNode + std::fmt::Debug,
+{
+    /// The type of the cost of an edge.
+    ///
+    /// The [`Default`] value is used as the cost of the root.
+    ///
+    /// [`Default`]: trait@std::default::Default
+    type Cost: Clone + Ord + Default + std::ops::Add<Output = Self::Cost>;
+
+    /// The type of the error when creating the stream fails.
+    type Error: std::fmt::Debug;
+
+    /// Returns a [`WeightedNodeStream`] of its children and the costs of the edges to them.
+    ///
+    /// # Errors
+    ///
+    /// Should return [`Self::Error`] if the stream can not be created.
+    ///
+    /// [`WeightedNodeStream`]: type@crate::async::WeightedNodeStream
+    /// [`Self::Error`]: type@crate::async::WeightedNode::Error
+    async fn children(
+        self: Arc<Self>,
+        depth: usize,
+    ) -> Result<WeightedNodeStream<Self, Self::Cost, Self::Error>, Self::Error>;
+}
+
+/// A node with a key that identifies it.
+///
+/// Traversals track visited nodes by their key, which allows nodes
+/// with a heavy or non-hashable payload to be identified by a small key instead.
+/// This trait is implemented for all nodes that are [`Hash`], [`Eq`] and [`Clone`],
+/// using the node itself as the key.
+///
+/// ### Example
+/// ```
+/// use par_dfs::r#async::KeyedNode;
+///
+/// #[derive(Clone, Debug)]
+/// struct Page {
+///     url: String,
+///     html: Vec<u8>,
+/// }
+///
+/// impl KeyedNode for Page {
+///     type Key = String;
+///
+///     fn key(&self) -> Self::Key {
+///         self.url.clone()
+///     }
+/// }
+/// ```
+///
+/// [`Hash`]: trait@std::hash::Hash
+/// [`Eq`]: trait@std::cmp::Eq
+/// [`Clone`]: trait@std::clone::Clone
+pub trait KeyedNode {
+    /// The type of the key that identifies a node.
+    type Key: Hash + Eq;
+
+    /// Returns the key that identifies this node.
+    fn key(&self) -> Self::Key;
+}
+
+impl<N> KeyedNode for N
+where
+    N: Hash + Eq + Clone,
+{
+    type Key = N;
+
+    #[inline]
+    fn key(&self) -> Self::Key {
+        self.clone()
+    }
+}
+
+/// A traversal over [`Node`]s that keeps track of the depth of each node.
+///
+/// Both [`Bfs`] and [`Dfs`] implement this trait.
+/// The provided methods are adapters that make use of the depth.
+///
+/// [`Node`]: trait@crate::async::Node
+/// [`Bfs`]: struct@crate::async::Bfs
+/// [`Dfs`]: struct@crate::async::Dfs
+pub trait Traverse {
+    /// The type of the traversed nodes.
+    type Node: Node;
+
+    /// Attempt to pull out the path to the next node of this traversal.
+    ///
+    /// Errors carry the node whose children failed and their depth.
+    /// See [`poll_next_path`].
+    ///
+    /// [`poll_next_path`]: fn@crate::async::Traverse::poll_next_path
+    #[allow(clippy::type_complexity)]
+    fn poll_next_path_with_context(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<
+        Option<
+            Result<Path<Self::Node>, TraversalError<Arc<Self::Node>, <Self::Node as Node>::Error>>,
+        >,
+    >;
+
+    /// Attempt to pull out the path to the next node of this traversal.
+    ///
+    /// The depth of the [`Path`] matches the `depth` that was used when calling
+    /// [`Node::children`], hence the direct children of the root have depth 1.
+    /// The node is shared with its pending [`Node::children`] call, if any.
+    ///
+    /// [`Path`]: struct@crate::async::Path
+    /// [`Node::children`]: fn@crate::async::Node::children
+    #[allow(clippy::type_complexity)]
+    fn poll_next_path(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Path<Self::Node>, <Self::Node as Node>::Error>>> {
+        self.poll_next_path_with_context(cx)
+            .map(|item| item.map(|item| item.map_err(TraversalError::into_source)))
+    }
+
+    /// Attempt to pull out the next node of this traversal
+    /// together with its depth.
+    ///
+    /// See [`poll_next_path`].
+    ///
+    /// [`poll_next_path`]: fn@crate::async::Traverse::poll_next_path
+    #[allow(clippy::type_complexity)]
+    fn poll_next_with_depth(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<(usize, Arc<Self::Node>), <Self::Node as Node>::Error>>> {
+        self.poll_next_path(cx)
+            .map(|item| item.map(|item| item.map(|path| (path.depth(), path.into_node()))))
+    }
+
+    /// Yields the depth alongside each node.
+    ///
+    /// The resulting [`Stream`] yields `Result<(usize, N), N::Error>`.
+    ///
+    /// [`Stream`]: trait@futures::stream::Stream
+    fn with_depth(self) -> WithDepth<Self>
+    where
+        Self: Sized,
+    {
+        WithDepth::new(self)
+    }
+
+    /// Yields a sequence number alongside each node, in the order the nodes are yielded.
+    ///
+    /// The resulting [`Stream`] yields `Result<(u64, N), N::Error>`.
+    /// The sequence numbers start at 0 and increase by one for every yielded node,
+    /// whereas errors do not consume a sequence number.
+    /// For a traversal in a deterministic order, such as an ordered [`Bfs`],
+    /// the sequence numbers are stable across runs.
+    ///
+    /// ### Example
+    /// ```
+    /// use futures::StreamExt;
+    /// use par_dfs::r#async::{AdjacencyNode, Bfs, Traverse};
+    /// use std::collections::HashMap;
+    /// use std::sync::Arc;
+    ///
+    /// let graph = Arc::new(HashMap::from([("a", vec!["b", "c"]), ("b", vec!["d"])]));
+    /// let nodes = tokio_test::block_on(async {
+    ///     Bfs::<AdjacencyNode<&str>>::new(AdjacencyNode::new(graph, "a"), None, false)
+    ///         .with_seq()
+    ///         .map(|item| item.map(|(seq, node)| (seq, *node.key())).unwrap())
+    ///         .collect::<Vec<_>>()
+    ///         .await
+    /// });
+    /// assert_eq!(nodes, [(0, "b"), (1, "c"), (2, "d")]);
+    /// ```
+    ///
+    /// [`Stream`]: trait@futures::stream::Stream
+    /// [`Bfs`]: struct@crate::async::Bfs
+    fn with_seq(self) -> WithSeq<Self>
+    where
+        Self: Sized,
+    {
+        WithSeq::new(self)
+    }
+
+    /// Yields the result of applying `f` to each node.
+    ///
+    /// The resulting [`Stream`] yields `Result<M, N::Error>`.
+    /// Only the yielded values are mapped: the traversal itself, including
+    /// visited tracking and the [`Node::children`] calls, continues on the original nodes.
+    /// To transform nodes before they are expanded, the mapped type must implement
+    /// [`Node`] itself, e.g. via a wrapping node whose children are mapped as well.
+    ///
+    /// [`Stream`]: trait@futures::stream::Stream
+    /// [`Node`]: trait@crate::async::Node
+    /// [`Node::children`]: fn@crate::async::Node::children
+    fn map_emitted<F, M>(self, f: F) -> MapEmitted<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Node) -> M,
+    {
+        MapEmitted::new(self, f)
+    }
+
+    /// Calls `f` with the depth of each yielded node that differs from the previous one.
+    ///
+    /// The first yielded node always triggers a call, and errors are passed through
+    /// without affecting the tracked depth.
+    /// In breadth-first order, this fires once when the traversal advances to a new level.
+    /// In depth-first order, where the depth also decreases when backtracking,
+    /// this fires on every change of the depth.
+    ///
+    /// ### Example
+    /// ```
+    /// use futures::StreamExt;
+    /// use par_dfs::r#async::{Bfs, Traverse};
+    /// # use par_dfs::r#async::{Node, NodeStream};
+    /// #
+    /// # #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    /// # struct Number(usize);
+    /// #
+    /// # #[async_trait::async_trait]
+    /// # impl Node for Number {
+    /// #     type Error = std::convert::Infallible;
+    /// #
+    /// #     async fn children(
+    /// #         self: std::sync::Arc<Self>,
+    /// #         _depth: usize
+    /// #     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+    /// #         let nodes = [Self(2 * self.0), Self(2 * self.0 + 1)].into_iter().map(Ok);
+    /// #         Ok(Box::pin(futures::stream::iter(nodes)))
+    /// #     }
+    /// # }
+    ///
+    /// let mut depths = Vec::new();
+    /// tokio_test::block_on(async {
+    ///     let bfs = Bfs::<Number>::new(Number(1), 3, false);
+    ///     let count = bfs.on_depth_change(|depth| depths.push(depth)).count().await;
+    ///     assert_eq!(count, 2 + 4 + 8);
+    /// });
+    /// assert_eq!(depths, [1, 2, 3]);
+    /// ```
+    fn on_depth_change<F>(self, f: F) -> OnDepthChange<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(usize),
+    {
+        OnDepthChange::new(self, f)
+    }
+
+    /// Calls `f` with each error of the traversal, before yielding it unchanged.
+    ///
+    /// Unlike [`StreamExt::inspect`], only errors are passed to `f`,
+    /// e.g. to log them while the traversal continues.
+    ///
+    /// [`StreamExt::inspect`]: fn@futures::stream::StreamExt::inspect
+    fn inspect_errors<F>(self, f: F) -> InspectErrors<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&<Self::Node as Node>::Error),
+    {
+        InspectErrors::new(self, f)
+    }
+
+    /// Yields the nodes until the first node deeper than `max_depth`, and then stops.
+    ///
+    /// Unlike [`StreamExt::take_while`], the resulting [`Traverse`] can be adapted further,
+    /// and unlike the `max_depth` of a traversal, this can be applied to a traversal that
+    /// is already built. Once a deeper node is seen, the traversal is not polled again,
+    /// hence no further [`Node::children`] futures make progress. However, the adapter
+    /// only learns the depth of the yielded nodes, hence the children of nodes at
+    /// `max_depth` may already have been requested, and their pending futures are only
+    /// dropped together with the adapter.
+    /// In depth-first order, this stops at the first deeper node,
+    /// even if shallower nodes would follow.
+    ///
+    /// [`StreamExt::take_while`]: fn@futures::stream::StreamExt::take_while
+    /// [`Traverse`]: trait@crate::async::Traverse
+    /// [`Node::children`]: fn@crate::async::Node::children
+    fn take_while_depth(self, max_depth: usize) -> TakeWhileDepth<Self>
+    where
+        Self: Sized,
+    {
+        TakeWhileDepth::new(self, max_depth)
+    }
+
+    /// Yields each node only the first time its [`KeyedNode::key`] is seen.
+    ///
+    /// Unlike disabling `allow_circles`, this only filters the output: the traversal
+    /// still expands every occurrence of a node, and errors are always passed through.
+    /// The adapter keeps the keys of all yielded nodes.
+    ///
+    /// ### Example
+    /// ```
+    /// use futures::StreamExt;
+    /// use par_dfs::r#async::{AdjacencyNode, Bfs, Traverse};
+    /// use std::collections::HashMap;
+    /// use std::sync::Arc;
+    ///
+    /// // "d" is reachable from both "b" and "c"
+    /// let graph = Arc::new(HashMap::from([
+    ///     ("a", vec!["b", "c"]),
+    ///     ("b", vec!["d"]),
+    ///     ("c", vec!["d"]),
+    /// ]));
+    ///
+    /// let result = tokio_test::block_on(async {
+    ///     let root = AdjacencyNode::new(graph, "a");
+    ///     let bfs = Bfs::<AdjacencyNode<&str>>::new(root, None, true);
+    ///     let output = bfs
+    ///         .dedup_emitted()
+    ///         .collect::<Vec<_>>()
+    ///         .await
+    ///         .into_iter()
+    ///         .collect::<Result<Vec<_>, _>>()
+    ///         .unwrap();
+    ///     output.into_iter().map(|node| *node.key()).collect::<Vec<_>>()
+    /// });
+    /// assert_eq!(result, ["b", "c", "d"]);
+    /// ```
+    ///
+    /// [`KeyedNode::key`]: fn@crate::async::KeyedNode::key
+    fn dedup_emitted(self) -> DedupEmitted<Self, <Self::Node as KeyedNode>::Key>
+    where
+        Self: Sized,
+    {
+        DedupEmitted::new(self)
+    }
+
+    /// Yields each node as an [`Arc`].
+    ///
+    /// The resulting [`Stream`] yields `Result<Arc<N>, N::Error>`.
+    /// Unlike the traversal itself, this does not require the nodes to be [`Clone`],
+    /// and avoids cloning nodes that are still referenced by their pending [`Node::children`] call.
+    ///
+    /// [`Stream`]: trait@futures::stream::Stream
+    /// [`Arc`]: struct@std::sync::Arc
+    /// [`Clone`]: trait@std::clone::Clone
+    /// [`Node::children`]: fn@crate::async::Node::children
+    fn shared(self) -> Shared<Self>
+    where
+        Self: Sized,
+    {
+        Shared::new(self)
+    }
+
+    /// Yields the path from the root to each node.
+    ///
+    /// The resulting [`Stream`] yields `Result<Vec<N>, N::Error>`,
+    /// starting with the root and ending with the node.
+    /// Use [`poll_next_path`] to access the shared [`Path`] without cloning the nodes.
+    ///
+    /// [`Stream`]: trait@futures::stream::Stream
+    /// [`Path`]: struct@crate::async::Path
+    /// [`poll_next_path`]: fn@crate::async::Traverse::poll_next_path
+    fn paths(self) -> Paths<Self>
+    where
+        Self: Sized,
+    {
+        Paths::new(self)
+    }
+
+    #[cfg(feature = "dot")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dot")))]
+    /// Yields a [DOT] edge statement for each discovered edge.
+    ///
+    /// The resulting [`Stream`] yields `Result<String, N::Error>`,
+    /// with one `"parent" -> "child";` line per yielded node that has a parent,
+    /// where the nodes are labeled via their [`Display`] implementation.
+    /// Use [`digraph`] to wrap the edges in a complete graph.
+    ///
+    /// [DOT]: https://graphviz.org/doc/info/lang.html
+    /// [`Stream`]: trait@futures::stream::Stream
+    /// [`Display`]: trait@std::fmt::Display
+    /// [`digraph`]: fn@crate::async::digraph
+    fn dot_edges(self) -> DotEdges<Self>
+    where
+        Self: Sized,
+        Self::Node: std::fmt::Display,
+    {
+        DotEdges::new(self)
+    }
+
+    /// Yields the nodes of a traversal that can not fail.
+    ///
+    /// The resulting [`Stream`] yields `N` instead of `Result<N, Infallible>`.
+    ///
+    /// [`Stream`]: trait@futures::stream::Stream
+    fn infallible(self) -> InfallibleNodes<Self>
+    where
+        Self: Sized,
+        Self::Node: Node<Error = std::convert::Infallible>,
+    {
+        InfallibleNodes::new(self)
+    }
+
+    /// Attaches the node whose children failed and their depth to each error.
+    ///
+    /// The resulting [`Stream`] yields `Result<N, TraversalError<N, N::Error>>`.
+    ///
+    /// [`Stream`]: trait@futures::stream::Stream
+    fn wrap_errors(self) -> WrapErrors<Self>
+    where
+        Self: Sized,
+    {
+        WrapErrors::new(self)
+    }
 }
 
 #[cfg(test)]
@@ -77,4 +1046,61 @@ mod tests {
         // similar_asserts::assert_eq!(s2.as_slice(), [1, 4, 6]);
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_borrow_node_is_node() -> Result<()> {
+        use super::{BorrowNode, Dfs, NodeStream};
+
+        /// An index into a graph stored elsewhere.
+        #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+        struct Index(usize);
+
+        static GRAPH: [&[usize]; 4] = [&[1, 2], &[3], &[3], &[]];
+
+        #[async_trait::async_trait]
+        impl BorrowNode for Index {
+            type Error = std::convert::Infallible;
+
+            async fn children(
+                &self,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                let nodes = GRAPH[self.0].iter().copied().map(Index).map(Ok);
+                Ok(Box::pin(futures::stream::iter(nodes)))
+            }
+        }
+
+        let dfs = Dfs::<Index>::new(Index(0), None, false);
+        let nodes = dfs
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        similar_asserts::assert_eq!(nodes, [Index(1), Index(3), Index(2)]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_infallible_node_is_node() -> Result<()> {
+        use super::{Dfs, InfallibleNode, InfallibleNodeStream, Traverse};
+
+        /// An index into a graph stored elsewhere.
+        #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+        struct Index(usize);
+
+        static GRAPH: [&[usize]; 4] = [&[1, 2], &[3], &[3], &[]];
+
+        #[async_trait::async_trait]
+        impl InfallibleNode for Index {
+            async fn children(&self, _depth: usize) -> InfallibleNodeStream<Self> {
+                let nodes = GRAPH[self.0].iter().copied().map(Index);
+                Box::pin(futures::stream::iter(nodes))
+            }
+        }
+
+        let dfs = Dfs::<Index>::new(Index(0), None, false);
+        let nodes: Vec<_> = dfs.infallible().collect().await;
+        similar_asserts::assert_eq!(nodes, [Index(1), Index(3), Index(2)]);
+        Ok(())
+    }
 }
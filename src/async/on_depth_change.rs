@@ -0,0 +1,76 @@
+use super::{Node, Path, TraversalError, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Stream for the [`on_depth_change`] method.
+///
+/// Calls a function with the depth of a yielded node whenever it differs from the previous one.
+///
+/// [`on_depth_change`]: fn@crate::async::Traverse::on_depth_change
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct OnDepthChange<S, F> {
+    #[pin]
+    inner: S,
+    f: F,
+    /// The depth of the last yielded node.
+    depth: Option<usize>,
+}
+
+impl<S, F> OnDepthChange<S, F> {
+    pub(super) fn new(inner: S, f: F) -> Self {
+        Self {
+            inner,
+            f,
+            depth: None,
+        }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, F> Traverse for OnDepthChange<S, F>
+where
+    S: Traverse,
+    F: FnMut(usize),
+{
+    type Node = S::Node;
+
+    fn poll_next_path_with_context(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Path<S::Node>, TraversalError<Arc<S::Node>, <S::Node as Node>::Error>>>>
+    {
+        let this = self.project();
+        let item = futures::ready!(this.inner.poll_next_path_with_context(cx));
+        if let Some(Ok(path)) = &item {
+            let depth = path.depth();
+            if this.depth.replace(depth) != Some(depth) {
+                (this.f)(depth);
+            }
+        }
+        Poll::Ready(item)
+    }
+}
+
+impl<S, F> Stream for OnDepthChange<S, F>
+where
+    S: Traverse,
+    S::Node: Clone,
+    F: FnMut(usize),
+{
+    type Item = Result<S::Node, <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with_depth(cx)
+            .map(|item| item.map(|item| item.map(|(_, node)| Arc::unwrap_or_clone(node))))
+    }
+}
@@ -0,0 +1,204 @@
+use super::{Node, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// The path from the root to a node of a traversal.
+///
+/// Paths are linked lists of [`Arc`] parent pointers,
+/// so the paths of siblings share the nodes of their common ancestors.
+///
+/// [`Arc`]: struct@std::sync::Arc
+pub struct Path<N> {
+    node: Arc<N>,
+    parent: Option<Arc<Path<N>>>,
+    depth: usize,
+}
+
+impl<N> Path<N> {
+    #[inline]
+    #[must_use]
+    pub(super) fn root(node: Arc<N>) -> Self {
+        Self {
+            node,
+            parent: None,
+            depth: 0,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Creates a path that consists of a single node at `depth`, without its ancestors.
+    pub(super) fn detached(node: Arc<N>, depth: usize) -> Self {
+        Self {
+            node,
+            parent: None,
+            depth,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub(super) fn child(parent: &Arc<Self>, node: Arc<N>) -> Self {
+        Self {
+            node,
+            depth: parent.depth + 1,
+            parent: Some(Arc::clone(parent)),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the last node of the path.
+    pub fn node(&self) -> &Arc<N> {
+        &self.node
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the last node of the path, dropping its ancestors.
+    pub fn into_node(self) -> Arc<N> {
+        Arc::clone(&self.node)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the path to the parent of the last node,
+    /// or `None` if the path only consists of the root.
+    pub fn parent(&self) -> Option<&Arc<Path<N>>> {
+        self.parent.as_ref()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the depth of the last node, where the root has depth 0.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    #[inline]
+    /// Returns an iterator over the nodes of the path,
+    /// starting with the last node and ending with the root.
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<N>> {
+        std::iter::successors(Some(self), |path| path.parent.as_deref()).map(|path| &path.node)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the nodes of the path, starting with the root.
+    pub fn to_vec(&self) -> Vec<N>
+    where
+        N: Clone,
+    {
+        let mut nodes: Vec<N> = self.iter().map(|node| N::clone(node)).collect();
+        nodes.reverse();
+        nodes
+    }
+}
+
+impl<N> Clone for Path<N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            node: Arc::clone(&self.node),
+            parent: self.parent.clone(),
+            depth: self.depth,
+        }
+    }
+}
+
+impl<N> std::fmt::Debug for Path<N>
+where
+    N: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut nodes: Vec<_> = self.iter().collect();
+        nodes.reverse();
+        f.debug_list().entries(nodes).finish()
+    }
+}
+
+impl<N> Drop for Path<N> {
+    fn drop(&mut self) {
+        // drop long chains of ancestors iteratively to not overflow the stack
+        let mut parent = self.parent.take();
+        while let Some(path) = parent {
+            match Arc::try_unwrap(path) {
+                Ok(mut path) => parent = path.parent.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+/// Stream for the [`paths`] method.
+///
+/// Yields the path from the root to each node.
+///
+/// [`paths`]: fn@crate::async::Traverse::paths
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct Paths<S> {
+    #[pin]
+    inner: S,
+}
+
+impl<S> Paths<S> {
+    pub(super) fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for Paths<S>
+where
+    S: Traverse,
+    S::Node: Clone,
+{
+    type Item = Result<Vec<S::Node>, <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project()
+            .inner
+            .poll_next_path(cx)
+            .map(|item| item.map(|item| item.map(|path| path.to_vec())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Path;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_path_to_vec() {
+        let root = Arc::new(Path::root(Arc::new(0)));
+        let child = Arc::new(Path::child(&root, Arc::new(1)));
+        let path = Path::child(&child, Arc::new(2));
+        assert_eq!(path.depth(), 2);
+        assert_eq!(path.to_vec(), [0, 1, 2]);
+        assert_eq!(
+            path.iter().map(|node| **node).collect::<Vec<_>>(),
+            [2, 1, 0]
+        );
+        assert_eq!(format!("{path:?}"), "[0, 1, 2]");
+    }
+
+    #[test]
+    fn test_drop_deep_path() {
+        let mut path = Arc::new(Path::root(Arc::new(0)));
+        for i in 1..1_000_000 {
+            path = Arc::new(Path::child(&path, Arc::new(i)));
+        }
+        assert_eq!(path.depth(), 999_999);
+        drop(path);
+    }
+}
@@ -0,0 +1,188 @@
+use super::{ChildStream, ChildStreamFut, Path};
+
+use futures::stream::{FuturesOrdered, FuturesUnordered, StreamExt};
+use futures::{Future, FutureExt};
+use std::collections::{BTreeMap, VecDeque};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// A [`ChildStreamFut`] tagged with the sequence number of its parent in the [`StreamQueue`].
+struct Sequenced<N, E> {
+    seq: usize,
+    fut: ChildStreamFut<N, E>,
+}
+
+impl<N, E> Future for Sequenced<N, E> {
+    type Output = (usize, ChildStream<N, E>);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let stream = futures::ready!(this.fut.poll_unpin(cx));
+        Poll::Ready((this.seq, stream))
+    }
+}
+
+/// The futures of a single depth in unordered mode.
+struct Group<N, E> {
+    depth: usize,
+    futs: FuturesUnordered<Sequenced<N, E>>,
+    /// Completed child streams that wait for the shallower groups to be yielded.
+    ready: VecDeque<(usize, ChildStream<N, E>)>,
+}
+
+/// The futures of a [`StreamQueue`].
+enum Futs<N, E> {
+    Ordered(FuturesOrdered<Sequenced<N, E>>),
+    Unordered(VecDeque<Group<N, E>>),
+    Eager(FuturesUnordered<Sequenced<N, E>>),
+}
+
+/// A queue of futures resolving to child streams.
+///
+/// In ordered mode, the child streams are yielded in the order
+/// their futures were added.
+/// In unordered mode, the futures are grouped by depth and
+/// the child streams of the shallowest group are yielded
+/// in the order their futures complete.
+/// The futures of the deeper groups are polled as well,
+/// but their child streams are only yielded once the shallower groups are exhausted.
+/// In eager mode, the child streams are yielded in the order their futures complete,
+/// regardless of their depth.
+pub(super) struct StreamQueue<N, E> {
+    futs: Futs<N, E>,
+    /// The parents of the pending futures by their sequence number,
+    /// i.e. in the order their futures were added.
+    parents: BTreeMap<usize, Arc<Path<N>>>,
+    /// The sequence number of the next future.
+    seq: usize,
+}
+
+impl<N, E> Default for StreamQueue<N, E> {
+    #[inline]
+    fn default() -> Self {
+        Self::ordered()
+    }
+}
+
+impl<N, E> StreamQueue<N, E> {
+    #[inline]
+    #[must_use]
+    pub fn ordered() -> Self {
+        Self {
+            futs: Futs::Ordered(FuturesOrdered::new()),
+            parents: BTreeMap::new(),
+            seq: 0,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn unordered() -> Self {
+        Self {
+            futs: Futs::Unordered(VecDeque::new()),
+            parents: BTreeMap::new(),
+            seq: 0,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn eager() -> Self {
+        Self {
+            futs: Futs::Eager(FuturesUnordered::new()),
+            parents: BTreeMap::new(),
+            seq: 0,
+        }
+    }
+
+    /// Returns the number of futures in the queue.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns `true` if there are no futures in the queue.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Returns the parents of the pending futures, in the order their futures were added.
+    #[inline]
+    pub fn parents(&self) -> impl Iterator<Item = &Arc<Path<N>>> {
+        self.parents.values()
+    }
+
+    /// Removes all futures from the queue.
+    #[inline]
+    pub fn clear(&mut self) {
+        match &mut self.futs {
+            Futs::Ordered(futs) => *futs = FuturesOrdered::new(),
+            Futs::Unordered(groups) => groups.clear(),
+            Futs::Eager(futs) => *futs = FuturesUnordered::new(),
+        }
+        self.parents.clear();
+    }
+
+    /// Adds a future for a child stream to the back of the queue.
+    #[inline]
+    pub fn push_back(&mut self, fut: ChildStreamFut<N, E>) {
+        let depth = fut.depth();
+        let seq = self.seq;
+        self.seq += 1;
+        self.parents.insert(seq, Arc::clone(fut.parent()));
+        let fut = Sequenced { seq, fut };
+        match &mut self.futs {
+            Futs::Ordered(futs) => futs.push_back(fut),
+            Futs::Unordered(groups) => match groups.back_mut() {
+                Some(group) if group.depth == depth => group.futs.push(fut),
+                _ => groups.push_back(Group {
+                    depth,
+                    futs: FuturesUnordered::from_iter([fut]),
+                    ready: VecDeque::new(),
+                }),
+            },
+            Futs::Eager(futs) => futs.push(fut),
+        }
+    }
+
+    /// Polls for the next child stream.
+    ///
+    /// Returns `Poll::Ready(None)` if the queue is empty.
+    #[inline]
+    pub fn poll_next_unpin(&mut self, cx: &mut Context<'_>) -> Poll<Option<ChildStream<N, E>>> {
+        let next = match &mut self.futs {
+            Futs::Ordered(futs) => futs.poll_next_unpin(cx),
+            Futs::Eager(futs) => futs.poll_next_unpin(cx),
+            Futs::Unordered(groups) => {
+                // drive the futures of all groups, so that the expansion of deeper nodes
+                // is not delayed by a slow future of a shallower group
+                for group in groups.iter_mut() {
+                    while let Poll::Ready(Some(next)) = group.futs.poll_next_unpin(cx) {
+                        group.ready.push_back(next);
+                    }
+                }
+                loop {
+                    let Some(group) = groups.front_mut() else {
+                        break Poll::Ready(None);
+                    };
+                    if let Some(next) = group.ready.pop_front() {
+                        break Poll::Ready(Some(next));
+                    }
+                    if !group.futs.is_empty() {
+                        break Poll::Pending;
+                    }
+                    // the shallowest group is done, continue with the next
+                    groups.pop_front();
+                }
+            }
+        };
+        next.map(|next| {
+            next.map(|(seq, stream)| {
+                self.parents.remove(&seq);
+                stream
+            })
+        })
+    }
+}
@@ -0,0 +1,162 @@
+use super::{Bfs, Node, Traverse};
+
+use std::collections::HashSet;
+use std::pin::Pin;
+
+/// Returns the keys of the nodes reachable from the `root` within `max_depth`, including the `root`.
+///
+/// For nodes that are [`Hash`], [`Eq`] and [`Clone`], the key is the node itself,
+/// hence the set contains the reachable nodes. For nodes with a custom [`KeyedNode`]
+/// implementation, the set contains their keys.
+///
+/// The nodes are found using a [`Bfs`], whose visited keys make up the set,
+/// hence nodes are not tracked twice. Only nodes that the traversal does not track,
+/// because of `allow_circles`, [`Node::allow_revisit`] or [`Node::is_terminal`],
+/// are collected separately. Without a maximum depth and with `allow_circles`,
+/// this does not complete for graphs with cycles.
+///
+/// # Errors
+///
+/// Returns the first error of a [`Node::children`] call.
+///
+/// ### Example
+/// ```
+/// use par_dfs::r#async::{reachable_within, AdjacencyNode};
+/// use std::collections::HashMap;
+/// use std::sync::Arc;
+///
+/// let graph = Arc::new(HashMap::from([("a", vec!["b", "c"]), ("b", vec!["d"]), ("d", vec!["e"])]));
+/// let root = AdjacencyNode::new(graph, "a");
+/// let reachable = tokio_test::block_on(reachable_within(root, 2, false)).unwrap();
+/// let mut keys: Vec<_> = reachable.iter().map(|node| *node.key()).collect();
+/// keys.sort_unstable();
+/// assert_eq!(keys, ["a", "b", "c", "d"]);
+/// ```
+///
+/// [`Hash`]: trait@std::hash::Hash
+/// [`Eq`]: trait@std::cmp::Eq
+/// [`Clone`]: trait@std::clone::Clone
+/// [`KeyedNode`]: trait@crate::async::KeyedNode
+/// [`Bfs`]: struct@crate::async::Bfs
+/// [`Node::allow_revisit`]: fn@crate::async::Node::allow_revisit
+/// [`Node::is_terminal`]: fn@crate::async::Node::is_terminal
+/// [`Node::children`]: fn@crate::async::Node::children
+pub async fn reachable_within<N, D>(
+    root: N,
+    max_depth: D,
+    allow_circles: bool,
+) -> Result<HashSet<N::Key>, N::Error>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+    D: Into<Option<usize>>,
+{
+    let mut bfs: Bfs<N> = Bfs::builder(root)
+        .max_depth(max_depth)
+        .allow_circles(allow_circles)
+        .include_root(true)
+        .build();
+    // nodes that are not tracked as visited by the traversal
+    let mut untracked = HashSet::new();
+    while let Some(path) =
+        futures::future::poll_fn(|cx| Pin::new(&mut bfs).poll_next_path(cx)).await
+    {
+        let path = path?;
+        let node = path.node();
+        if allow_circles || node.allow_revisit() || node.is_terminal() {
+            untracked.insert(node.key());
+        }
+    }
+    if allow_circles {
+        return Ok(untracked);
+    }
+    let mut reachable = bfs.into_visited();
+    reachable.extend(untracked);
+    Ok(reachable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::reachable_within;
+    use crate::r#async::{AdjacencyNode, KeyedNode, Node, NodeStream};
+    use anyhow::Result;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reachable_within_dag() -> Result<()> {
+        // diamond, where "d" is reached via both "b" and "c"
+        let graph = Arc::new(HashMap::from([
+            ("a", vec!["b", "c"]),
+            ("b", vec!["d"]),
+            ("c", vec!["d"]),
+            ("d", vec!["e"]),
+        ]));
+        let root = AdjacencyNode::new(graph, "a");
+        for allow_circles in [false, true] {
+            for (max_depth, expected) in [(Some(0), 1), (Some(2), 4), (None, 5)] {
+                let reachable = reachable_within(root.clone(), max_depth, allow_circles).await?;
+                similar_asserts::assert_eq!(reachable.len(), expected);
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reachable_within_cycle() -> Result<()> {
+        // "a" is reachable from itself via "c"
+        let graph = Arc::new(HashMap::from([
+            ("a", vec!["b"]),
+            ("b", vec!["c"]),
+            ("c", vec!["a", "d"]),
+        ]));
+        let root = AdjacencyNode::new(graph, "a");
+        for (max_depth, expected) in [
+            (Some(2), vec!["a", "b", "c"]),
+            (None, vec!["a", "b", "c", "d"]),
+        ] {
+            let reachable = reachable_within(root.clone(), max_depth, false).await?;
+            let mut keys: Vec<_> = reachable.iter().map(|node| *node.key()).collect();
+            keys.sort_unstable();
+            similar_asserts::assert_eq!(keys, expected);
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reachable_within_custom_key() -> Result<()> {
+        /// A node that is identified by its id only.
+        #[derive(Debug)]
+        struct Page {
+            id: u32,
+        }
+
+        impl KeyedNode for Page {
+            type Key = u32;
+
+            fn key(&self) -> Self::Key {
+                self.id
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl Node for Page {
+            type Error = crate::utils::test::Error;
+
+            async fn children(
+                self: Arc<Self>,
+                _depth: usize,
+            ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+                // a cycle of length 3
+                let next = Self {
+                    id: (self.id + 1) % 3,
+                };
+                Ok(Box::pin(futures::stream::iter([Ok(next)])))
+            }
+        }
+
+        let reachable = reachable_within(Page { id: 0 }, None, false).await?;
+        similar_asserts::assert_eq!(reachable, [0, 1, 2].into());
+        Ok(())
+    }
+}
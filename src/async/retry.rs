@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+/// Policy for retrying failed [`Node::children`] calls.
+///
+/// Only the [`Node::children`] call itself is retried,
+/// errors of the items within the returned stream are not.
+/// The delay before each retry starts at `backoff` and doubles with every attempt.
+///
+/// [`Node::children`]: fn@crate::async::Node::children
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    /// The maximum number of retries after the first failed call.
+    pub max_retries: usize,
+    /// The delay before the first retry.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    #[inline]
+    #[must_use]
+    /// Creates a new [`RetryPolicy`].
+    ///
+    /// [`RetryPolicy`]: struct@crate::async::RetryPolicy
+    pub fn new(max_retries: usize, backoff: Duration) -> Self {
+        Self {
+            max_retries,
+            backoff,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the delay before the retry following the failed `attempt`,
+    /// where the first attempt is zero.
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let factor =
+            u32::try_from(attempt).map_or(u32::MAX, |attempt| 2u32.saturating_pow(attempt));
+        self.backoff.saturating_mul(factor)
+    }
+}
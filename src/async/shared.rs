@@ -0,0 +1,47 @@
+use super::{Node, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Stream for the [`shared`] method.
+///
+/// Yields each node as an [`Arc`], which does not require the nodes to be [`Clone`].
+///
+/// [`shared`]: fn@crate::async::Traverse::shared
+/// [`Arc`]: struct@std::sync::Arc
+/// [`Clone`]: trait@std::clone::Clone
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct Shared<S> {
+    #[pin]
+    inner: S,
+}
+
+impl<S> Shared<S> {
+    pub(super) fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for Shared<S>
+where
+    S: Traverse,
+{
+    type Item = Result<Arc<S::Node>, <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project()
+            .inner
+            .poll_next_with_depth(cx)
+            .map(|item| item.map(|item| item.map(|(_, node)| node)))
+    }
+}
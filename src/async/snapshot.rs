@@ -0,0 +1,25 @@
+use super::KeyedNode;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// A resumable snapshot of the state of a [`Bfs`].
+///
+/// Created by [`Bfs::snapshot`] and resumed by [`Bfs::resume`].
+///
+/// [`Bfs`]: struct@crate::async::Bfs
+/// [`Bfs::snapshot`]: fn@crate::async::Bfs::snapshot
+/// [`Bfs::resume`]: fn@crate::async::Bfs::resume
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot<N, V = HashSet<<N as KeyedNode>::Key>>
+where
+    N: KeyedNode,
+{
+    /// Roots that are yet to be yielded.
+    pub(super) roots: Vec<N>,
+    /// Nodes whose children are yet to be yielded, together with their depth.
+    pub(super) frontier: Vec<(usize, N)>,
+    pub(super) visited: V,
+    pub(super) max_depth: Option<usize>,
+    pub(super) allow_circles: bool,
+}
@@ -0,0 +1,265 @@
+use super::{KeyedNode, VisitedSet};
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// A [`VisitedSet`] that keeps up to `capacity` recently seen keys in memory
+/// and spills the remaining keys to an on-disk [`sled`] database.
+///
+/// The keys in memory are a cache of the most recently visited or revisited keys.
+/// Once the cache is full, the least recently seen key is evicted
+/// and written to disk, unless it was read from disk before.
+/// Lookups check the keys in memory before falling back to the database,
+/// hence cycle detection remains exact while the memory used is bounded by `capacity`.
+///
+/// Keys are stored in their [`bincode`] encoding, which must be unique for each key.
+///
+/// # Panics
+///
+/// Since [`VisitedSet`] is infallible, reading from or writing to the database panics
+/// if the database fails, e.g. because the disk is full.
+///
+/// ### Example
+/// ```
+/// use futures::StreamExt;
+/// use par_dfs::r#async::{Bfs, SpillingVisitedSet};
+/// # use par_dfs::r#async::{Node, NodeStream};
+/// #
+/// # #[derive(PartialEq, Eq, Hash, Clone, Debug, serde::Serialize)]
+/// # struct Number(u32);
+/// #
+/// # #[async_trait::async_trait]
+/// # impl Node for Number {
+/// #     type Error = std::convert::Infallible;
+/// #
+/// #     async fn children(
+/// #         self: std::sync::Arc<Self>,
+/// #         _depth: usize
+/// #     ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+/// #         let nodes = [Self(self.0 / 2), Self(self.0 * 2)].into_iter().map(Ok);
+/// #         Ok(Box::pin(futures::stream::iter(nodes)))
+/// #     }
+/// # }
+///
+/// let visited = SpillingVisitedSet::temporary(16).unwrap();
+/// let bfs = Bfs::<Number>::builder(Number(1)).max_depth(8).visited(visited).build();
+/// let count = tokio_test::block_on(bfs.count()).unwrap();
+/// assert_eq!(count, 9);
+/// ```
+///
+/// [`VisitedSet`]: trait@crate::async::VisitedSet
+/// [`sled`]: https://docs.rs/sled
+/// [`bincode`]: https://docs.rs/bincode
+#[cfg_attr(docsrs, doc(cfg(feature = "sled")))]
+#[derive(Debug)]
+pub struct SpillingVisitedSet<K> {
+    capacity: usize,
+    /// The recency of each key in memory, and whether the key is already on disk.
+    hot: HashMap<K, (u64, bool)>,
+    /// The keys in memory by recency, starting with the least recently seen key.
+    order: BTreeMap<u64, K>,
+    next_stamp: u64,
+    db: sled::Db,
+    /// The number of keys in the set, both in memory and on disk.
+    len: usize,
+    key: PhantomData<fn() -> K>,
+}
+
+impl<K> SpillingVisitedSet<K>
+where
+    K: Hash + Eq + Clone + Serialize,
+{
+    /// Creates an empty set backed by the database at `path`,
+    /// which keeps at most `capacity` keys in memory.
+    ///
+    /// Existing entries of the database are cleared.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database can not be opened or cleared.
+    pub fn open<P>(path: P, capacity: usize) -> sled::Result<Self>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let db = sled::open(path)?;
+        db.clear()?;
+        Ok(Self::with_db(db, capacity))
+    }
+
+    /// Creates an empty set backed by a temporary database,
+    /// which keeps at most `capacity` keys in memory.
+    ///
+    /// The database is removed once the set is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database can not be created.
+    pub fn temporary(capacity: usize) -> sled::Result<Self> {
+        let db = sled::Config::new().temporary(true).open()?;
+        Ok(Self::with_db(db, capacity))
+    }
+
+    fn with_db(db: sled::Db, capacity: usize) -> Self {
+        Self {
+            capacity,
+            hot: HashMap::new(),
+            order: BTreeMap::new(),
+            next_stamp: 0,
+            db,
+            len: 0,
+            key: PhantomData,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the maximum number of keys in memory.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of keys in the set, both in memory and on disk.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns `true` if the set contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of keys in memory.
+    pub fn hot_len(&self) -> usize {
+        self.hot.len()
+    }
+
+    fn encode(key: &K) -> Vec<u8> {
+        bincode::serialize(key).expect("serialize visited key")
+    }
+
+    /// Returns `true` if the `key` is in the set.
+    fn contains_key(&self, key: &K) -> bool {
+        self.hot.contains_key(key) || self.contains_on_disk(key)
+    }
+
+    /// Returns `true` if the `key` was spilled to disk.
+    fn contains_on_disk(&self, key: &K) -> bool {
+        self.db
+            .contains_key(Self::encode(key))
+            .expect("read visited key from disk")
+    }
+
+    /// Marks the `key` as the most recently seen key in memory,
+    /// evicting the least recently seen key if the cache is full.
+    fn touch(&mut self, key: K, on_disk: bool) {
+        let stamp = self.next_stamp;
+        self.next_stamp += 1;
+        if let Some((previous, _)) = self.hot.get_mut(&key) {
+            self.order.remove(previous);
+            *previous = stamp;
+            self.order.insert(stamp, key);
+            return;
+        }
+        self.hot.insert(key.clone(), (stamp, on_disk));
+        self.order.insert(stamp, key);
+        if self.hot.len() > self.capacity {
+            self.evict();
+        }
+    }
+
+    /// Removes the least recently seen key from memory,
+    /// writing it to disk unless it is already on disk.
+    fn evict(&mut self) {
+        let Some((_, oldest)) = self.order.pop_first() else {
+            return;
+        };
+        if let Some((_, false)) = self.hot.remove(&oldest) {
+            self.db
+                .insert(Self::encode(&oldest), &[])
+                .expect("write visited key to disk");
+        }
+    }
+}
+
+impl<N> VisitedSet<N> for SpillingVisitedSet<N::Key>
+where
+    N: KeyedNode,
+    N::Key: Clone + Serialize,
+{
+    #[inline]
+    fn contains(&self, node: &N) -> bool {
+        self.contains_key(&node.key())
+    }
+
+    fn insert(&mut self, node: &N) -> bool {
+        let key = node.key();
+        let inserted = !self.hot.contains_key(&key) && !self.contains_on_disk(&key);
+        // revisited keys are cached as well, since they are likely to be seen again
+        self.touch(key, !inserted);
+        if inserted {
+            self.len += 1;
+        }
+        inserted
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpillingVisitedSet;
+    use crate::r#async::VisitedSet;
+    use anyhow::Result;
+
+    #[test]
+    fn test_spilling_visited_set_exceeds_capacity() -> Result<()> {
+        let capacity = 100;
+        let num_keys = 10 * capacity as u64 + 1;
+        let mut visited = SpillingVisitedSet::temporary(capacity)?;
+        for key in 0..num_keys {
+            assert!(VisitedSet::insert(&mut visited, &key));
+            assert!(visited.hot_len() <= capacity);
+        }
+        similar_asserts::assert_eq!(visited.len(), 1001);
+        // both the spilled and the keys in memory are still visited
+        for key in 0..num_keys {
+            assert!(VisitedSet::contains(&visited, &key));
+            assert!(!VisitedSet::insert(&mut visited, &key));
+        }
+        assert!(!VisitedSet::contains(&visited, &num_keys));
+        similar_asserts::assert_eq!(visited.len(), 1001);
+        Ok(())
+    }
+
+    #[test]
+    fn test_spilling_visited_set_caches_recently_seen() -> Result<()> {
+        let mut visited = SpillingVisitedSet::temporary(2)?;
+        for key in 0..4_u64 {
+            assert!(VisitedSet::insert(&mut visited, &key));
+        }
+        // the least recently seen keys were spilled to disk
+        assert!(!visited.hot.contains_key(&0));
+        assert!(visited.hot.contains_key(&3));
+        // revisiting a spilled key brings it back into memory
+        assert!(!VisitedSet::insert(&mut visited, &0));
+        assert!(visited.hot.contains_key(&0));
+        assert!(!visited.hot.contains_key(&2));
+        similar_asserts::assert_eq!(visited.hot_len(), 2);
+        similar_asserts::assert_eq!(visited.len(), 4);
+        for key in 0..4_u64 {
+            assert!(VisitedSet::contains(&visited, &key));
+        }
+        Ok(())
+    }
+}
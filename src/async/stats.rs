@@ -0,0 +1,77 @@
+/// Statistics about the work done by a traversal.
+///
+/// The statistics are updated while the traversal is polled
+/// and do not affect the traversal itself.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The number of nodes that were yielded.
+    pub nodes_emitted: usize,
+    /// The depth of the deepest node that was yielded, if any.
+    pub max_depth_reached: Option<usize>,
+    /// The peak number of pending child streams.
+    pub max_frontier_size: usize,
+    /// The peak number of child streams on the stack.
+    ///
+    /// Always zero for traversals without a stack, such as [`Bfs`].
+    ///
+    /// [`Bfs`]: struct@crate::async::Bfs
+    pub max_stack_depth: usize,
+    /// The number of nodes whose children were requested.
+    ///
+    /// Retried [`Node::children`] calls count only once.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    pub children_calls: usize,
+}
+
+impl Stats {
+    /// Records a yielded node at `depth`.
+    #[inline]
+    pub(super) fn record_emitted(&mut self, depth: usize) {
+        self.nodes_emitted += 1;
+        self.max_depth_reached = self.max_depth_reached.max(Some(depth));
+    }
+
+    /// Records a [`Node::children`] call, which results in a frontier of `frontier_size`.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    #[inline]
+    pub(super) fn record_children_call(&mut self, frontier_size: usize) {
+        self.children_calls += 1;
+        self.max_frontier_size = self.max_frontier_size.max(frontier_size);
+    }
+
+    /// Records a stack of `stack_depth` child streams.
+    #[inline]
+    pub(super) fn record_stack_depth(&mut self, stack_depth: usize) {
+        self.max_stack_depth = self.max_stack_depth.max(stack_depth);
+    }
+}
+
+/// The current state of a traversal, for inspecting a traversal in progress.
+///
+/// Unlike [`Stats`], which accumulate over the entire traversal,
+/// this describes the state at a single point in time.
+///
+/// [`Stats`]: struct@crate::async::Stats
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraversalSnapshot {
+    /// The depth of the next node to be yielded, if any.
+    ///
+    /// This is an estimate, since child streams are lazy.
+    pub depth: Option<usize>,
+    /// The number of pending [`Node::children`] calls.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    pub pending_children: usize,
+    /// The number of child streams on the stack.
+    ///
+    /// Always zero for traversals without a stack, such as [`Bfs`].
+    ///
+    /// [`Bfs`]: struct@crate::async::Bfs
+    pub stack_depth: usize,
+    /// The number of nodes whose children are yet to be yielded.
+    pub frontier_len: usize,
+    /// The number of nodes that were yielded so far.
+    pub nodes_emitted: usize,
+}
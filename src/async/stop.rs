@@ -0,0 +1,71 @@
+#[cfg(any(feature = "cancellation", feature = "tokio"))]
+use std::future::Future;
+#[cfg(any(feature = "cancellation", feature = "tokio"))]
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::Context;
+
+#[cfg(feature = "cancellation")]
+type Cancelled = Pin<Box<tokio_util::sync::WaitForCancellationFutureOwned>>;
+
+#[cfg(feature = "tokio")]
+type Deadline = Pin<Box<tokio::time::Sleep>>;
+
+/// The conditions that stop a traversal regardless of its progress.
+///
+/// Shared by [`Bfs`] and [`Dfs`], such that both check the same conditions
+/// before yielding the next node.
+///
+/// [`Bfs`]: struct@crate::async::Bfs
+/// [`Dfs`]: struct@crate::async::Dfs
+#[derive(Default)]
+pub(super) struct Stop {
+    /// Stops the traversal once set.
+    pub flag: Option<Arc<AtomicBool>>,
+    #[cfg(feature = "cancellation")]
+    pub cancelled: Option<Cancelled>,
+    #[cfg(feature = "tokio")]
+    pub deadline: Option<Deadline>,
+}
+
+impl Stop {
+    /// Returns `true` if any condition may stop the traversal.
+    #[inline]
+    pub fn is_armed(&self) -> bool {
+        let armed = self.flag.is_some();
+        #[cfg(feature = "cancellation")]
+        let armed = armed || self.cancelled.is_some();
+        #[cfg(feature = "tokio")]
+        let armed = armed || self.deadline.is_some();
+        armed
+    }
+
+    /// Returns `true` if the traversal is stopped.
+    ///
+    /// Registers `cx` to be woken once the token is cancelled or the deadline has passed.
+    #[inline]
+    #[cfg_attr(
+        not(any(feature = "cancellation", feature = "tokio")),
+        allow(unused_variables)
+    )]
+    pub fn poll_stopped(&mut self, cx: &mut Context<'_>) -> bool {
+        let stopped = self
+            .flag
+            .as_ref()
+            .is_some_and(|stop| stop.load(Ordering::Acquire));
+        #[cfg(feature = "cancellation")]
+        let stopped = stopped
+            || self
+                .cancelled
+                .as_mut()
+                .is_some_and(|cancelled| cancelled.as_mut().poll(cx).is_ready());
+        #[cfg(feature = "tokio")]
+        let stopped = stopped
+            || self
+                .deadline
+                .as_mut()
+                .is_some_and(|deadline| deadline.as_mut().poll(cx).is_ready());
+        stopped
+    }
+}
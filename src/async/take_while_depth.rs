@@ -0,0 +1,92 @@
+use super::{Node, Path, TraversalError, Traverse};
+
+use futures::stream::{FusedStream, Stream};
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Stream for the [`take_while_depth`] method.
+///
+/// Yields the nodes of a traversal until the first node deeper than a maximum depth.
+///
+/// [`take_while_depth`]: fn@crate::async::Traverse::take_while_depth
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct TakeWhileDepth<S> {
+    #[pin]
+    inner: S,
+    max_depth: usize,
+    /// Whether a node deeper than `max_depth` was seen, after which only `None` is yielded.
+    done: bool,
+}
+
+impl<S> TakeWhileDepth<S> {
+    pub(super) fn new(inner: S, max_depth: usize) -> Self {
+        Self {
+            inner,
+            max_depth,
+            done: false,
+        }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Traverse for TakeWhileDepth<S>
+where
+    S: Traverse,
+{
+    type Node = S::Node;
+
+    fn poll_next_path_with_context(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Path<S::Node>, TraversalError<Arc<S::Node>, <S::Node as Node>::Error>>>>
+    {
+        let this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+        let item = futures::ready!(this.inner.poll_next_path_with_context(cx));
+        match item {
+            Some(Ok(path)) if path.depth() > *this.max_depth => {
+                // stop without polling the traversal again
+                *this.done = true;
+                Poll::Ready(None)
+            }
+            None => {
+                *this.done = true;
+                Poll::Ready(None)
+            }
+            item => Poll::Ready(item),
+        }
+    }
+}
+
+impl<S> Stream for TakeWhileDepth<S>
+where
+    S: Traverse,
+    S::Node: Clone,
+{
+    type Item = Result<S::Node, <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_with_depth(cx)
+            .map(|item| item.map(|item| item.map(|(_, node)| Arc::unwrap_or_clone(node))))
+    }
+}
+
+impl<S> FusedStream for TakeWhileDepth<S>
+where
+    S: Traverse,
+    S::Node: Clone,
+{
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
@@ -0,0 +1,85 @@
+use super::{ByLevel, Node, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Stream for the [`tiebreak`] method.
+///
+/// Yields the nodes of each depth sorted by a comparison function.
+///
+/// [`tiebreak`]: fn@crate::async::Bfs::tiebreak
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct Tiebreak<S, F>
+where
+    S: Traverse,
+{
+    #[pin]
+    levels: ByLevel<S>,
+    cmp: F,
+    /// The sorted nodes of the current level that are yet to be yielded.
+    sorted: VecDeque<S::Node>,
+}
+
+impl<S, F> Tiebreak<S, F>
+where
+    S: Traverse,
+{
+    pub(super) fn new(inner: S, cmp: F) -> Self {
+        Self {
+            levels: ByLevel::new(inner),
+            cmp,
+            sorted: VecDeque::new(),
+        }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    ///
+    /// The nodes of the current level that have not been yielded yet are lost.
+    pub fn into_inner(self) -> S {
+        self.levels.into_inner()
+    }
+}
+
+impl<S, F> std::fmt::Debug for Tiebreak<S, F>
+where
+    S: Traverse + std::fmt::Debug,
+    S::Node: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tiebreak")
+            .field("levels", &self.levels)
+            .field("sorted", &self.sorted)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, F> Stream for Tiebreak<S, F>
+where
+    S: Traverse,
+    S::Node: Clone,
+    F: FnMut(&S::Node, &S::Node) -> Ordering,
+{
+    type Item = Result<S::Node, <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            if let Some(node) = this.sorted.pop_front() {
+                return Poll::Ready(Some(Ok(node)));
+            }
+            match futures::ready!(this.levels.as_mut().poll_next(cx)) {
+                Some(Ok((_, mut nodes))) => {
+                    nodes.sort_by(|a, b| (this.cmp)(a, b));
+                    *this.sorted = nodes.into();
+                }
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
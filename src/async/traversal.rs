@@ -0,0 +1,1012 @@
+use super::{
+    Admission, Admitted, BestFirst, Bfs, DepthControl, Dfs, Expand, Expansion, Node, Origin, Path,
+    Stats, Stop, TraversalError, TraversalSnapshot, Traverse, VisitedSet,
+};
+
+use futures::stream::{FusedStream, Stream};
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// The next node reached by a traversal, or the error of a child stream.
+type Reached<N> = Result<Path<N>, TraversalError<Arc<N>, <N as Node>::Error>>;
+
+/// The nodes whose children are yet to be reached, which decide the order of a [`Traversal`].
+///
+/// The [`Traversal`] decides which of the reached nodes are yielded and expanded,
+/// while the frontier decides when the children of the expanded nodes are requested
+/// and in which order they are reached, e.g. first in, first out for [`Bfs`],
+/// last in, first out for [`Dfs`], and by priority for [`BestFirst`].
+///
+/// [`Bfs`]: struct@crate::async::Bfs
+/// [`Dfs`]: struct@crate::async::Dfs
+/// [`BestFirst`]: struct@crate::async::BestFirst
+pub(super) trait FrontierQueue<N>
+where
+    N: Node,
+{
+    /// Adds the last node of `parent` to be expanded.
+    ///
+    /// The expansion may be deferred by adding `parent` to the deferred nodes of the `state`.
+    fn push<V>(&mut self, parent: &Path<N>, state: &mut State<N, V>);
+
+    /// Polls for the next node reached by the traversal.
+    ///
+    /// The deferred nodes of the `state` are expanded once the frontier has room for them.
+    /// Returns `Poll::Ready(None)` once both the frontier and the deferred nodes are exhausted.
+    fn poll_next<V>(
+        &mut self,
+        cx: &mut Context<'_>,
+        state: &mut State<N, V>,
+    ) -> Poll<Option<Reached<N>>>
+    where
+        V: VisitedSet<N>;
+
+    /// Drops all pending child streams.
+    fn clear(&mut self);
+
+    /// Returns the number of nodes whose children are yet to be reached.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if no more nodes can be reached, apart from the deferred nodes.
+    fn is_empty(&self) -> bool;
+
+    /// Returns the number of pending [`Node::children`] calls.
+    ///
+    /// [`Node::children`]: fn@crate::async::Node::children
+    fn pending_children(&self) -> usize;
+
+    /// Returns the number of child streams on the stack, if any.
+    #[inline]
+    fn stack_depth(&self) -> usize {
+        0
+    }
+
+    /// Returns an estimate of the depth of the next node to be reached, if any.
+    fn peek_depth(&self) -> Option<usize>;
+
+    /// Returns an empty frontier with the same order, for restarting the traversal.
+    #[must_use]
+    fn restart(&self) -> Self;
+}
+
+/// The state of a [`Traversal`] that is independent of its frontier.
+pub(super) struct State<N, V>
+where
+    N: Node,
+{
+    pub expansion: Expansion<N>,
+    /// Roots that are yet to be yielded.
+    pub roots: VecDeque<Arc<Path<N>>>,
+    /// Nodes whose expansion is deferred, e.g. the roots until the first poll,
+    /// or the nodes that wait for room in the frontier.
+    pub deferred: VecDeque<Arc<Path<N>>>,
+    pub max_depth: Option<usize>,
+    /// Updates `max_depth` on every poll, if any.
+    pub depth_control: Option<DepthControl>,
+    /// Decides which nodes are yielded and expanded.
+    pub admission: Admission<N>,
+    pub stats: Stats,
+    /// Errors that were skipped, if errors are skipped.
+    pub skipped_errors: Option<Vec<N::Error>>,
+    /// Stops the traversal regardless of its progress.
+    pub stop: Stop,
+    pub visited: V,
+    /// The nodes the traversal started from, for restarting it.
+    pub origin: Origin<N>,
+    /// Whether the children of a node were not requested because of the maximum depth.
+    pub truncated: bool,
+    /// Whether the traversal completed, after which it only yields `None`.
+    pub done: bool,
+}
+
+/// The traversal shared by [`Bfs`], [`Dfs`] and [`BestFirst`], which only differ in their
+/// [`FrontierQueue`].
+///
+/// [`Bfs`]: struct@crate::async::Bfs
+/// [`Dfs`]: struct@crate::async::Dfs
+/// [`BestFirst`]: struct@crate::async::BestFirst
+pub(super) struct Traversal<N, V, Q>
+where
+    N: Node,
+{
+    pub frontier: Q,
+    pub state: State<N, V>,
+}
+
+impl<N, V, Q> Default for Traversal<N, V, Q>
+where
+    N: Node,
+    V: Default,
+    Q: Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self {
+            frontier: Q::default(),
+            state: State {
+                expansion: Expansion::default(),
+                roots: VecDeque::new(),
+                deferred: VecDeque::new(),
+                max_depth: None,
+                depth_control: None,
+                admission: Admission::default(),
+                stats: Stats::default(),
+                skipped_errors: None,
+                stop: Stop::default(),
+                visited: V::default(),
+                origin: Origin::default(),
+                truncated: false,
+                done: false,
+            },
+        }
+    }
+}
+
+impl<N, V, Q> Traversal<N, V, Q>
+where
+    N: Node + Send + Unpin + 'static,
+    N::Error: Send + 'static,
+    V: VisitedSet<N>,
+    Q: FrontierQueue<N>,
+{
+    /// Creates a new traversal from shared `roots` at `initial_depth`,
+    /// which are only yielded when `emit_roots`.
+    pub fn from_roots<I>(
+        frontier: Q,
+        roots: I,
+        max_depth: Option<usize>,
+        allow_circles: bool,
+        mut visited: V,
+        emit_roots: bool,
+        initial_depth: usize,
+    ) -> Self
+    where
+        I: IntoIterator<Item = Arc<N>>,
+    {
+        // the expansion of the roots is deferred until the first poll,
+        // so that the frontier can still be configured
+        let deferred: VecDeque<_> = roots
+            .into_iter()
+            .filter(|root| allow_circles || root.allow_revisit() || visited.insert(root))
+            .map(|root| Arc::new(Path::detached(root, initial_depth)))
+            .collect();
+        let roots = if emit_roots {
+            deferred.clone()
+        } else {
+            VecDeque::new()
+        };
+        Self::from_parts(frontier, roots, deferred, max_depth, allow_circles, visited)
+    }
+
+    /// Creates a new traversal from the `roots` that are yet to be yielded
+    /// and the `deferred` nodes that are yet to be expanded.
+    pub fn from_parts(
+        frontier: Q,
+        roots: VecDeque<Arc<Path<N>>>,
+        deferred: VecDeque<Arc<Path<N>>>,
+        max_depth: Option<usize>,
+        allow_circles: bool,
+        visited: V,
+    ) -> Self {
+        let origin = Origin {
+            roots: roots.clone(),
+            deferred: deferred.clone(),
+            max_depth,
+        };
+        // the children of these nodes are beyond the maximum depth
+        let mut deferred = deferred;
+        let expandable = deferred.len();
+        deferred.retain(|path| max_depth.is_none_or(|max_depth| path.depth() < max_depth));
+        let truncated = deferred.len() < expandable;
+        Self {
+            frontier,
+            state: State {
+                expansion: Expansion::default(),
+                roots,
+                deferred,
+                max_depth,
+                depth_control: None,
+                admission: Admission {
+                    allow_circles,
+                    ..Admission::default()
+                },
+                stats: Stats::default(),
+                skipped_errors: None,
+                stop: Stop::default(),
+                visited,
+                origin,
+                truncated,
+                done: false,
+            },
+        }
+    }
+
+    /// Creates a fresh traversal from the nodes this traversal started from.
+    pub fn restart(&self) -> Self
+    where
+        V: Default,
+    {
+        let Origin {
+            roots,
+            deferred,
+            max_depth,
+        } = self.state.origin.clone();
+        let allow_circles = self.state.admission.allow_circles;
+        let mut visited = V::default();
+        if !allow_circles {
+            for path in roots.iter().chain(&deferred) {
+                visited.insert(path.node());
+            }
+        }
+        Self::from_parts(
+            self.frontier.restart(),
+            roots,
+            deferred,
+            max_depth,
+            allow_circles,
+            visited,
+        )
+    }
+
+    /// Drops all pending roots and expansions.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.frontier.clear();
+        self.state.roots.clear();
+        self.state.deferred.clear();
+    }
+
+    /// Returns the depth of the next node to be yielded, without polling the traversal.
+    #[inline]
+    pub fn peek_depth(&self) -> Option<usize> {
+        if !self.state.roots.is_empty() {
+            return Some(0);
+        }
+        self.frontier
+            .peek_depth()
+            .or_else(|| self.state.deferred.front().map(|parent| parent.depth() + 1))
+    }
+
+    /// Returns the number of nodes whose children are yet to be yielded.
+    #[inline]
+    pub fn frontier_len(&self) -> usize {
+        self.frontier.len() + self.state.deferred.len()
+    }
+
+    /// Returns the current state of the traversal.
+    #[inline]
+    pub fn debug_snapshot(&self) -> TraversalSnapshot {
+        TraversalSnapshot {
+            depth: self.peek_depth(),
+            pending_children: self.frontier.pending_children(),
+            stack_depth: self.frontier.stack_depth(),
+            frontier_len: self.frontier_len(),
+            nodes_emitted: self.state.stats.nodes_emitted,
+        }
+    }
+
+    /// Returns the bounds on the number of nodes that are yet to be yielded.
+    pub fn size_hint(&self) -> (usize, Option<usize>) {
+        let state = &self.state;
+        if state.roots.is_empty() && state.deferred.is_empty() && self.frontier.is_empty() {
+            return (0, Some(0));
+        }
+        let remaining = state
+            .admission
+            .max_nodes
+            .map(|max_nodes| max_nodes.saturating_sub(state.stats.nodes_emitted));
+
+        // the roots are yielded before anything else, unless stopped or cancelled
+        let lower = if state.stop.is_armed() {
+            0
+        } else {
+            remaining.map_or(state.roots.len(), |remaining| {
+                remaining.min(state.roots.len())
+            })
+        };
+
+        // errors do not count towards the maximum number of nodes, unless they are skipped
+        let upper = remaining.filter(|_| state.skipped_errors.is_some());
+        (lower, upper)
+    }
+
+    /// Polls for the path to the next node to be yielded.
+    pub fn poll_next_path(&mut self, cx: &mut Context<'_>) -> Poll<Option<Reached<N>>> {
+        if self.state.done {
+            return Poll::Ready(None);
+        }
+
+        if let Some(control) = self.state.depth_control.as_ref() {
+            self.state.max_depth = control.get();
+        }
+
+        if self.state.stop.poll_stopped(cx) {
+            // drop all pending child streams
+            self.clear();
+            self.state.done = true;
+            return Poll::Ready(None);
+        }
+
+        let state = &mut self.state;
+        if let Some(root) =
+            state
+                .admission
+                .pop_root(&mut state.roots, &mut state.deferred, &mut state.stats)
+        {
+            return Poll::Ready(Some(Ok(root)));
+        }
+
+        loop {
+            let next = self.frontier.poll_next(cx, &mut self.state);
+            trace!(?next);
+            match next {
+                // stream item is ready but failure success
+                Poll::Ready(Some(Err(err))) => {
+                    if let Some(skipped_errors) = self.state.skipped_errors.as_mut() {
+                        // keep the error and continue with the frontier
+                        skipped_errors.push(err.into_source());
+                        continue;
+                    }
+                    return Poll::Ready(Some(Err(err)));
+                }
+                // stream item is ready and success
+                Poll::Ready(Some(Ok(path))) => {
+                    if let Some(path) = self.admit(path) {
+                        return Poll::Ready(Some(Ok(path)));
+                    }
+                }
+                // the frontier and the deferred nodes are exhausted
+                Poll::Ready(None) => {
+                    self.state.done = true;
+                    return Poll::Ready(None);
+                }
+                // still waiting for the next node
+                Poll::Pending => {
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+
+    /// Expands the last node of `path` if admitted, and returns it if it is yielded.
+    fn admit(&mut self, path: Path<N>) -> Option<Path<N>> {
+        let state = &mut self.state;
+        let admitted = state.admission.admit(
+            &path,
+            &mut state.visited,
+            state.stats.nodes_emitted,
+            state.max_depth,
+        );
+        match admitted.expand() {
+            Expand::Yes => self.frontier.push(&path, state),
+            Expand::Truncated => state.truncated = true,
+            Expand::No => {}
+        }
+        let Admitted::Emit { last, .. } = admitted else {
+            return None;
+        };
+        if last {
+            // this is the last node, drop all pending child streams
+            self.frontier.clear();
+            self.state.deferred.clear();
+        }
+        self.state.stats.record_emitted(path.depth());
+        Some(path)
+    }
+}
+
+/// Implements the configuration, consumers and streams shared by the traversals
+/// built on a [`Traversal`].
+macro_rules! traversal {
+    ($stream:ident) => {
+        impl<N, V> Unpin for $stream<N, V> where N: Node {}
+
+        impl<N, V> $stream<N, V>
+        where
+            N: Node + Send + Unpin + 'static,
+            N::Error: Send + 'static,
+            V: VisitedSet<N>,
+        {
+            #[inline]
+            #[must_use]
+            /// Only yields nodes with a depth of at least `min_depth`.
+            ///
+            /// Nodes above the minimum depth are still traversed (and tracked as visited)
+            /// to reach the deeper nodes, but they are not yielded.
+            /// When `min_depth` exceeds the maximum depth, the stream is empty.
+            pub fn min_depth(mut self, min_depth: usize) -> Self {
+                let state = &mut self.traversal.state;
+                if state
+                    .max_depth
+                    .is_some_and(|max_depth| min_depth > max_depth)
+                {
+                    // no node can be yielded, hence there is no need to traverse at all
+                    self.traversal.frontier.clear();
+                    state.deferred.clear();
+                }
+                if min_depth > 0 {
+                    state.roots.clear();
+                }
+                state.admission.min_depth = min_depth;
+                self
+            }
+
+            #[inline]
+            #[must_use]
+            /// Stops the traversal after `max_nodes` nodes have been yielded.
+            ///
+            /// Only successfully yielded nodes count towards the limit, errors do not.
+            /// The last node is not expanded, so no further [`Node::children`] futures are created.
+            /// Together with `max_depth`, whichever limit is hit first stops the expansion.
+            ///
+            /// [`Node::children`]: fn@crate::async::Node::children
+            pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+                if max_nodes == 0 {
+                    self.traversal.clear();
+                }
+                self.traversal.state.admission.max_nodes = Some(max_nodes);
+                self
+            }
+
+            #[inline]
+            #[must_use]
+            /// Yields but does not expand nodes for which `prune` returns `true`.
+            ///
+            /// Unlike `max_depth`, this allows to skip subtrees depending on their content,
+            /// since [`Node::children`] is not called for pruned nodes.
+            /// The predicate is called with each node and its depth,
+            /// e.g. to skip the contents of `.git` directories while still yielding the directory.
+            /// Pruned nodes are still tracked as visited. The roots are always expanded.
+            ///
+            /// [`Node::children`]: fn@crate::async::Node::children
+            pub fn prune<F>(mut self, prune: F) -> Self
+            where
+                F: Fn(&N, usize) -> bool + Send + Sync + 'static,
+            {
+                self.traversal.state.admission.prune = Some(Box::new(prune));
+                self
+            }
+
+            #[inline]
+            #[must_use]
+            /// Only yields and expands nodes for which `filter` returns `true`.
+            ///
+            /// The predicate is called with each node and its depth.
+            /// Unlike filtering the resulting stream, the entire subtree of a rejected node is skipped,
+            /// since [`Node::children`] is not called for it.
+            /// Rejected nodes are still tracked as visited.
+            /// The roots are filtered as well, hence a rejected root is neither yielded nor expanded.
+            ///
+            /// [`Node::children`]: fn@crate::async::Node::children
+            pub fn filter_nodes<F>(mut self, filter: F) -> Self
+            where
+                F: Fn(&N, usize) -> bool + Send + Sync + 'static,
+            {
+                let state = &mut self.traversal.state;
+                state.roots.retain(|root| filter(root.node(), root.depth()));
+                state
+                    .deferred
+                    .retain(|root| filter(root.node(), root.depth()));
+                state.admission.filter = Some(Box::new(filter));
+                self
+            }
+
+            #[inline]
+            #[must_use]
+            /// Yields nodes every time they are reached, but only expands them the first time.
+            ///
+            /// Visited nodes are tracked even when `allow_circles`, but only decide
+            /// whether a node is expanded, not whether it is yielded.
+            /// This avoids the exponential blowup of traversing every path of a DAG,
+            /// while still yielding a node once per edge it is reached by, e.g. to count paths.
+            pub fn expand_once(mut self, expand_once: bool) -> Self {
+                let state = &mut self.traversal.state;
+                if expand_once && state.admission.allow_circles && !state.admission.expand_once {
+                    // the roots were not tracked when the traversal was created
+                    for root in state.roots.iter().chain(state.deferred.iter()) {
+                        state.visited.insert(root.node());
+                    }
+                }
+                state.admission.expand_once = expand_once;
+                self
+            }
+
+            #[inline]
+            #[must_use]
+            /// Reverses the order in which the children of each node are emitted.
+            ///
+            /// Only the children of each node are reversed, the order in which the nodes
+            /// are expanded is unchanged: a [`Bfs`] still yields the nodes level by level,
+            /// while a [`Dfs`] then visits the rightmost child first, matching a stack-based
+            /// implementation that pushes children in order.
+            ///
+            /// Since child streams are lazy, each child stream is buffered into a [`Vec`]
+            /// before it is reversed, which requires memory proportional to the number of children.
+            /// Child streams are only buffered when enabled.
+            ///
+            /// [`Bfs`]: struct@crate::async::Bfs
+            /// [`Dfs`]: struct@crate::async::Dfs
+            /// [`Vec`]: struct@std::vec::Vec
+            pub fn reverse_children(mut self, reverse_children: bool) -> Self {
+                self.traversal
+                    .state
+                    .expansion
+                    .set_reverse_children(reverse_children);
+                self
+            }
+
+            #[inline]
+            #[must_use]
+            /// Truncates the child stream of each node after `max_children_per_node` items.
+            ///
+            /// A child stream is consumed entirely before the traversal moves on,
+            /// hence a single node with an unbounded child stream stalls the traversal.
+            /// Truncating the child streams bounds the work per node.
+            /// Errors within a child stream count as items.
+            pub fn max_children_per_node(mut self, max_children_per_node: usize) -> Self {
+                self.traversal
+                    .state
+                    .expansion
+                    .set_max_children(max_children_per_node);
+                self
+            }
+
+            #[inline]
+            #[must_use]
+            /// Continues the traversal when an error is encountered.
+            ///
+            /// By default, errors are yielded as `Err` items, and consumers that collect
+            /// into a `Result` abort the entire traversal on the first error.
+            /// When skipping errors, the erroneous subtree is skipped instead,
+            /// and the error is kept so that it can be inspected via [`skipped_errors`]
+            /// once the stream completes.
+            ///
+            #[doc = concat!("[`skipped_errors`]: fn@crate::async::", stringify!($stream), "::skipped_errors")]
+            pub fn skip_errors(mut self, skip_errors: bool) -> Self {
+                self.traversal.state.skipped_errors = skip_errors.then(Vec::new);
+                self
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the errors that were skipped so far.
+            ///
+            /// Always empty unless [`skip_errors`] is enabled.
+            ///
+            #[doc = concat!("[`skip_errors`]: fn@crate::async::", stringify!($stream), "::skip_errors")]
+            pub fn skipped_errors(&self) -> &[N::Error] {
+                self.traversal
+                    .state
+                    .skipped_errors
+                    .as_deref()
+                    .unwrap_or_default()
+            }
+
+            #[inline]
+            /// Takes the errors that were skipped so far, leaving none behind.
+            pub fn take_skipped_errors(&mut self) -> Vec<N::Error> {
+                self.traversal
+                    .state
+                    .skipped_errors
+                    .as_mut()
+                    .map(std::mem::take)
+                    .unwrap_or_default()
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the [`Stats`] of the traversal so far.
+            ///
+            /// [`Stats`]: struct@crate::async::Stats
+            pub fn stats(&self) -> &Stats {
+                &self.traversal.state.stats
+            }
+
+            #[inline]
+            #[must_use]
+            /// Consumes the traversal, returning its final [`Stats`].
+            ///
+            /// [`Stats`]: struct@crate::async::Stats
+            pub fn into_stats(self) -> Stats {
+                self.traversal.state.stats
+            }
+
+            #[must_use]
+            /// Creates a fresh traversal from the nodes this traversal started from.
+            ///
+            /// Pending [`Node::children`] calls can not be cloned, hence this is not a
+            /// [`Clone`] of the current state, but a rewind to the start: the restarted traversal
+            /// has the same roots, maximum depth and `allow_circles`, and an empty set of visited nodes.
+            /// Further configuration, such as `min_depth` or `max_nodes`, is not carried over.
+            ///
+            /// [`Node::children`]: fn@crate::async::Node::children
+            /// [`Clone`]: trait@std::clone::Clone
+            pub fn restart(&self) -> Self
+            where
+                V: Default,
+            {
+                Self {
+                    traversal: self.traversal.restart(),
+                }
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the depth of the deepest node yielded so far, or `None` if no node was yielded.
+            ///
+            /// Unlike the configured maximum depth, this is the depth that was actually reached,
+            /// and remains available after the stream is exhausted.
+            pub fn max_depth_reached(&self) -> Option<usize> {
+                self.traversal.state.stats.max_depth_reached
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns `true` if the maximum depth cut off the traversal so far,
+            /// i.e. the children of a node at the maximum depth were not requested.
+            ///
+            /// Nodes at the maximum depth may not have any children, hence the graph
+            /// may still be fully explored. If this returns `false` once the stream is exhausted,
+            /// a deeper maximum depth would not yield any more nodes.
+            /// Pruned nodes and nodes beyond the maximum number of nodes do not count.
+            pub fn was_truncated(&self) -> bool {
+                self.traversal.state.truncated
+            }
+
+            #[inline]
+            #[must_use]
+            /// Reads the maximum depth from `control` whenever the stream is polled.
+            ///
+            /// Changes take effect for the nodes yielded after the next poll:
+            /// lowering the maximum depth stops the expansion of nodes at or below the new limit,
+            /// but does not retract nodes that were already yielded. Child streams that are
+            /// already in flight still drain, hence their nodes may be deeper than the new limit.
+            /// Raising the maximum depth only affects nodes that are yet to be yielded.
+            /// The maximum depth of `control` replaces the current maximum depth.
+            pub fn depth_control(mut self, control: DepthControl) -> Self {
+                self.traversal.state.max_depth = control.get();
+                self.traversal.state.depth_control = Some(control);
+                self
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the number of nodes in the frontier, i.e. the nodes whose children
+            /// are yet to be yielded, without polling the stream.
+            pub fn frontier_len(&self) -> usize {
+                self.traversal.frontier_len()
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the current state of the traversal as a [`TraversalSnapshot`],
+            /// without polling the stream.
+            ///
+            /// This is cheap enough to be called after every yielded node,
+            /// e.g. to visualize the progress of the traversal.
+            ///
+            /// [`TraversalSnapshot`]: struct@crate::async::TraversalSnapshot
+            pub fn debug_snapshot(&self) -> TraversalSnapshot {
+                self.traversal.debug_snapshot()
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns `true` if the `node` has been visited so far.
+            ///
+            /// When `allow_circles` without [`expand_once`], visited nodes are not tracked
+            /// and this always returns `false`.
+            ///
+            #[doc = concat!("[`expand_once`]: fn@crate::async::", stringify!($stream), "::expand_once")]
+            pub fn is_visited(&self, node: &N) -> bool {
+                let state = &self.traversal.state;
+                state.admission.tracks_visited() && state.visited.contains(node)
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the number of nodes visited so far, as reported by the [`VisitedSet`].
+            ///
+            /// When `allow_circles` without [`expand_once`], visited nodes are not tracked
+            /// and this always returns `0`.
+            /// Use [`stats`] for the number of yielded nodes instead.
+            ///
+            /// [`VisitedSet`]: trait@crate::async::VisitedSet
+            #[doc = concat!("[`stats`]: fn@crate::async::", stringify!($stream), "::stats")]
+            #[doc = concat!("[`expand_once`]: fn@crate::async::", stringify!($stream), "::expand_once")]
+            pub fn visited_count(&self) -> usize {
+                let state = &self.traversal.state;
+                if state.admission.tracks_visited() {
+                    state.visited.len()
+                } else {
+                    0
+                }
+            }
+
+            #[inline]
+            #[must_use]
+            /// Stops the traversal once `stop` is set.
+            ///
+            /// The flag is checked whenever the stream is polled. Once set, all pending
+            /// child streams are dropped and the stream yields `None`.
+            /// Unlike a cancellation token, setting the flag does not wake a pending stream,
+            /// hence it is best set by the consumer between polls.
+            pub fn stop_flag(mut self, stop: Arc<AtomicBool>) -> Self {
+                self.traversal.state.stop.flag = Some(stop);
+                self
+            }
+
+            #[cfg(feature = "cancellation")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "cancellation")))]
+            #[inline]
+            #[must_use]
+            /// Stops the traversal once `token` is cancelled.
+            ///
+            /// When cancelled, all pending child streams are dropped
+            /// and the stream yields `None` on the next poll.
+            pub fn cancellation_token(mut self, token: tokio_util::sync::CancellationToken) -> Self {
+                self.traversal.state.stop.cancelled = Some(Box::pin(token.cancelled_owned()));
+                self
+            }
+
+            #[cfg(feature = "tokio")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+            #[inline]
+            #[must_use]
+            /// Stops the traversal once `deadline` has passed, regardless of its progress.
+            ///
+            /// Like a cancellation, all pending child streams are dropped
+            /// and the stream yields `None` on the next poll. A timer is registered
+            /// for the deadline, hence a pending stream is woken once it has passed,
+            /// even if no [`Node::children`] call completes.
+            ///
+            /// # Panics
+            ///
+            /// Panics if not called within a [`tokio`] runtime with the time driver enabled.
+            ///
+            /// [`Node::children`]: fn@crate::async::Node::children
+            pub fn deadline(mut self, deadline: tokio::time::Instant) -> Self {
+                self.traversal.state.stop.deadline =
+                    Some(Box::pin(tokio::time::sleep_until(deadline)));
+                self
+            }
+
+            #[cfg(feature = "tokio")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+            #[inline]
+            #[must_use]
+            /// Fails [`Node::children`] calls that do not complete within `timeout`.
+            ///
+            /// A timed out call yields a [`TimeoutError`] for the children of that node,
+            /// and the traversal continues with the remaining nodes.
+            /// Requires a [`tokio`] runtime with the time driver enabled.
+            ///
+            /// [`Node::children`]: fn@crate::async::Node::children
+            /// [`TimeoutError`]: struct@crate::async::TimeoutError
+            pub fn per_call_timeout(mut self, timeout: std::time::Duration) -> Self
+            where
+                N::Error: From<super::TimeoutError>,
+            {
+                self.traversal.state.expansion.set_timeout(timeout);
+                self
+            }
+
+            #[cfg(feature = "tokio")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+            #[inline]
+            #[must_use]
+            /// Retries failed [`Node::children`] calls according to the [`RetryPolicy`].
+            ///
+            /// Only the error of the last attempt is yielded.
+            /// When combined with [`per_call_timeout`], the timeout applies to each attempt.
+            ///
+            /// [`Node::children`]: fn@crate::async::Node::children
+            /// [`RetryPolicy`]: struct@crate::async::RetryPolicy
+            #[doc = concat!("[`per_call_timeout`]: fn@crate::async::", stringify!($stream), "::per_call_timeout")]
+            pub fn retry(mut self, retry: super::RetryPolicy) -> Self
+            where
+                N: Sync,
+            {
+                self.traversal.state.expansion.set_retry(retry);
+                self
+            }
+
+            #[inline]
+            #[must_use]
+            /// Converts panics of [`Node::children`] futures into errors.
+            ///
+            /// A panicking call yields a [`PanicError`] for the children of that node,
+            /// and the traversal continues with the remaining nodes.
+            /// The panic is still reported by the panic hook, and the node is not required
+            /// to be [`UnwindSafe`], hence it must not rely on state that a panic may leave inconsistent.
+            ///
+            /// [`Node::children`]: fn@crate::async::Node::children
+            /// [`PanicError`]: struct@crate::async::PanicError
+            /// [`UnwindSafe`]: trait@std::panic::UnwindSafe
+            pub fn catch_panics(mut self) -> Self
+            where
+                N::Error: From<super::PanicError>,
+            {
+                self.traversal.state.expansion.set_catch_panics();
+                self
+            }
+
+            /// Consumes the traversal, counting the yielded nodes.
+            ///
+            /// Unlike collecting the nodes, each node is dropped as soon as it is counted,
+            /// hence only the nodes that are yet to be expanded are kept in memory.
+            /// Since inherent methods take precedence, this shadows [`StreamExt::count`],
+            /// which counts the errors as well.
+            ///
+            /// # Errors
+            ///
+            /// Returns the first error of the traversal.
+            ///
+            /// [`StreamExt::count`]: fn@futures::stream::StreamExt::count
+            pub async fn count(self) -> Result<usize, N::Error> {
+                super::count::count(self).await
+            }
+
+            /// Consumes the traversal, calling `f` with a reference to each node and its depth.
+            ///
+            /// Unlike [`StreamExt::for_each`], which needs to take ownership of each node,
+            /// the nodes are borrowed from the traversal, hence a node that is still shared with
+            /// its pending [`Node::children`] call is never cloned.
+            ///
+            /// # Errors
+            ///
+            /// Returns the first error of the traversal.
+            ///
+            /// [`StreamExt::for_each`]: fn@futures::stream::StreamExt::for_each
+            /// [`Node::children`]: fn@crate::async::Node::children
+            pub async fn for_each_ref<F>(self, f: F) -> Result<(), N::Error>
+            where
+                F: FnMut(&N, usize),
+            {
+                super::for_each::for_each_ref(self, f).await
+            }
+
+            /// Consumes the traversal until the first node matching `predicate`.
+            ///
+            /// Unlike combining [`StreamExt::filter`] and [`StreamExt::next`], which leaves the
+            /// pending [`Node::children`] futures queued in the stream, the traversal is dropped
+            /// as soon as a match is found, hence no further [`Node::children`] futures are polled.
+            ///
+            /// # Errors
+            ///
+            /// Returns the first error of the traversal.
+            ///
+            /// [`StreamExt::filter`]: fn@futures::stream::StreamExt::filter
+            /// [`StreamExt::next`]: fn@futures::stream::StreamExt::next
+            /// [`Node::children`]: fn@crate::async::Node::children
+            pub async fn find<F>(self, predicate: F) -> Result<Option<N>, N::Error>
+            where
+                N: Clone,
+                F: FnMut(&N) -> bool,
+            {
+                super::find::find(self, predicate).await
+            }
+
+            /// Consumes the traversal, collecting the children of each node as they are yielded.
+            ///
+            /// The children of a node are listed in the order they were yielded.
+            /// Nodes without yielded children are omitted, including the nodes whose expansion
+            /// was cut off by the maximum depth, hence a missing key means that no children are known.
+            /// Since visited nodes are not yielded again, a child is only listed for the parent
+            /// it was discovered from, unless `allow_circles`.
+            /// Nodes above the minimum depth are not listed as children, but their children are.
+            ///
+            /// # Errors
+            ///
+            /// Returns the first error of the traversal.
+            pub async fn collect_adjacency(self) -> Result<HashMap<N::Key, Vec<N>>, N::Error>
+            where
+                N: Clone,
+            {
+                super::adjacency::collect_adjacency(self).await
+            }
+
+            #[cfg(feature = "tokio")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+            /// Consumes the traversal, sending each node into the bounded channel `tx`.
+            ///
+            /// A slot of the channel is reserved before the traversal is polled for the next node,
+            /// hence a lagging consumer pauses the traversal, including the expansion of further
+            /// nodes. In contrast, a task that polls the traversal and buffers the nodes itself
+            /// lets it run ahead of the consumer, and the pending [`Node::children`] futures
+            /// are queued for every yielded node, regardless of the consumer's speed.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the receiver is dropped, in which case the traversal is dropped as well.
+            ///
+            /// [`Node::children`]: fn@crate::async::Node::children
+            pub async fn drain_into(
+                self,
+                tx: tokio::sync::mpsc::Sender<Result<N, N::Error>>,
+            ) -> Result<(), tokio::sync::mpsc::error::SendError<()>>
+            where
+                N: Clone,
+            {
+                super::drain::drain_into(self, tx).await
+            }
+        }
+
+        #[cfg(feature = "petgraph")]
+        impl<N, V> $stream<N, V>
+        where
+            N: Node + Send + Clone + Unpin + 'static,
+            N::Error: Send + 'static,
+            V: VisitedSet<N>,
+        {
+            #[cfg_attr(docsrs, doc(cfg(feature = "petgraph")))]
+            /// Consumes the traversal, collecting the explored subgraph into a [`Graph`].
+            ///
+            /// Each yielded node is added once per key, together with the edge from the parent
+            /// it was discovered from. Since visited nodes are not yielded again,
+            /// the graph is a tree rooted at the root node, or a forest for multiple roots,
+            /// unless `allow_circles`, in which case the graph may contain cycles.
+            ///
+            /// # Errors
+            ///
+            /// Returns the first error of the traversal.
+            ///
+            /// [`Graph`]: struct@petgraph::graph::Graph
+            pub async fn collect_graph(self) -> Result<petgraph::graph::Graph<N, ()>, N::Error> {
+                super::graph::collect_graph(self).await
+            }
+        }
+
+        impl<N, V> Traverse for $stream<N, V>
+        where
+            N: Node + Send + Unpin + 'static,
+            N::Error: Send + 'static,
+            V: VisitedSet<N>,
+        {
+            type Node = N;
+
+            fn poll_next_path_with_context(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Option<Reached<N>>> {
+                self.get_mut().traversal.poll_next_path(cx)
+            }
+        }
+
+        impl<N, V> Stream for $stream<N, V>
+        where
+            N: Node + Send + Clone + Unpin + 'static,
+            N::Error: Send + 'static,
+            V: VisitedSet<N>,
+        {
+            type Item = Result<N, N::Error>;
+
+            fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+                self.poll_next_with_depth(cx)
+                    .map(|item| item.map(|item| item.map(|(_, node)| Arc::unwrap_or_clone(node))))
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.traversal.size_hint()
+            }
+        }
+
+        impl<N, V> FusedStream for $stream<N, V>
+        where
+            N: Node + Send + Clone + Unpin + 'static,
+            N::Error: Send + 'static,
+            V: VisitedSet<N>,
+        {
+            fn is_terminated(&self) -> bool {
+                self.traversal.state.done
+            }
+        }
+    };
+}
+
+traversal!(Bfs);
+traversal!(Dfs);
+traversal!(BestFirst);
@@ -0,0 +1,123 @@
+use super::{Node, Traverse};
+
+use std::fmt::Display;
+
+/// Consumes the pre-order `traversal`, formatting its nodes as an indented tree.
+///
+/// Nodes at depth zero are formatted without a connector,
+/// all deeper nodes are connected to their parent at the previous depth.
+pub(super) async fn fmt_tree<T>(traversal: T) -> Result<String, <T::Node as Node>::Error>
+where
+    T: Traverse,
+    T::Node: Display,
+{
+    let mut nodes = vec![];
+    let mut traversal = std::pin::pin!(traversal);
+    while let Some(node) =
+        futures::future::poll_fn(|cx| traversal.as_mut().poll_next_with_depth(cx)).await
+    {
+        let (depth, node) = node?;
+        nodes.push((depth, node.to_string()));
+    }
+
+    // a node is the last of its siblings unless another node follows
+    // at the same depth before the traversal returns to a lower depth
+    let mut is_last = vec![false; nodes.len()];
+    let mut has_next_sibling: Vec<bool> = vec![];
+    for (idx, (depth, _)) in nodes.iter().enumerate().rev() {
+        has_next_sibling.resize(depth + 1, false);
+        is_last[idx] = !has_next_sibling[*depth];
+        has_next_sibling[*depth] = true;
+    }
+
+    let mut tree = String::new();
+    // whether the ancestor at each depth has a sibling below
+    let mut open: Vec<bool> = vec![];
+    for ((depth, label), is_last) in nodes.iter().zip(is_last) {
+        open.resize(*depth, false);
+        for ancestor_open in open.iter().skip(1) {
+            tree.push_str(if *ancestor_open { "│  " } else { "   " });
+        }
+        if *depth > 0 {
+            tree.push_str(if is_last { "└─ " } else { "├─ " });
+        }
+        tree.push_str(label);
+        tree.push('\n');
+        open.push(!is_last);
+    }
+    Ok(tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::r#async::{Dfs, Node, NodeStream};
+    use anyhow::Result;
+    use std::sync::Arc;
+
+    #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    struct Label(&'static str);
+
+    impl std::fmt::Display for Label {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Node for Label {
+        type Error = std::convert::Infallible;
+
+        async fn children(
+            self: Arc<Self>,
+            _depth: usize,
+        ) -> Result<NodeStream<Self, Self::Error>, Self::Error> {
+            let children = match self.0 {
+                "src" => vec!["async", "sync", "lib.rs"],
+                "async" => vec!["bfs.rs", "dfs.rs"],
+                "sync" => vec!["par.rs"],
+                _ => vec![],
+            };
+            let nodes = children.into_iter().map(|label| Ok(Self(label)));
+            Ok(Box::pin(futures::stream::iter(nodes)))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fmt_tree() -> Result<()> {
+        let dfs = Dfs::<Label>::builder(Label("src"))
+            .include_root(true)
+            .build();
+        let tree = dfs.fmt_tree().await?;
+        similar_asserts::assert_eq!(
+            tree,
+            "\
+src
+├─ async
+│  ├─ bfs.rs
+│  └─ dfs.rs
+├─ sync
+│  └─ par.rs
+└─ lib.rs
+"
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fmt_tree_without_root() -> Result<()> {
+        let dfs = Dfs::<Label>::new(Label("src"), None, false);
+        let tree = dfs.fmt_tree().await?;
+        similar_asserts::assert_eq!(
+            tree,
+            "\
+├─ async
+│  ├─ bfs.rs
+│  └─ dfs.rs
+├─ sync
+│  └─ par.rs
+└─ lib.rs
+"
+        );
+        Ok(())
+    }
+}
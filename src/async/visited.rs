@@ -0,0 +1,188 @@
+use super::KeyedNode;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use std::hash::{BuildHasher, Hash};
+
+/// A set of visited nodes, used to detect cycles during a traversal.
+///
+/// The default implementation is a [`HashSet`] of the [`KeyedNode::Key`]s of the nodes, which is exact.
+/// Custom implementations can trade exactness for memory, e.g. using a Bloom filter,
+/// or store the visited nodes outside of memory, e.g. in a disk-backed set.
+///
+/// An approximate set affects the correctness of the traversal:
+/// - false positives, i.e. nodes that are reported as visited even though they are not,
+///   are skipped together with their entire subtree.
+/// - false negatives, i.e. visited nodes that are reported as not visited,
+///   are yielded and expanded again, which can lead to cycles.
+///
+/// [`HashSet`]: struct@std::collections::HashSet
+/// [`KeyedNode::Key`]: type@crate::async::KeyedNode::Key
+pub trait VisitedSet<N> {
+    /// Returns `true` if the `node` has been visited.
+    fn contains(&self, node: &N) -> bool;
+
+    /// Marks the `node` as visited.
+    ///
+    /// Returns `true` if the `node` has not been visited before.
+    fn insert(&mut self, node: &N) -> bool;
+
+    /// Returns the number of visited nodes in the set.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if no nodes have been visited.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<N, S> VisitedSet<N> for HashSet<N::Key, S>
+where
+    N: KeyedNode,
+    S: BuildHasher,
+{
+    #[inline]
+    fn contains(&self, node: &N) -> bool {
+        HashSet::contains(self, &node.key())
+    }
+
+    #[inline]
+    fn insert(&mut self, node: &N) -> bool {
+        HashSet::insert(self, node.key())
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+}
+
+/// A [`VisitedSet`] that holds at most `capacity` keys,
+/// evicting the least recently seen key when full.
+///
+/// This bounds the memory used for cycle detection on very large graphs,
+/// but makes cycle detection best-effort: a visited node whose key was evicted
+/// is yielded and expanded again when it is reached another time.
+/// With a capacity of zero, no nodes are tracked at all.
+///
+/// [`VisitedSet`]: trait@crate::async::VisitedSet
+#[derive(Debug, Clone)]
+pub struct LruVisitedSet<K> {
+    capacity: usize,
+    /// The recency of each key.
+    stamps: HashMap<K, u64>,
+    /// The keys by recency, starting with the least recently seen key.
+    order: BTreeMap<u64, K>,
+    next_stamp: u64,
+}
+
+impl<K> LruVisitedSet<K>
+where
+    K: Hash + Eq + Clone,
+{
+    #[inline]
+    #[must_use]
+    /// Creates an empty set that holds at most `capacity` keys.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            stamps: HashMap::new(),
+            order: BTreeMap::new(),
+            next_stamp: 0,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the maximum number of keys in the set.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of keys in the set.
+    pub fn len(&self) -> usize {
+        self.stamps.len()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns `true` if the set contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.stamps.is_empty()
+    }
+
+    /// Marks the `key` as the most recently seen key.
+    ///
+    /// Returns `true` if the `key` was not in the set.
+    fn touch(&mut self, key: K) -> bool {
+        if self.capacity == 0 {
+            return true;
+        }
+        let stamp = self.next_stamp;
+        self.next_stamp += 1;
+        if let Some(previous) = self.stamps.insert(key.clone(), stamp) {
+            self.order.remove(&previous);
+            self.order.insert(stamp, key);
+            return false;
+        }
+        self.order.insert(stamp, key);
+        if self.stamps.len() > self.capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.stamps.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+impl<N> VisitedSet<N> for LruVisitedSet<N::Key>
+where
+    N: KeyedNode,
+    N::Key: Clone,
+{
+    #[inline]
+    fn contains(&self, node: &N) -> bool {
+        self.stamps.contains_key(&node.key())
+    }
+
+    #[inline]
+    fn insert(&mut self, node: &N) -> bool {
+        self.touch(node.key())
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.stamps.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LruVisitedSet, VisitedSet};
+
+    #[test]
+    fn test_lru_visited_set_evicts_least_recently_seen() {
+        let mut visited = LruVisitedSet::new(2);
+        assert!(VisitedSet::insert(&mut visited, &1));
+        assert!(VisitedSet::insert(&mut visited, &2));
+        // seeing 1 again makes 2 the least recently seen
+        assert!(!VisitedSet::insert(&mut visited, &1));
+        assert!(VisitedSet::insert(&mut visited, &3));
+        similar_asserts::assert_eq!(visited.len(), 2);
+        assert!(VisitedSet::contains(&visited, &1));
+        assert!(!VisitedSet::contains(&visited, &2));
+        assert!(VisitedSet::contains(&visited, &3));
+        // the evicted key is treated as not visited
+        assert!(VisitedSet::insert(&mut visited, &2));
+    }
+
+    #[test]
+    fn test_lru_visited_set_zero_capacity() {
+        let mut visited = LruVisitedSet::new(0);
+        assert!(VisitedSet::insert(&mut visited, &1));
+        assert!(VisitedSet::insert(&mut visited, &1));
+        assert!(visited.is_empty());
+    }
+}
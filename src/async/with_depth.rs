@@ -0,0 +1,45 @@
+use super::{Node, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Stream for the [`with_depth`] method.
+///
+/// Yields each node together with its depth.
+///
+/// [`with_depth`]: fn@crate::async::Traverse::with_depth
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct WithDepth<S> {
+    #[pin]
+    inner: S,
+}
+
+impl<S> WithDepth<S> {
+    pub(super) fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for WithDepth<S>
+where
+    S: Traverse,
+    S::Node: Clone,
+{
+    type Item = Result<(usize, S::Node), <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().inner.poll_next_with_depth(cx).map(|item| {
+            item.map(|item| item.map(|(depth, node)| (depth, Arc::unwrap_or_clone(node))))
+        })
+    }
+}
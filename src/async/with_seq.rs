@@ -0,0 +1,55 @@
+use super::{Node, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Stream for the [`with_seq`] method.
+///
+/// Yields each node together with its sequence number.
+///
+/// [`with_seq`]: fn@crate::async::Traverse::with_seq
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct WithSeq<S> {
+    #[pin]
+    inner: S,
+    /// The sequence number of the next yielded node.
+    seq: u64,
+}
+
+impl<S> WithSeq<S> {
+    pub(super) fn new(inner: S) -> Self {
+        Self { inner, seq: 0 }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for WithSeq<S>
+where
+    S: Traverse,
+    S::Node: Clone,
+{
+    type Item = Result<(u64, S::Node), <S::Node as Node>::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let seq = this.seq;
+        this.inner.poll_next_with_depth(cx).map(|item| {
+            item.map(|item| {
+                item.map(|(_, node)| {
+                    let node = (*seq, Arc::unwrap_or_clone(node));
+                    *seq += 1;
+                    node
+                })
+            })
+        })
+    }
+}
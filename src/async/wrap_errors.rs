@@ -0,0 +1,52 @@
+use super::{Node, TraversalError, Traverse};
+
+use futures::stream::Stream;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Stream for the [`wrap_errors`] method.
+///
+/// Yields each error as a [`TraversalError`] with the node whose children failed.
+///
+/// [`wrap_errors`]: fn@crate::async::Traverse::wrap_errors
+/// [`TraversalError`]: struct@crate::async::TraversalError
+#[derive(Debug)]
+#[pin_project]
+#[must_use = "streams do nothing unless polled"]
+pub struct WrapErrors<S> {
+    #[pin]
+    inner: S,
+}
+
+impl<S> WrapErrors<S> {
+    pub(super) fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Consumes this adapter, returning the underlying traversal.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for WrapErrors<S>
+where
+    S: Traverse,
+    S::Node: Clone,
+{
+    type Item = Result<S::Node, TraversalError<S::Node, <S::Node as Node>::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project()
+            .inner
+            .poll_next_path_with_context(cx)
+            .map(|item| {
+                item.map(|item| match item {
+                    Ok(path) => Ok(Arc::unwrap_or_clone(path.into_node())),
+                    Err(err) => Err(err.map_node(Arc::unwrap_or_clone)),
+                })
+            })
+    }
+}
@@ -179,8 +179,11 @@ where
         let mut queue = queue::Queue::new(allow_circles);
         let root: N = root.into();
         let max_depth = max_depth.into();
-        let mut depth_queue = queue::QueueWrapper::new(0, &mut queue);
-        depth_queue.add(Ok(root));
+        let depth = 1;
+        let mut depth_queue = queue::QueueWrapper::new(depth, &mut queue);
+        if let Err(err) = root.add_children(depth, &mut depth_queue) {
+            depth_queue.add(Err(err));
+        }
         Self { queue, max_depth }
     }
 }
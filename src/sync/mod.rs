@@ -1,3 +1,15 @@
+//! Synchronous traversal iterators.
+//!
+//! [`Bfs`] and [`Dfs`] mirror the streams of the `async` module:
+//! they are created with the same `new(root, max_depth, allow_circles)` signature,
+//! track visited nodes with the same cycle semantics, and yield `Result<N, N::Error>`.
+//! Unlike the async `Node`, the synchronous [`Node::children`] borrows the node
+//! and nodes are tracked as visited by their [`Hash`] and [`Eq`] implementation.
+//!
+//! [`Node::children`]: fn@crate::sync::Node::children
+//! [`Hash`]: trait@std::hash::Hash
+//! [`Eq`]: trait@std::cmp::Eq
+
 pub mod bfs;
 pub mod dfs;
 #[cfg(feature = "rayon")]
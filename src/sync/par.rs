@@ -7,6 +7,23 @@
 //! [`ParallelSplittableIterator`]
 //! implements [`rayon::iter::ParallelIterator`].
 //!
+//! The [`Bfs`] and [`Dfs`] iterators implement [`rayon::iter::IntoParallelIterator`]
+//! by splitting off half of their queue whenever a thread runs out of work,
+//! so that the children of many queued nodes are expanded concurrently.
+//! The visited nodes are shared between all splits, hence each node is still
+//! yielded at most once unless `allow_circles` is set.
+//!
+//! ### Ordering
+//!
+//! Parallel iteration does not preserve the order of the serial iterators:
+//! - each split yields its nodes in breadth-first or depth-first order,
+//!   but the splits run concurrently, so nodes of different depths are interleaved.
+//! - when a node is reachable on several paths, which path reaches it first,
+//!   and hence the depth it is yielded with, depends on scheduling.
+//!
+//! Use an order-preserving adapter such as [`collect`] followed by sorting
+//! when a stable output is required.
+//!
 //! ### Acknowledgements
 //!
 //! This approach is taken from the amazing [blog post by tavianator](https://tavianator.com/2022/parallel_graph_search.html).
@@ -15,6 +32,10 @@
 //! [`ParallelSplittableIterator`]: struct@self::ParallelSplittableIterator
 //! [`SplittableIterator`]: trait@self::SplittableIterator
 //! [`rayon::iter::ParallelIterator`]: trait@rayon::iter::ParallelIterator
+//! [`rayon::iter::IntoParallelIterator`]: trait@rayon::iter::IntoParallelIterator
+//! [`Bfs`]: struct@crate::sync::Bfs
+//! [`Dfs`]: struct@crate::sync::Dfs
+//! [`collect`]: fn@rayon::iter::ParallelIterator::collect
 
 use rayon::iter::plumbing::{Folder, Reducer, UnindexedConsumer};
 use rayon::iter::ParallelIterator;
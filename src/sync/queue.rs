@@ -20,11 +20,10 @@ where
     I: Hash + Eq + Clone,
 {
     if visited.read().unwrap().contains(item) {
-        false
-    } else {
-        visited.write().unwrap().insert(item.clone());
-        true
+        return false;
     }
+    // another thread may have inserted the item in the meantime
+    visited.write().unwrap().insert(item.clone())
 }
 
 #[cfg(not(feature = "rayon"))]
@@ -26,7 +26,7 @@ pub mod test {
     #[error("error")]
     pub struct Error;
 
-    #[derive(PartialEq, Eq, Hash, Clone, Debug)]
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
     pub struct Node(pub usize);
 
     impl From<usize> for Node {
@@ -38,7 +38,7 @@ pub mod test {
     #[cfg(feature = "async")]
     #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
     pub mod r#async {
-        use crate::r#async::{Node, NodeStream};
+        use crate::r#async::{Node, NodeStream, VisitedSet};
         use async_trait::async_trait;
         use futures::{stream, StreamExt};
         use std::sync::Arc;
@@ -65,6 +65,43 @@ pub mod test {
                 Ok(Box::pin(stream.boxed()))
             }
         }
+
+        /// A visited set that reports every node as visited,
+        /// like a saturated Bloom filter.
+        #[derive(Default)]
+        pub struct AllVisited;
+
+        impl<N> VisitedSet<N> for AllVisited {
+            fn contains(&self, _node: &N) -> bool {
+                true
+            }
+
+            fn insert(&mut self, _node: &N) -> bool {
+                false
+            }
+
+            fn len(&self) -> usize {
+                0
+            }
+        }
+
+        /// A visited set that never reports a node as visited.
+        #[derive(Default)]
+        pub struct NoneVisited;
+
+        impl<N> VisitedSet<N> for NoneVisited {
+            fn contains(&self, _node: &N) -> bool {
+                false
+            }
+
+            fn insert(&mut self, _node: &N) -> bool {
+                true
+            }
+
+            fn len(&self) -> usize {
+                0
+            }
+        }
     }
 
     #[cfg(feature = "sync")]